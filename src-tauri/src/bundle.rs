@@ -0,0 +1,1038 @@
+//! Build order bundles: a whole library (builds, their tags and collections)
+//! shared as one `.aoe4bundle` zip archive.
+//!
+//! Layout inside the archive:
+//!
+//! ```text
+//! manifest.json        BundleManifest (format version, content version, hashes)
+//! collections.json     Vec<BuildOrderCollection>, optional
+//! builds/<id>.json     one build order per file, personal data stripped
+//! assets/<id>/<file>    step attachment images referenced by that build
+//! ```
+//!
+//! Nothing in an archive is ever extracted by its own path. Entry names are
+//! checked against the layout above, every build goes through the same parsing
+//! and validation as `import_build_order`, and files are written to the library
+//! under their validated id only.
+
+use crate::config::{
+    parse_build_order_json, referenced_attachments, retain_attachments, sha256_hex,
+    strip_user_data, validate_attachment_file_name, validate_attachment_image,
+    validate_build_order_id, BuildOrder, BuildOrderCollection, CollectionStore,
+    MAX_ATTACHMENT_SIZE,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::io::{Read, Seek, Write};
+use std::time::{SystemTime, UNIX_EPOCH};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+/// Version 2 added `assets/`; version 1 bundles are still read.
+pub const BUNDLE_FORMAT_VERSION: u32 = 2;
+/// Largest archive accepted for import.
+pub const MAX_BUNDLE_SIZE: u64 = 32 * 1024 * 1024; // 32MB limit
+pub const MAX_BUNDLE_ENTRIES: usize = 1000;
+/// Per-entry limit on uncompressed size of JSON entries, matching a single
+/// build import. Images are held to `MAX_ATTACHMENT_SIZE` instead.
+pub const MAX_BUNDLE_ENTRY_SIZE: u64 = 1024 * 1024; // 1MB limit
+
+const MANIFEST_PATH: &str = "manifest.json";
+const COLLECTIONS_PATH: &str = "collections.json";
+const BUILDS_DIR: &str = "builds/";
+const ASSETS_DIR: &str = "assets/";
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleManifest {
+    pub format_version: u32,
+    /// App version that wrote the bundle, for diagnostics only.
+    #[serde(default)]
+    pub app_version: String,
+    /// `assetContentVersion` of the exporting app (game patch the builds target).
+    #[serde(default)]
+    pub content_version: Option<String>,
+    /// Unix epoch milliseconds.
+    #[serde(default)]
+    pub created_at: u64,
+    pub builds: Vec<BundleBuildEntry>,
+    #[serde(default)]
+    pub collections: Option<BundleFileEntry>,
+    #[serde(default)]
+    pub assets: Vec<BundleAssetEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleAssetEntry {
+    /// Bundle id of the build the image belongs to.
+    pub build_id: String,
+    pub file_name: String,
+    pub path: String,
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleBuildEntry {
+    pub id: String,
+    pub name: String,
+    pub path: String,
+    /// Lowercase hex SHA-256 of the entry's bytes.
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleFileEntry {
+    pub path: String,
+    pub sha256: String,
+}
+
+/// A bundle read into memory. Builds that failed hashing or validation keep
+/// their error so one bad entry doesn't reject the whole archive.
+#[derive(Debug, Clone)]
+pub struct BundleContents {
+    pub manifest: BundleManifest,
+    pub builds: Vec<(BundleBuildEntry, Result<BuildOrder, String>)>,
+    pub collections: Vec<BuildOrderCollection>,
+    /// Verified images keyed by bundle build id, then file name.
+    pub assets: HashMap<String, HashMap<String, Vec<u8>>>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConflictResolution {
+    /// Keep the library's build and ignore the bundled one.
+    #[default]
+    Skip,
+    /// Overwrite the library's build with the bundled one.
+    Replace,
+    /// Import the bundled build under a new id.
+    KeepBoth,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BundleEntryStatus {
+    New,
+    /// Same id and same content as a library build; importing is a no-op.
+    Identical,
+    /// Same id, different content; needs a `ConflictResolution`.
+    Conflict,
+    Invalid,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleEntryPreview {
+    pub id: String,
+    pub name: String,
+    pub status: BundleEntryStatus,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundlePreview {
+    pub format_version: u32,
+    pub app_version: String,
+    pub content_version: Option<String>,
+    /// The bundle targets a different game patch than this install.
+    pub content_version_mismatch: bool,
+    pub builds: Vec<BundleEntryPreview>,
+    pub collections: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BundleImportOptions {
+    /// Applied to conflicting builds without an entry in `resolutions`.
+    pub default_resolution: ConflictResolution,
+    /// Per-build overrides keyed by the id inside the bundle.
+    pub resolutions: HashMap<String, ConflictResolution>,
+    pub include_collections: bool,
+}
+
+impl Default for BundleImportOptions {
+    fn default() -> Self {
+        Self {
+            default_resolution: ConflictResolution::Skip,
+            resolutions: HashMap::new(),
+            include_collections: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleRename {
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleEntryError {
+    pub id: String,
+    pub error: String,
+}
+
+#[derive(Debug, Default, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleImportReport {
+    pub imported: Vec<String>,
+    pub replaced: Vec<String>,
+    pub renamed: Vec<BundleRename>,
+    pub skipped: Vec<String>,
+    pub failed: Vec<BundleEntryError>,
+    /// Ids of collections created or extended by the import.
+    pub collections: Vec<String>,
+}
+
+/// What an import would write, computed without touching disk.
+#[derive(Debug, Clone)]
+pub struct BundleImportPlan {
+    /// (bundle id, build to write carrying its final id)
+    pub builds: Vec<(String, BuildOrder)>,
+    /// Bundle id -> library id for every build that ends up in the library.
+    pub id_map: HashMap<String, String>,
+    pub report: BundleImportReport,
+}
+
+fn build_entry_path(id: &str) -> String {
+    format!("{}{}.json", BUILDS_DIR, id)
+}
+
+fn asset_entry_path(build_id: &str, file_name: &str) -> String {
+    format!("{}{}/{}", ASSETS_DIR, build_id, file_name)
+}
+
+/// Rejects entry names that could escape a target directory or that don't fit
+/// the bundle layout. Zip entries always use `/`, so `\`, absolute paths, drive
+/// prefixes and `..`/`.` segments are all refused outright.
+pub fn validate_entry_path(name: &str) -> Result<(), String> {
+    let unsafe_name = name.is_empty()
+        || name.starts_with('/')
+        || name.contains('\\')
+        || name.contains(':')
+        || name.contains('\0')
+        || name
+            .split('/')
+            .any(|segment| segment.is_empty() || segment == "." || segment == "..");
+    if unsafe_name {
+        return Err(format!("Unsafe path in bundle: \"{}\"", name));
+    }
+
+    if name == MANIFEST_PATH || name == COLLECTIONS_PATH {
+        return Ok(());
+    }
+    if let Some(id) = name
+        .strip_prefix(BUILDS_DIR)
+        .and_then(|file| file.strip_suffix(".json"))
+    {
+        return validate_build_order_id(id)
+            .map_err(|e| format!("Invalid build entry \"{}\": {}", name, e));
+    }
+    if let Some((id, file_name)) = name
+        .strip_prefix(ASSETS_DIR)
+        .and_then(|rest| rest.split_once('/'))
+    {
+        return validate_build_order_id(id)
+            .and_then(|_| validate_attachment_file_name(file_name))
+            .map_err(|e| format!("Invalid asset entry \"{}\": {}", name, e));
+    }
+    Err(format!("Unexpected file in bundle: \"{}\"", name))
+}
+
+/// Writes `builds` (personal data stripped), their step images and the
+/// `collections` that refer to them into a new archive. Collection members
+/// outside `builds` are dropped. `load_asset(build_id, file_name)` supplies
+/// image bytes; images it can't find are left out.
+pub fn write_bundle<W, F>(
+    writer: W,
+    builds: &[BuildOrder],
+    collections: &[BuildOrderCollection],
+    content_version: Option<String>,
+    load_asset: F,
+) -> Result<BundleManifest, String>
+where
+    W: Write + Seek,
+    F: Fn(&str, &str) -> Option<Vec<u8>>,
+{
+    let mut zip = ZipWriter::new(writer);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    let mut add = |path: &str, bytes: &[u8]| -> Result<String, String> {
+        zip.start_file(path, options).map_err(|e| e.to_string())?;
+        zip.write_all(bytes).map_err(|e| e.to_string())?;
+        Ok(sha256_hex(bytes))
+    };
+
+    let mut entries = Vec::with_capacity(builds.len());
+    let mut assets = Vec::new();
+    for order in builds {
+        validate_build_order_id(&order.id)?;
+        let path = build_entry_path(&order.id);
+        let json = serde_json::to_vec_pretty(&strip_user_data(order)).map_err(|e| e.to_string())?;
+        let sha256 = add(&path, &json)?;
+        entries.push(BundleBuildEntry {
+            id: order.id.clone(),
+            name: order.name.clone(),
+            path,
+            sha256,
+        });
+
+        for file_name in referenced_attachments(order) {
+            let Some(bytes) = load_asset(&order.id, &file_name) else {
+                continue;
+            };
+            let path = asset_entry_path(&order.id, &file_name);
+            let sha256 = add(&path, &bytes)?;
+            assets.push(BundleAssetEntry {
+                build_id: order.id.clone(),
+                file_name,
+                path,
+                sha256,
+            });
+        }
+    }
+
+    let exported: HashSet<&str> = builds.iter().map(|o| o.id.as_str()).collect();
+    let bundled_collections: Vec<BuildOrderCollection> = collections
+        .iter()
+        .map(|c| BuildOrderCollection {
+            build_order_ids: c
+                .build_order_ids
+                .iter()
+                .filter(|id| exported.contains(id.as_str()))
+                .cloned()
+                .collect(),
+            ..c.clone()
+        })
+        .filter(|c| !c.build_order_ids.is_empty())
+        .collect();
+    let collections_entry = if bundled_collections.is_empty() {
+        None
+    } else {
+        let json = serde_json::to_vec_pretty(&bundled_collections).map_err(|e| e.to_string())?;
+        Some(BundleFileEntry {
+            path: COLLECTIONS_PATH.to_string(),
+            sha256: add(COLLECTIONS_PATH, &json)?,
+        })
+    };
+
+    let manifest = BundleManifest {
+        format_version: BUNDLE_FORMAT_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        content_version,
+        created_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or_default(),
+        builds: entries,
+        collections: collections_entry,
+        assets,
+    };
+    let json = serde_json::to_vec_pretty(&manifest).map_err(|e| e.to_string())?;
+    add(MANIFEST_PATH, &json)?;
+
+    zip.finish().map_err(|e| e.to_string())?;
+    Ok(manifest)
+}
+
+/// Reads one entry, refusing anything larger than `limit` once decompressed
+/// regardless of what the archive header claims.
+fn read_entry<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+    path: &str,
+    limit: u64,
+) -> Result<Vec<u8>, String> {
+    let file = archive
+        .by_name(path)
+        .map_err(|_| format!("Missing file in bundle: \"{}\"", path))?;
+    let mut bytes = Vec::new();
+    file.take(limit + 1)
+        .read_to_end(&mut bytes)
+        .map_err(|e| format!("Failed to read \"{}\": {}", path, e))?;
+    if bytes.len() as u64 > limit {
+        return Err(format!("\"{}\" is too large (max {} bytes)", path, limit));
+    }
+    Ok(bytes)
+}
+
+fn read_verified<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+    path: &str,
+    sha256: &str,
+    limit: u64,
+) -> Result<Vec<u8>, String> {
+    let bytes = read_entry(archive, path, limit)?;
+    if !sha256_hex(&bytes).eq_ignore_ascii_case(sha256) {
+        return Err(format!("Hash mismatch for \"{}\"", path));
+    }
+    Ok(bytes)
+}
+
+/// Opens and validates an archive. Structural problems (unsafe paths, missing
+/// or unsupported manifest) fail the whole bundle; problems with a single build
+/// are reported on that entry.
+pub fn read_bundle<R: Read + Seek>(reader: R) -> Result<BundleContents, String> {
+    let mut archive =
+        ZipArchive::new(reader).map_err(|e| format!("Invalid bundle archive: {}", e))?;
+    if archive.len() > MAX_BUNDLE_ENTRIES {
+        return Err(format!(
+            "Bundle has too many files: {} (max {})",
+            archive.len(),
+            MAX_BUNDLE_ENTRIES
+        ));
+    }
+    for i in 0..archive.len() {
+        let file = archive.by_index_raw(i).map_err(|e| e.to_string())?;
+        if file.is_dir() {
+            // Directory records carry no data; only their name needs checking.
+            let name = file.name().trim_end_matches('/');
+            let known = name == BUILDS_DIR.trim_end_matches('/')
+                || name == ASSETS_DIR.trim_end_matches('/')
+                || name
+                    .strip_prefix(ASSETS_DIR)
+                    .is_some_and(|id| validate_build_order_id(id).is_ok());
+            if !known {
+                return Err(format!(
+                    "Unexpected directory in bundle: \"{}\"",
+                    file.name()
+                ));
+            }
+            continue;
+        }
+        validate_entry_path(file.name())?;
+    }
+
+    let manifest_bytes = read_entry(&mut archive, MANIFEST_PATH, MAX_BUNDLE_ENTRY_SIZE)?;
+    let manifest: BundleManifest = serde_json::from_slice(&manifest_bytes)
+        .map_err(|e| format!("Invalid bundle manifest: {}", e))?;
+    if manifest.format_version == 0 || manifest.format_version > BUNDLE_FORMAT_VERSION {
+        return Err(format!(
+            "Unsupported bundle format version {} (this app reads up to {})",
+            manifest.format_version, BUNDLE_FORMAT_VERSION
+        ));
+    }
+
+    let mut seen = HashSet::new();
+    let mut builds = Vec::with_capacity(manifest.builds.len());
+    for entry in &manifest.builds {
+        let result = if !seen.insert(entry.id.clone()) {
+            Err(format!("Duplicate build id \"{}\" in bundle", entry.id))
+        } else if entry.path != build_entry_path(&entry.id) {
+            Err(format!(
+                "Manifest path \"{}\" does not match build id \"{}\"",
+                entry.path, entry.id
+            ))
+        } else {
+            read_verified(
+                &mut archive,
+                &entry.path,
+                &entry.sha256,
+                MAX_BUNDLE_ENTRY_SIZE,
+            )
+            .and_then(|bytes| {
+                let content = String::from_utf8(bytes)
+                    .map_err(|_| "Build order file is not valid UTF-8".to_string())?;
+                let order = strip_user_data(&parse_build_order_json(&content)?);
+                if order.id != entry.id {
+                    return Err(format!(
+                        "Build file id \"{}\" does not match manifest id \"{}\"",
+                        order.id, entry.id
+                    ));
+                }
+                Ok(order)
+            })
+        };
+        builds.push((entry.clone(), result));
+    }
+
+    let collections = match &manifest.collections {
+        Some(entry) if entry.path == COLLECTIONS_PATH => {
+            let bytes = read_verified(
+                &mut archive,
+                &entry.path,
+                &entry.sha256,
+                MAX_BUNDLE_ENTRY_SIZE,
+            )?;
+            serde_json::from_slice(&bytes)
+                .map_err(|e| format!("Invalid collections in bundle: {}", e))?
+        }
+        Some(entry) => {
+            return Err(format!(
+                "Unexpected collections path \"{}\" in manifest",
+                entry.path
+            ))
+        }
+        None => Vec::new(),
+    };
+
+    // Images are only accepted for a build that references them; a missing or
+    // corrupt image drops the attachment (with a warning) instead of the build.
+    let mut assets: HashMap<String, HashMap<String, Vec<u8>>> = HashMap::new();
+    for entry in &manifest.assets {
+        if entry.path != asset_entry_path(&entry.build_id, &entry.file_name) {
+            continue;
+        }
+        let referenced = builds.iter().any(|(b, result)| {
+            b.id == entry.build_id
+                && result
+                    .as_ref()
+                    .is_ok_and(|order| referenced_attachments(order).contains(&entry.file_name))
+        });
+        if !referenced {
+            continue;
+        }
+        let image = read_verified(
+            &mut archive,
+            &entry.path,
+            &entry.sha256,
+            MAX_ATTACHMENT_SIZE,
+        )
+        .and_then(|bytes| validate_attachment_image(&bytes).map(|_| bytes));
+        if let Ok(bytes) = image {
+            assets
+                .entry(entry.build_id.clone())
+                .or_default()
+                .insert(entry.file_name.clone(), bytes);
+        }
+    }
+    for (entry, result) in builds.iter_mut() {
+        if let Ok(order) = result {
+            let available = assets.get(&entry.id);
+            retain_attachments(order, |file_name| {
+                available.is_some_and(|files| files.contains_key(file_name))
+            });
+        }
+    }
+
+    Ok(BundleContents {
+        manifest,
+        builds,
+        collections,
+        assets,
+    })
+}
+
+/// True when two builds would serialize to the same file. The difficulty
+/// estimate is derived, so it is ignored.
+fn same_content(a: &BuildOrder, b: &BuildOrder) -> bool {
+    let content = |order: &BuildOrder| {
+        let mut order = strip_user_data(order);
+        order.difficulty_estimate = None;
+        serde_json::to_value(order).ok()
+    };
+    content(a) == content(b)
+}
+
+fn library_status(order: &BuildOrder, library: &[BuildOrder]) -> BundleEntryStatus {
+    match library.iter().find(|o| o.id == order.id) {
+        None => BundleEntryStatus::New,
+        Some(existing) if same_content(existing, order) => BundleEntryStatus::Identical,
+        Some(_) => BundleEntryStatus::Conflict,
+    }
+}
+
+pub fn preview_bundle(
+    contents: &BundleContents,
+    library: &[BuildOrder],
+    current_content_version: &str,
+) -> BundlePreview {
+    let builds = contents
+        .builds
+        .iter()
+        .map(|(entry, result)| {
+            let (status, error) = match result {
+                Ok(order) => (library_status(order, library), None),
+                Err(e) => (BundleEntryStatus::Invalid, Some(e.clone())),
+            };
+            BundleEntryPreview {
+                id: entry.id.clone(),
+                name: entry.name.clone(),
+                status,
+                error,
+            }
+        })
+        .collect();
+
+    BundlePreview {
+        format_version: contents.manifest.format_version,
+        app_version: contents.manifest.app_version.clone(),
+        content_version: contents.manifest.content_version.clone(),
+        content_version_mismatch: contents
+            .manifest
+            .content_version
+            .as_deref()
+            .is_some_and(|v| v != current_content_version),
+        builds,
+        collections: contents
+            .collections
+            .iter()
+            .map(|c| c.name.clone())
+            .collect(),
+    }
+}
+
+/// Picks `<id>-imported`, `<id>-imported-2`, ... that is free in `taken` and
+/// still a valid build order id.
+fn unique_import_id(id: &str, taken: &HashSet<String>) -> String {
+    let base: String = id.chars().take(48).collect();
+    let mut candidate = format!("{}-imported", base);
+    let mut suffix = 2;
+    while taken.contains(&candidate) {
+        candidate = format!("{}-imported-{}", base, suffix);
+        suffix += 1;
+    }
+    candidate
+}
+
+/// Plans `order` under a fresh `<id>-imported` id next to the build holding
+/// its id.
+fn plan_renamed(
+    plan: &mut BundleImportPlan,
+    taken: &mut HashSet<String>,
+    entry_id: &str,
+    order: &BuildOrder,
+) {
+    let new_id = unique_import_id(&order.id, taken);
+    taken.insert(new_id.clone());
+    plan.id_map.insert(order.id.clone(), new_id.clone());
+    plan.report.renamed.push(BundleRename {
+        from: order.id.clone(),
+        to: new_id.clone(),
+    });
+    let mut renamed = order.clone();
+    renamed.id = new_id;
+    plan.builds.push((entry_id.to_string(), renamed));
+}
+
+/// Decides what happens to every bundled build against the current library.
+pub fn plan_import(
+    contents: &BundleContents,
+    library: &[BuildOrder],
+    options: &BundleImportOptions,
+) -> BundleImportPlan {
+    let mut taken: HashSet<String> = library.iter().map(|o| o.id.clone()).collect();
+    let mut plan = BundleImportPlan {
+        builds: Vec::new(),
+        id_map: HashMap::new(),
+        report: BundleImportReport::default(),
+    };
+
+    for (entry, result) in &contents.builds {
+        let order = match result {
+            Ok(order) => order,
+            Err(error) => {
+                plan.report.failed.push(BundleEntryError {
+                    id: entry.id.clone(),
+                    error: error.clone(),
+                });
+                continue;
+            }
+        };
+
+        match library_status(order, library) {
+            // Not in the library, but claimed by a build renamed earlier in
+            // this import
+            BundleEntryStatus::New if taken.contains(&order.id) => {
+                plan_renamed(&mut plan, &mut taken, &entry.id, order);
+            }
+            BundleEntryStatus::New => {
+                taken.insert(order.id.clone());
+                plan.id_map.insert(order.id.clone(), order.id.clone());
+                plan.report.imported.push(order.id.clone());
+                plan.builds.push((entry.id.clone(), order.clone()));
+            }
+            BundleEntryStatus::Identical => {
+                plan.id_map.insert(order.id.clone(), order.id.clone());
+                plan.report.skipped.push(order.id.clone());
+            }
+            BundleEntryStatus::Conflict => {
+                let resolution = options
+                    .resolutions
+                    .get(&order.id)
+                    .copied()
+                    .unwrap_or(options.default_resolution);
+                match resolution {
+                    ConflictResolution::Skip => {
+                        plan.id_map.insert(order.id.clone(), order.id.clone());
+                        plan.report.skipped.push(order.id.clone());
+                    }
+                    ConflictResolution::Replace => {
+                        plan.id_map.insert(order.id.clone(), order.id.clone());
+                        plan.report.replaced.push(order.id.clone());
+                        plan.builds.push((entry.id.clone(), order.clone()));
+                    }
+                    ConflictResolution::KeepBoth => {
+                        plan_renamed(&mut plan, &mut taken, &entry.id, order);
+                    }
+                }
+            }
+            BundleEntryStatus::Invalid => {}
+        }
+    }
+    plan
+}
+
+/// Adds bundled collections to `store`, remapping member ids through `id_map`.
+/// A collection whose name already exists is extended instead of duplicated.
+/// Returns the ids of collections that changed.
+pub fn merge_bundle_collections(
+    store: &mut CollectionStore,
+    collections: &[BuildOrderCollection],
+    id_map: &HashMap<String, String>,
+    library: &[BuildOrder],
+) -> Result<Vec<String>, String> {
+    let mut changed = Vec::new();
+    for bundled in collections {
+        let members: Vec<String> = bundled
+            .build_order_ids
+            .iter()
+            .filter_map(|id| id_map.get(id).cloned())
+            .collect();
+        if members.is_empty() {
+            continue;
+        }
+
+        let existing = store
+            .collections
+            .iter()
+            .find(|c| c.name.eq_ignore_ascii_case(bundled.name.trim()))
+            .cloned();
+        let (id, mut ids) = match existing {
+            Some(c) => (c.id, c.build_order_ids),
+            None => match store.create(&bundled.name) {
+                Ok(created) => (created.id, Vec::new()),
+                // Bad names or a full store only cost the collection, not the import
+                Err(_) => continue,
+            },
+        };
+        let before = ids.len();
+        ids.extend(members);
+        store.set_build_orders(&id, ids, library)?;
+        let after = store
+            .collections
+            .iter()
+            .find(|c| c.id == id)
+            .map_or(0, |c| c.build_order_ids.len());
+        if before == 0 || after != before {
+            changed.push(id);
+        }
+    }
+    Ok(changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{BuildOrderStep, StepAttachment};
+    use std::io::Cursor;
+
+    fn build(id: &str, description: &str) -> BuildOrder {
+        BuildOrder {
+            id: id.to_string(),
+            name: id.to_string(),
+            civilization: "English".to_string(),
+            description: description.to_string(),
+            difficulty: "Beginner".to_string(),
+            steps: vec![BuildOrderStep {
+                id: "s1".to_string(),
+                description: "Step".to_string(),
+                ..Default::default()
+            }],
+            enabled: true,
+            tags: Some(vec!["ranked".to_string()]),
+            ..Default::default()
+        }
+    }
+
+    fn collection(name: &str, ids: &[&str]) -> BuildOrderCollection {
+        BuildOrderCollection {
+            id: name.to_lowercase(),
+            name: name.to_string(),
+            build_order_ids: ids.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    fn bundle_bytes(builds: &[BuildOrder], collections: &[BuildOrderCollection]) -> Vec<u8> {
+        let mut cursor = Cursor::new(Vec::new());
+        write_bundle(
+            &mut cursor,
+            builds,
+            collections,
+            Some("2026-05-07".to_string()),
+            |_, _| None,
+        )
+        .unwrap();
+        cursor.into_inner()
+    }
+
+    /// Writes raw entries, for archives `write_bundle` would never produce.
+    fn raw_zip(entries: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, bytes) in entries {
+            zip.start_file(*name, SimpleFileOptions::default()).unwrap();
+            zip.write_all(bytes).unwrap();
+        }
+        zip.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn test_round_trip_strips_personal_data() {
+        let mut a = build("a", "first");
+        a.favorite = true;
+        let bytes = bundle_bytes(
+            &[a, build("b", "second")],
+            &[collection("Pocket", &["a", "x"])],
+        );
+
+        let contents = read_bundle(Cursor::new(bytes)).unwrap();
+        assert_eq!(contents.manifest.format_version, BUNDLE_FORMAT_VERSION);
+        assert_eq!(
+            contents.manifest.content_version.as_deref(),
+            Some("2026-05-07")
+        );
+        assert_eq!(contents.builds.len(), 2);
+        let a = contents.builds[0].1.as_ref().unwrap();
+        assert!(!a.favorite);
+        assert_eq!(a.tags.as_deref(), Some(&["ranked".to_string()][..]));
+        // Members that weren't exported are dropped
+        assert_eq!(contents.collections[0].build_order_ids, vec!["a"]);
+    }
+
+    #[test]
+    fn test_round_trip_includes_referenced_images() {
+        const PNG: &[u8] = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+        let mut order = build("a", "");
+        order.steps[0].attachments = Some(vec![
+            StepAttachment {
+                file_name: "s1-map.png".to_string(),
+                caption: Some("Wall here".to_string()),
+            },
+            StepAttachment {
+                file_name: "s1-lost.png".to_string(),
+                caption: None,
+            },
+        ]);
+
+        let mut cursor = Cursor::new(Vec::new());
+        let manifest = write_bundle(&mut cursor, &[order], &[], None, |build_id, file| {
+            (build_id == "a" && file == "s1-map.png").then(|| PNG.to_vec())
+        })
+        .unwrap();
+        assert_eq!(manifest.assets.len(), 1);
+        assert_eq!(manifest.assets[0].path, "assets/a/s1-map.png");
+
+        let contents = read_bundle(Cursor::new(cursor.into_inner())).unwrap();
+        assert_eq!(contents.assets["a"]["s1-map.png"], PNG);
+        let imported = contents.builds[0].1.as_ref().unwrap();
+        let attachments = imported.steps[0].attachments.as_ref().unwrap();
+        assert_eq!(attachments.len(), 1);
+        assert_eq!(attachments[0].caption.as_deref(), Some("Wall here"));
+        assert!(imported.warnings.as_ref().unwrap()[0].contains("s1-lost.png"));
+    }
+
+    #[test]
+    fn test_validate_entry_path_rejects_traversal() {
+        assert!(validate_entry_path("manifest.json").is_ok());
+        assert!(validate_entry_path("builds/english-fc.json").is_ok());
+        assert!(validate_entry_path("assets/english-fc/s1-abc.png").is_ok());
+        for bad in [
+            "../manifest.json",
+            "builds/../../evil.json",
+            "/etc/passwd",
+            "builds\\evil.json",
+            "C:/evil.json",
+            "builds//a.json",
+            "builds/./a.json",
+            "builds/bad id.json",
+            "notes.txt",
+            "assets/english-fc/../../evil.png",
+            "assets/english-fc/s1.svg",
+            "assets/s1.png",
+        ] {
+            assert!(
+                validate_entry_path(bad).is_err(),
+                "{} should be rejected",
+                bad
+            );
+        }
+    }
+
+    #[test]
+    fn test_read_rejects_unsafe_archive() {
+        let bytes = raw_zip(&[("manifest.json", b"{}"), ("../../evil.json", b"{}")]);
+        let err = read_bundle(Cursor::new(bytes)).unwrap_err();
+        assert!(err.contains("Unsafe path"));
+    }
+
+    #[test]
+    fn test_read_rejects_newer_format() {
+        let manifest = format!(
+            r#"{{"formatVersion": {}, "builds": []}}"#,
+            BUNDLE_FORMAT_VERSION + 1
+        );
+        let bytes = raw_zip(&[("manifest.json", manifest.as_bytes())]);
+        let err = read_bundle(Cursor::new(bytes)).unwrap_err();
+        assert!(err.contains("Unsupported bundle format"));
+    }
+
+    #[test]
+    fn test_hash_mismatch_fails_only_that_entry() {
+        let good = serde_json::to_vec(&build("good", "")).unwrap();
+        let bad = serde_json::to_vec(&build("bad", "")).unwrap();
+        let manifest = serde_json::to_vec(&BundleManifest {
+            format_version: 1,
+            app_version: String::new(),
+            content_version: None,
+            created_at: 0,
+            builds: vec![
+                BundleBuildEntry {
+                    id: "good".to_string(),
+                    name: "good".to_string(),
+                    path: "builds/good.json".to_string(),
+                    sha256: sha256_hex(&good),
+                },
+                BundleBuildEntry {
+                    id: "bad".to_string(),
+                    name: "bad".to_string(),
+                    path: "builds/bad.json".to_string(),
+                    sha256: sha256_hex(b"something else"),
+                },
+            ],
+            collections: None,
+            assets: Vec::new(),
+        })
+        .unwrap();
+        let bytes = raw_zip(&[
+            ("manifest.json", &manifest),
+            ("builds/good.json", &good),
+            ("builds/bad.json", &bad),
+        ]);
+
+        let contents = read_bundle(Cursor::new(bytes)).unwrap();
+        assert!(contents.builds[0].1.is_ok());
+        assert!(contents.builds[1]
+            .1
+            .as_ref()
+            .unwrap_err()
+            .contains("Hash mismatch"));
+    }
+
+    #[test]
+    fn test_preview_and_plan_conflicts() {
+        let library = vec![
+            build("same", "x"),
+            build("changed", "old"),
+            build("other", ""),
+        ];
+        let bytes = bundle_bytes(
+            &[
+                build("same", "x"),
+                build("changed", "new"),
+                build("fresh", ""),
+            ],
+            &[],
+        );
+        let contents = read_bundle(Cursor::new(bytes)).unwrap();
+
+        let preview = preview_bundle(&contents, &library, "2025-01-01");
+        let statuses: Vec<BundleEntryStatus> = preview.builds.iter().map(|b| b.status).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                BundleEntryStatus::Identical,
+                BundleEntryStatus::Conflict,
+                BundleEntryStatus::New
+            ]
+        );
+        assert!(preview.content_version_mismatch);
+
+        let skip = plan_import(&contents, &library, &BundleImportOptions::default());
+        assert_eq!(skip.report.imported, vec!["fresh"]);
+        assert_eq!(skip.report.skipped, vec!["same", "changed"]);
+
+        let mut options = BundleImportOptions::default();
+        options
+            .resolutions
+            .insert("changed".to_string(), ConflictResolution::KeepBoth);
+        let keep_both = plan_import(&contents, &library, &options);
+        assert_eq!(
+            keep_both.report.renamed,
+            vec![BundleRename {
+                from: "changed".to_string(),
+                to: "changed-imported".to_string()
+            }]
+        );
+        assert_eq!(keep_both.id_map["changed"], "changed-imported");
+
+        options.default_resolution = ConflictResolution::Replace;
+        options.resolutions.clear();
+        let replace = plan_import(&contents, &library, &options);
+        assert_eq!(replace.report.replaced, vec!["changed"]);
+        assert_eq!(replace.builds.len(), 2);
+    }
+
+    #[test]
+    fn test_plan_renames_new_build_taken_by_earlier_rename() {
+        let library = vec![build("a", "old")];
+        let bytes = bundle_bytes(&[build("a", "new"), build("a-imported", "")], &[]);
+        let contents = read_bundle(Cursor::new(bytes)).unwrap();
+        let options = BundleImportOptions {
+            default_resolution: ConflictResolution::KeepBoth,
+            ..Default::default()
+        };
+
+        let plan = plan_import(&contents, &library, &options);
+        let ids: Vec<&str> = plan.builds.iter().map(|(_, o)| o.id.as_str()).collect();
+        assert_eq!(ids, vec!["a-imported", "a-imported-imported"]);
+        assert_eq!(plan.id_map["a"], "a-imported");
+        assert_eq!(plan.id_map["a-imported"], "a-imported-imported");
+        assert!(plan.report.imported.is_empty());
+    }
+
+    #[test]
+    fn test_unique_import_id() {
+        let taken: HashSet<String> = ["a-imported".to_string()].into_iter().collect();
+        assert_eq!(unique_import_id("a", &taken), "a-imported-2");
+        let long = "x".repeat(64);
+        assert!(validate_build_order_id(&unique_import_id(&long, &HashSet::new())).is_ok());
+    }
+
+    #[test]
+    fn test_merge_bundle_collections() {
+        let library = vec![build("a", ""), build("b", ""), build("b-imported", "")];
+        let mut store = CollectionStore::default();
+        let pocket = store.create("Pocket").unwrap();
+        store
+            .set_build_orders(&pocket.id, vec!["a".to_string()], &library)
+            .unwrap();
+
+        let id_map: HashMap<String, String> = [
+            ("a".to_string(), "a".to_string()),
+            ("b".to_string(), "b-imported".to_string()),
+        ]
+        .into_iter()
+        .collect();
+        let changed = merge_bundle_collections(
+            &mut store,
+            &[
+                collection("pocket", &["a", "b"]),
+                collection("Flank", &["b", "gone"]),
+            ],
+            &id_map,
+            &library,
+        )
+        .unwrap();
+
+        assert_eq!(changed, vec!["pocket", "flank"]);
+        assert_eq!(
+            store.collections[0].build_order_ids,
+            vec!["a", "b-imported"]
+        );
+        assert_eq!(store.collections[1].build_order_ids, vec!["b-imported"]);
+    }
+}
@@ -0,0 +1,134 @@
+use super::build_order::{broadcast_build_orders, served_build_order, write_build_order_file};
+use crate::config::{
+    all_steps_mut, attachment_file_name, get_build_assets_dir, read_attachment_image,
+    remove_orphaned_assets, store_attachment, validate_build_order, validate_build_order_id,
+    validate_step_attachments, BuildOrder, BuildOrderStep, StepAttachment,
+};
+use crate::state::AppState;
+use std::path::PathBuf;
+use tauri::{AppHandle, State};
+
+/// Applies `edit` to one step of a library build, then saves, re-indexes and
+/// broadcasts the build. `store_files` runs only once the edited build is
+/// valid; if it or the save fails, files the old build does not use are
+/// removed again. Orphaned images are cleaned up afterwards.
+fn update_step<F, S>(
+    build_order_id: &str,
+    step_id: &str,
+    state: &State<AppState>,
+    app: &AppHandle,
+    edit: F,
+    store_files: S,
+) -> Result<BuildOrder, String>
+where
+    F: FnOnce(&mut BuildOrderStep) -> Result<(), String>,
+    S: FnOnce() -> Result<(), String>,
+{
+    let mut orders = state.build_orders.lock().map_err(|e| e.to_string())?;
+    let index = orders
+        .iter()
+        .position(|o| o.id == build_order_id)
+        .ok_or_else(|| format!("Build order \"{}\" does not exist", build_order_id))?;
+
+    let mut order = orders[index].clone();
+    let step = all_steps_mut(&mut order)
+        .find(|s| s.id == step_id)
+        .ok_or_else(|| format!("Step \"{}\" not found in build order", step_id))?;
+    edit(step)?;
+    validate_build_order(&order)?;
+
+    if let Err(e) = store_files().and_then(|_| write_build_order_file(&mut order)) {
+        if let Err(cleanup) = remove_orphaned_assets(&orders[index]) {
+            eprintln!("Failed to clean up assets of {}: {}", order.id, cleanup);
+        }
+        return Err(e);
+    }
+    if let Err(e) = remove_orphaned_assets(&order) {
+        eprintln!("Failed to clean up assets of {}: {}", order.id, e);
+    }
+    state
+        .search_index
+        .lock()
+        .map_err(|e| e.to_string())?
+        .upsert(&order);
+    orders[index] = order.clone();
+
+    let metadata = state.user_metadata.lock().map_err(|e| e.to_string())?;
+    broadcast_build_orders(app, &orders, &metadata)?;
+    served_build_order(&order, &metadata)
+}
+
+/// Copies the image at `source_path` into the build's assets folder and
+/// attaches it to the step.
+#[tauri::command]
+pub fn add_step_attachment(
+    build_order_id: String,
+    step_id: String,
+    source_path: String,
+    caption: Option<String>,
+    state: State<AppState>,
+    app: AppHandle,
+) -> Result<BuildOrder, String> {
+    validate_build_order_id(&build_order_id)?;
+    let (bytes, image_type) = read_attachment_image(&PathBuf::from(&source_path))?;
+    let file_name = attachment_file_name(&step_id, &bytes, image_type);
+    let caption = caption.filter(|c| !c.trim().is_empty());
+
+    update_step(
+        &build_order_id,
+        &step_id,
+        &state,
+        &app,
+        |step| {
+            let attachments = step.attachments.get_or_insert_with(Vec::new);
+            if attachments.iter().any(|a| a.file_name == file_name) {
+                return Err("This image is already attached to the step".to_string());
+            }
+            attachments.push(StepAttachment {
+                file_name: file_name.clone(),
+                caption,
+            });
+            validate_step_attachments(step)
+        },
+        || store_attachment(&build_order_id, &file_name, &bytes),
+    )
+}
+
+#[tauri::command]
+pub fn remove_step_attachment(
+    build_order_id: String,
+    step_id: String,
+    file_name: String,
+    state: State<AppState>,
+    app: AppHandle,
+) -> Result<BuildOrder, String> {
+    update_step(
+        &build_order_id,
+        &step_id,
+        &state,
+        &app,
+        |step| {
+            let attachments = step.attachments.get_or_insert_with(Vec::new);
+            let before = attachments.len();
+            attachments.retain(|a| a.file_name != file_name);
+            if attachments.len() == before {
+                return Err(format!("Attachment \"{}\" not found on step", file_name));
+            }
+            if attachments.is_empty() {
+                step.attachments = None;
+            }
+            Ok(())
+        },
+        || Ok(()),
+    )
+}
+
+/// Absolute folder holding a build's images, for the UI to load them from.
+#[tauri::command]
+pub fn get_build_assets_dir_path(build_order_id: String) -> Result<String, String> {
+    validate_build_order_id(&build_order_id)?;
+    get_build_assets_dir(&build_order_id)
+        .to_str()
+        .map(|path| path.to_string())
+        .ok_or_else(|| "Build assets folder path is not valid UTF-8".to_string())
+}
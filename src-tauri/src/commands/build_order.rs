@@ -1,6 +1,13 @@
+use super::collections::persist_collections;
+use super::game_plans::persist_game_plans;
 use crate::config::{
-    atomic_write, get_build_orders_dir, validate_build_order, validate_build_order_id, BuildOrder,
+    atomic_write, get_build_orders_dir, load_attachment, parse_build_order_json,
+    read_attachment_image, referenced_attachments, remove_build_assets, remove_orphaned_assets,
+    retain_attachments, save_user_metadata, sidecar_assets_dir, store_attachment, strip_user_data,
+    validate_build_order, validate_build_order_id, BuildOrder, UserMetadataStore,
 };
+use crate::difficulty::refresh_difficulty_estimate;
+use crate::icons::{attach_description_segments, normalize_build_order_icons};
 use crate::state::AppState;
 use std::fs;
 use std::path::PathBuf;
@@ -18,10 +25,57 @@ Useful sources:
 - AoE4World: https://aoe4world.com/builds
 "#;
 
+/// Writes `order` to the library folder as `<id>.json`, without personal data.
+/// Icon notations are normalized and the difficulty estimate refreshed first so
+/// the file and the in-memory library agree.
+pub(super) fn write_build_order_file(order: &mut BuildOrder) -> Result<(), String> {
+    normalize_build_order_icons(order)?;
+    refresh_difficulty_estimate(order);
+    let path = get_build_orders_dir().join(format!("{}.json", order.id));
+    let json = serde_json::to_string_pretty(&strip_user_data(order)).map_err(|e| e.to_string())?;
+    atomic_write(path, json).map_err(|e| e.to_string())
+}
+
+/// A library entry as the UI sees it: user metadata merged in and step
+/// descriptions split into text and icon segments.
+pub(super) fn served_build_order(
+    order: &BuildOrder,
+    metadata: &UserMetadataStore,
+) -> Result<BuildOrder, String> {
+    let mut served = metadata.merge_into(order);
+    attach_description_segments(&mut served)?;
+    Ok(served)
+}
+
+fn served_build_orders(
+    orders: &[BuildOrder],
+    metadata: &UserMetadataStore,
+) -> Result<Vec<BuildOrder>, String> {
+    orders
+        .iter()
+        .map(|o| served_build_order(o, metadata))
+        .collect()
+}
+
+/// Broadcasts the library to all windows in the shape `get_build_orders`
+/// returns.
+pub(super) fn broadcast_build_orders(
+    app: &AppHandle,
+    orders: &[BuildOrder],
+    metadata: &UserMetadataStore,
+) -> Result<(), String> {
+    app.emit(
+        BUILD_ORDERS_CHANGED_EVENT,
+        served_build_orders(orders, metadata)?,
+    )
+    .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub fn get_build_orders(state: State<AppState>) -> Result<Vec<BuildOrder>, String> {
     let orders = state.build_orders.lock().map_err(|e| e.to_string())?;
-    Ok(orders.clone())
+    let metadata = state.user_metadata.lock().map_err(|e| e.to_string())?;
+    served_build_orders(&orders, &metadata)
 }
 
 #[tauri::command]
@@ -48,23 +102,38 @@ pub fn save_build_order(
     validate_build_order(&order)?;
     validate_build_order_id(&order.id)?;
 
-    // Save to file
-    let dir = get_build_orders_dir();
-    let path = dir.join(format!("{}.json", order.id));
-    let json = serde_json::to_string_pretty(&order).map_err(|e| e.to_string())?;
-    atomic_write(path, json).map_err(|e| e.to_string())?;
+    // Save to file; pin/favorite go to user metadata, not the shared build file
+    let mut content = strip_user_data(&order);
+    write_build_order_file(&mut content)?;
+
+    // Images of steps that were removed or lost their attachments
+    if let Err(e) = remove_orphaned_assets(&content) {
+        eprintln!("Failed to clean up assets of {}: {}", content.id, e);
+    }
 
     // Update cache
     let mut orders = state.build_orders.lock().map_err(|e| e.to_string())?;
+    state
+        .search_index
+        .lock()
+        .map_err(|e| e.to_string())?
+        .upsert(&content);
     if let Some(index) = orders.iter().position(|o| o.id == order.id) {
-        orders[index] = order;
+        orders[index] = content;
     } else {
-        orders.push(order);
+        orders.push(content);
+    }
+
+    let mut metadata = state.user_metadata.lock().map_err(|e| e.to_string())?;
+    let mut next = metadata.clone();
+    next.absorb_flags(&order);
+    if next != *metadata {
+        save_user_metadata(&next)?;
+        *metadata = next;
     }
 
     // Broadcast build order change to all windows
-    app.emit(BUILD_ORDERS_CHANGED_EVENT, &*orders)
-        .map_err(|e| e.to_string())?;
+    broadcast_build_orders(&app, &orders, &metadata)?;
 
     Ok(())
 }
@@ -86,14 +155,35 @@ pub fn delete_build_order(
             return Err(format!("Failed to delete build order file: {}", e));
         }
     }
-
     // Update cache
     let mut orders = state.build_orders.lock().map_err(|e| e.to_string())?;
     orders.retain(|o| o.id != id);
+    state
+        .search_index
+        .lock()
+        .map_err(|e| e.to_string())?
+        .remove(&id);
+
+    // Drop the deleted build from any collection that referenced it
+    let mut collections = state.collections.lock().map_err(|e| e.to_string())?;
+    if collections.remove_build_order(&id) {
+        persist_collections(&collections, &app)?;
+    }
+    let mut game_plans = state.game_plans.lock().map_err(|e| e.to_string())?;
+    if game_plans.remove_build_order(&id) {
+        persist_game_plans(&game_plans, &app)?;
+    }
+
+    // The build is gone either way; leftover images are only disk clutter
+    if let Err(e) = remove_build_assets(&id) {
+        eprintln!("Failed to clean up assets of {}: {}", id, e);
+    }
+
+    // User metadata is kept so notes and history return if the build is re-imported
+    let metadata = state.user_metadata.lock().map_err(|e| e.to_string())?;
 
     // Broadcast build order change to all windows
-    app.emit(BUILD_ORDERS_CHANGED_EVENT, &*orders)
-        .map_err(|e| e.to_string())?;
+    broadcast_build_orders(&app, &orders, &metadata)?;
 
     Ok(())
 }
@@ -125,11 +215,8 @@ pub fn import_build_order(
 
     // Read and parse
     let content = fs::read_to_string(&path).map_err(|e| format!("Failed to read file: {}", e))?;
-    let order: BuildOrder =
-        serde_json::from_str(&content).map_err(|e| format!("Invalid build order format: {}", e))?;
-
-    validate_build_order_id(&order.id)?;
-    validate_build_order(&order)?;
+    // Flags in a shared file belong to whoever exported it
+    let mut order = strip_user_data(&parse_build_order_json(&content)?);
 
     // Validate duplicate against in-memory cache before writing to disk
     let mut orders = state.build_orders.lock().map_err(|e| e.to_string())?;
@@ -140,25 +227,52 @@ pub fn import_build_order(
         ));
     }
 
+    // Copy images from the `<name>-assets` folder exported alongside the file;
+    // attachments whose image is missing or invalid are dropped with a warning.
+    let sidecar = sidecar_assets_dir(&path);
+    let build_id = order.id.clone();
+    retain_attachments(&mut order, |file_name| {
+        read_attachment_image(&sidecar.join(file_name))
+            .and_then(|(bytes, _)| store_attachment(&build_id, file_name, &bytes))
+            .is_ok()
+    });
+
     // Save to build orders directory
-    let dir = get_build_orders_dir();
-    let save_path = dir.join(format!("{}.json", order.id));
-    let json = serde_json::to_string_pretty(&order).map_err(|e| e.to_string())?;
-    atomic_write(save_path, json).map_err(|e| e.to_string())?;
+    write_build_order_file(&mut order)?;
 
+    state
+        .search_index
+        .lock()
+        .map_err(|e| e.to_string())?
+        .upsert(&order);
     orders.push(order.clone());
 
     // Broadcast build order change to all windows
-    app.emit(BUILD_ORDERS_CHANGED_EVENT, &*orders)
-        .map_err(|e| e.to_string())?;
+    let metadata = state.user_metadata.lock().map_err(|e| e.to_string())?;
+    broadcast_build_orders(&app, &orders, &metadata)?;
 
-    Ok(order)
+    served_build_order(&order, &metadata)
 }
 
 #[tauri::command]
 pub fn export_build_order(order: BuildOrder, path: String) -> Result<(), String> {
-    let json = serde_json::to_string_pretty(&order).map_err(|e| e.to_string())?;
-    atomic_write(&path, json).map_err(|e| format!("Failed to write file: {}", e))
+    let json = serde_json::to_string_pretty(&strip_user_data(&order)).map_err(|e| e.to_string())?;
+    atomic_write(&path, json).map_err(|e| format!("Failed to write file: {}", e))?;
+
+    // Step images go into a `<name>-assets` folder next to the file
+    let referenced = referenced_attachments(&order);
+    if referenced.is_empty() {
+        return Ok(());
+    }
+    let sidecar = sidecar_assets_dir(&PathBuf::from(&path));
+    fs::create_dir_all(&sidecar).map_err(|e| format!("Failed to write file: {}", e))?;
+    for file_name in referenced {
+        if let Some(bytes) = load_attachment(&order.id, &file_name) {
+            atomic_write(sidecar.join(&file_name), bytes)
+                .map_err(|e| format!("Failed to write file: {}", e))?;
+        }
+    }
+    Ok(())
 }
 
 #[cfg(test)]
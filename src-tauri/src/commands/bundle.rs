@@ -0,0 +1,194 @@
+use super::build_order::broadcast_build_orders;
+use super::build_order::write_build_order_file;
+use super::collections::persist_collections;
+use crate::bundle::{
+    self, BundleContents, BundleEntryError, BundleImportOptions, BundleImportReport,
+    BundleManifest, BundlePreview, MAX_BUNDLE_SIZE,
+};
+use crate::config::{
+    load_attachment, referenced_attachments, remove_orphaned_assets, store_attachment, BuildOrder,
+};
+use crate::state::AppState;
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::PathBuf;
+use tauri::{AppHandle, State};
+
+fn open_bundle(path: &str) -> Result<BundleContents, String> {
+    let path = PathBuf::from(path);
+    let metadata = fs::metadata(&path).map_err(|e| format!("Cannot access file: {}", e))?;
+    if !metadata.is_file() {
+        return Err("Path must be a regular file".to_string());
+    }
+    if metadata.len() > MAX_BUNDLE_SIZE {
+        return Err(format!(
+            "File too large: {} bytes (max {} bytes)",
+            metadata.len(),
+            MAX_BUNDLE_SIZE
+        ));
+    }
+    let file = File::open(&path).map_err(|e| format!("Failed to read file: {}", e))?;
+    bundle::read_bundle(file)
+}
+
+/// Exports the given builds (or the whole library when `build_ids` is None)
+/// plus the collections that reference them as a single bundle archive.
+#[tauri::command]
+pub fn export_bundle(
+    path: String,
+    build_ids: Option<Vec<String>>,
+    include_collections: Option<bool>,
+    state: State<AppState>,
+) -> Result<BundleManifest, String> {
+    let content_version = state
+        .config
+        .lock()
+        .map_err(|e| e.to_string())?
+        .asset_content_version
+        .clone();
+
+    let builds: Vec<BuildOrder> = {
+        let orders = state.build_orders.lock().map_err(|e| e.to_string())?;
+        match &build_ids {
+            None => orders.clone(),
+            Some(ids) => {
+                let mut selected = Vec::with_capacity(ids.len());
+                for id in ids {
+                    let order = orders
+                        .iter()
+                        .find(|o| o.id == *id)
+                        .ok_or_else(|| format!("Build order \"{}\" does not exist", id))?;
+                    selected.push(order.clone());
+                }
+                selected
+            }
+        }
+    };
+    if builds.is_empty() {
+        return Err("Nothing to export".to_string());
+    }
+
+    let collections = if include_collections.unwrap_or(true) {
+        state
+            .collections
+            .lock()
+            .map_err(|e| e.to_string())?
+            .collections
+            .clone()
+    } else {
+        Vec::new()
+    };
+
+    // Write next to the target and rename, like atomic_write, so a failed
+    // export never leaves a truncated archive behind.
+    let target = PathBuf::from(&path);
+    let tmp_path = target.with_extension("tmp");
+    let result = File::create(&tmp_path)
+        .map_err(|e| format!("Failed to write file: {}", e))
+        .and_then(|file| {
+            bundle::write_bundle(
+                BufWriter::new(file),
+                &builds,
+                &collections,
+                Some(content_version),
+                load_attachment,
+            )
+        })
+        .and_then(|manifest| {
+            fs::rename(&tmp_path, &target)
+                .map(|_| manifest)
+                .map_err(|e| format!("Failed to write file: {}", e))
+        });
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp_path);
+    }
+    result
+}
+
+/// Reads a bundle and reports, per build, whether importing it would add,
+/// no-op, conflict or fail, so the UI can ask how to resolve conflicts.
+#[tauri::command]
+pub fn preview_bundle(path: String, state: State<AppState>) -> Result<BundlePreview, String> {
+    let contents = open_bundle(&path)?;
+    let content_version = state
+        .config
+        .lock()
+        .map_err(|e| e.to_string())?
+        .asset_content_version
+        .clone();
+    let orders = state.build_orders.lock().map_err(|e| e.to_string())?;
+    Ok(bundle::preview_bundle(&contents, &orders, &content_version))
+}
+
+#[tauri::command]
+pub fn import_bundle(
+    path: String,
+    options: Option<BundleImportOptions>,
+    state: State<AppState>,
+    app: AppHandle,
+) -> Result<BundleImportReport, String> {
+    let contents = open_bundle(&path)?;
+    let options = options.unwrap_or_default();
+
+    let mut orders = state.build_orders.lock().map_err(|e| e.to_string())?;
+    let mut plan = bundle::plan_import(&contents, &orders, &options);
+
+    {
+        let mut index = state.search_index.lock().map_err(|e| e.to_string())?;
+        for (bundle_id, mut order) in plan.builds {
+            let images = contents.assets.get(&bundle_id);
+            let written = referenced_attachments(&order)
+                .iter()
+                .try_for_each(
+                    |file_name| match images.and_then(|files| files.get(file_name)) {
+                        Some(bytes) => store_attachment(&order.id, file_name, bytes),
+                        None => Ok(()),
+                    },
+                )
+                .and_then(|_| write_build_order_file(&mut order));
+            if let Err(error) = written {
+                plan.id_map.remove(&bundle_id);
+                plan.report.imported.retain(|id| *id != bundle_id);
+                plan.report.replaced.retain(|id| *id != bundle_id);
+                plan.report.renamed.retain(|r| r.from != bundle_id);
+                plan.report.failed.push(BundleEntryError {
+                    id: bundle_id,
+                    error: format!("Failed to write file: {}", error),
+                });
+                continue;
+            }
+            // A replaced build may have dropped images the old version used
+            if let Err(e) = remove_orphaned_assets(&order) {
+                eprintln!("Failed to clean up assets of {}: {}", order.id, e);
+            }
+
+            index.upsert(&order);
+            if let Some(existing) = orders.iter().position(|o| o.id == order.id) {
+                orders[existing] = order;
+            } else {
+                orders.push(order);
+            }
+        }
+    }
+
+    if options.include_collections && !contents.collections.is_empty() {
+        let mut collections = state.collections.lock().map_err(|e| e.to_string())?;
+        let mut next = collections.clone();
+        let changed = bundle::merge_bundle_collections(
+            &mut next,
+            &contents.collections,
+            &plan.id_map,
+            &orders,
+        )?;
+        if !changed.is_empty() {
+            persist_collections(&next, &app)?;
+            *collections = next;
+        }
+        plan.report.collections = changed;
+    }
+
+    let metadata = state.user_metadata.lock().map_err(|e| e.to_string())?;
+    broadcast_build_orders(&app, &orders, &metadata)?;
+
+    Ok(plan.report)
+}
@@ -0,0 +1,112 @@
+use crate::config::{atomic_write, get_collections_path, BuildOrderCollection, CollectionStore};
+use crate::state::AppState;
+use tauri::{AppHandle, Emitter, State};
+
+pub(super) const COLLECTIONS_CHANGED_EVENT: &str = "collections-changed";
+
+/// Writes the collection store to disk and broadcasts it to all windows.
+pub(super) fn persist_collections(store: &CollectionStore, app: &AppHandle) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(store).map_err(|e| e.to_string())?;
+    atomic_write(get_collections_path(), json).map_err(|e| e.to_string())?;
+    app.emit(COLLECTIONS_CHANGED_EVENT, store)
+        .map_err(|e| e.to_string())
+}
+
+/// Applies `update` to the collection store under the lock, then persists and
+/// broadcasts the result. Nothing is written if `update` fails.
+fn update_collections<T, F>(
+    state: &State<AppState>,
+    app: &AppHandle,
+    update: F,
+) -> Result<T, String>
+where
+    F: FnOnce(&mut CollectionStore) -> Result<T, String>,
+{
+    let mut store = state.collections.lock().map_err(|e| e.to_string())?;
+    let mut next = store.clone();
+    let result = update(&mut next)?;
+    persist_collections(&next, app)?;
+    *store = next;
+    Ok(result)
+}
+
+#[tauri::command]
+pub fn get_collections(state: State<AppState>) -> Result<CollectionStore, String> {
+    let store = state.collections.lock().map_err(|e| e.to_string())?;
+    Ok(store.clone())
+}
+
+#[tauri::command]
+pub fn create_collection(
+    name: String,
+    state: State<AppState>,
+    app: AppHandle,
+) -> Result<BuildOrderCollection, String> {
+    update_collections(&state, &app, |store| store.create(&name))
+}
+
+#[tauri::command]
+pub fn rename_collection(
+    id: String,
+    name: String,
+    state: State<AppState>,
+    app: AppHandle,
+) -> Result<(), String> {
+    update_collections(&state, &app, |store| store.rename(&id, &name))
+}
+
+#[tauri::command]
+pub fn delete_collection(id: String, state: State<AppState>, app: AppHandle) -> Result<(), String> {
+    update_collections(&state, &app, |store| store.delete(&id))
+}
+
+#[tauri::command]
+pub fn reorder_collections(
+    ids: Vec<String>,
+    state: State<AppState>,
+    app: AppHandle,
+) -> Result<(), String> {
+    update_collections(&state, &app, |store| store.reorder(&ids))
+}
+
+/// Replaces the ordered list of builds in a collection (add, remove and reorder).
+#[tauri::command]
+pub fn set_collection_build_orders(
+    id: String,
+    build_order_ids: Vec<String>,
+    state: State<AppState>,
+    app: AppHandle,
+) -> Result<(), String> {
+    let orders = state.build_orders.lock().map_err(|e| e.to_string())?;
+    update_collections(&state, &app, |store| {
+        store.set_build_orders(&id, build_order_ids, &orders)
+    })
+}
+
+/// Selects the collection the cycle-build-order hotkey walks (`None` = whole library).
+#[tauri::command]
+pub fn set_active_collection(
+    id: Option<String>,
+    state: State<AppState>,
+    app: AppHandle,
+) -> Result<(), String> {
+    update_collections(&state, &app, |store| store.set_active(id))
+}
+
+/// Ordered build ids the cycle-build-order hotkey should step through.
+#[tauri::command]
+pub fn get_cycle_build_order_ids(state: State<AppState>) -> Result<Vec<String>, String> {
+    let orders = state.build_orders.lock().map_err(|e| e.to_string())?;
+    let store = state.collections.lock().map_err(|e| e.to_string())?;
+    Ok(store.cycle_build_order_ids(&orders))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collections_changed_event_name() {
+        assert_eq!(COLLECTIONS_CHANGED_EVENT, "collections-changed");
+    }
+}
@@ -1,7 +1,14 @@
-use crate::config::{atomic_write, get_config_path, AppConfig};
+use super::profiles::persist_profiles;
+use super::ui::{apply_content_protection, place_overlay};
+use crate::config::{
+    atomic_write, changed_paths, get_config_path, normalize_config,
+    patch_config as apply_config_patch, validate_config, AppConfig, ConfigChange, ConfigLoadReport,
+};
 use crate::hotkeys::register_hotkeys;
 use crate::state::AppState;
-use tauri::{AppHandle, Emitter, State};
+use serde_json::Value;
+use std::sync::atomic::Ordering;
+use tauri::{AppHandle, Emitter, Manager, State};
 
 pub(super) const CONFIG_CHANGED_EVENT: &str = "config-changed";
 
@@ -11,23 +18,203 @@ pub fn get_config(state: State<AppState>) -> Result<AppConfig, String> {
     Ok(config.clone())
 }
 
+/// Revision of the live config, for windows that load it with `get_config`
+/// and later save with `patch_config(.., expected_revision)`.
+#[tauri::command]
+pub fn get_config_revision(state: State<AppState>) -> u64 {
+    state.config_revision.load(Ordering::SeqCst)
+}
+
+/// The config the overlay runs with: the global config, the active profile
+/// and, when `build_id` is given, that build's settings overrides.
+#[tauri::command]
+pub fn get_effective_config(
+    build_id: Option<String>,
+    state: State<AppState>,
+) -> Result<AppConfig, String> {
+    let config = state.config.lock().map_err(|e| e.to_string())?;
+    let Some(build_id) = build_id else {
+        return Ok(config.clone());
+    };
+    let metadata = state.user_metadata.lock().map_err(|e| e.to_string())?;
+    Ok(metadata.effective_config(&config, &build_id))
+}
+
+/// Whether `config.json` loaded cleanly at startup, which fields were reset
+/// and where the damaged original was backed up.
+#[tauri::command]
+pub fn get_config_diagnostics(state: State<AppState>) -> Result<ConfigLoadReport, String> {
+    let report = state.config_load_report.lock().map_err(|e| e.to_string())?;
+    Ok(report.clone())
+}
+
+/// Makes an already validated `live` the running config, bumps the revision
+/// and broadcasts both with the paths that changed. Returns the config it
+/// replaced, for `apply_config_effects`. `current` is the caller's guard on
+/// `state.config`.
+pub(super) fn install_live_config(
+    state: &AppState,
+    current: &mut AppConfig,
+    live: AppConfig,
+    app: &AppHandle,
+) -> Result<AppConfig, String> {
+    let changed_paths = changed_paths(current, &live);
+    let previous = std::mem::replace(current, live);
+    let revision = state.config_revision.fetch_add(1, Ordering::SeqCst) + 1;
+    // Notify all windows that config changed so they can refresh state
+    let change = ConfigChange {
+        revision,
+        changed_paths,
+        config: current.clone(),
+    };
+    app.emit(CONFIG_CHANGED_EVENT, &change)
+        .map_err(|e| e.to_string())?;
+    Ok(previous)
+}
+
+/// Validates an edited live config, splits it between `config.json` and the
+/// active profile, writes both and installs it. Out-of-range values are
+/// clamped, so the broadcast carries the values actually stored; unusable
+/// ones fail with per-field errors and leave everything untouched.
+/// `current` is the caller's guard on `state.config`.
+pub(super) fn commit_config(
+    state: &AppState,
+    current: &mut AppConfig,
+    mut next: AppConfig,
+    app: &AppHandle,
+) -> Result<AppConfig, String> {
+    validate_config(&mut next)?;
+    let mut global = state.global_config.lock().map_err(|e| e.to_string())?;
+    let mut profiles = state.profiles.lock().map_err(|e| e.to_string())?;
+    let mut next_profiles = profiles.clone();
+    let next_global = next_profiles.split(&global, &next)?;
+
+    let json = serde_json::to_string_pretty(&next_global).map_err(|e| e.to_string())?;
+    atomic_write(get_config_path(), json).map_err(|e| e.to_string())?;
+    if next_profiles != *profiles {
+        persist_profiles(&next_profiles, app)?;
+        *profiles = next_profiles;
+    }
+    *global = next_global;
+    install_live_config(state, current, next, app)
+}
+
+/// Applies what changed between two live configs outside the webviews:
+/// hotkeys, click-through, capture protection and docking. Call with the
+/// config lock released; re-registering hotkeys reads it.
+pub(super) fn apply_config_effects(
+    app: &AppHandle,
+    previous: &AppConfig,
+    current: &AppConfig,
+) -> Result<(), String> {
+    if current.hotkeys != previous.hotkeys {
+        register_hotkeys(app)?;
+    }
+    if let Some(window) = app.get_webview_window("overlay") {
+        if current.click_through != previous.click_through {
+            window
+                .set_ignore_cursor_events(current.click_through)
+                .map_err(|e| e.to_string())?;
+        }
+        if current.content_protection != previous.content_protection {
+            apply_content_protection(&window, current.content_protection)?;
+        }
+    }
+    if current.overlay_position != previous.overlay_position {
+        place_overlay(app, current.overlay_position)?;
+    }
+    Ok(())
+}
+
 #[tauri::command]
 pub fn save_config(
     config: AppConfig,
     state: State<AppState>,
     app: AppHandle,
 ) -> Result<(), String> {
-    let config_path = get_config_path();
-    let json = serde_json::to_string_pretty(&config).map_err(|e| e.to_string())?;
-    atomic_write(&config_path, json).map_err(|e| e.to_string())?;
+    let (previous, current) = {
+        let mut current = state.config.lock().map_err(|e| e.to_string())?;
+        let previous = commit_config(&state, &mut current, config, &app)?;
+        (previous, current.clone())
+    };
+    apply_config_effects(&app, &previous, &current)
+}
 
-    let mut current = state.config.lock().map_err(|e| e.to_string())?;
-    *current = config;
+/// Applies a JSON merge patch to the live config under the lock, so windows
+/// changing different settings never overwrite each other. With
+/// `expected_revision`, the patch is rejected if the config changed since the
+/// caller last saw it. Unchanged settings are neither written nor broadcast.
+#[tauri::command]
+pub fn patch_config(
+    patch: Value,
+    expected_revision: Option<u64>,
+    state: State<AppState>,
+    app: AppHandle,
+) -> Result<ConfigChange, String> {
+    let (previous, change) = {
+        let mut current = state.config.lock().map_err(|e| e.to_string())?;
+        let revision = state.config_revision.load(Ordering::SeqCst);
+        if let Some(expected) = expected_revision {
+            if expected != revision {
+                return Err(format!(
+                    "Config changed since revision {} (now {}); reload and retry",
+                    expected, revision
+                ));
+            }
+        }
 
-    // Notify all windows that config changed so they can refresh state
-    app.emit(CONFIG_CHANGED_EVENT, &*current)
-        .map_err(|e| e.to_string())?;
-    Ok(())
+        let mut next = apply_config_patch(&current, &patch)?;
+        validate_config(&mut next)?;
+        let changed_paths = changed_paths(&current, &next);
+        if changed_paths.is_empty() {
+            return Ok(ConfigChange {
+                revision,
+                changed_paths,
+                config: current.clone(),
+            });
+        }
+
+        let previous = commit_config(&state, &mut current, next, &app)?;
+        let change = ConfigChange {
+            revision: state.config_revision.load(Ordering::SeqCst),
+            changed_paths,
+            config: current.clone(),
+        };
+        (previous, change)
+    };
+    apply_config_effects(&app, &previous, &change.config)?;
+    Ok(change)
+}
+
+/// Installs `global` read back from `config.json` after an edit made outside
+/// the app, re-resolving the active profile on top and applying hotkeys,
+/// click-through and capture protection like a save would. The file is not
+/// rewritten. Returns false when `global` is the config already stored, as
+/// after the app's own writes.
+pub(crate) fn apply_external_config(app: &AppHandle, global: AppConfig) -> Result<bool, String> {
+    let state = app.state::<AppState>();
+    let (previous, current) = {
+        let mut config = state.config.lock().map_err(|e| e.to_string())?;
+        let mut stored = state.global_config.lock().map_err(|e| e.to_string())?;
+        let profiles = state.profiles.lock().map_err(|e| e.to_string())?;
+
+        let unchanged = serde_json::to_value(&*stored).map_err(|e| e.to_string())?
+            == serde_json::to_value(&global).map_err(|e| e.to_string())?;
+        if unchanged {
+            return Ok(false);
+        }
+
+        let mut live = profiles.resolve(&global).unwrap_or_else(|e| {
+            eprintln!("Ignoring active profile: {}", e);
+            global.clone()
+        });
+        normalize_config(&mut live);
+        *stored = global;
+        let previous = install_live_config(&state, &mut config, live, app)?;
+        (previous, config.clone())
+    };
+    apply_config_effects(app, &previous, &current)?;
+    Ok(true)
 }
 
 #[tauri::command]
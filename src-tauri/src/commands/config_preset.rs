@@ -0,0 +1,81 @@
+use super::config_commands::{apply_config_effects, commit_config};
+use crate::config::{
+    atomic_write, export_config_preset, plan_config_import, read_config_preset,
+    ConfigImportPreview, ConfigPreset, MAX_CONFIG_PRESET_SIZE,
+};
+use crate::state::AppState;
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, State};
+
+fn open_config_preset(path: &str) -> Result<ConfigPreset, String> {
+    let path = PathBuf::from(path);
+    let metadata = fs::metadata(&path).map_err(|e| format!("Cannot access file: {}", e))?;
+    if !metadata.is_file() {
+        return Err("Path must be a regular file".to_string());
+    }
+    if metadata.len() > MAX_CONFIG_PRESET_SIZE {
+        return Err(format!(
+            "File too large: {} bytes (max {} bytes)",
+            metadata.len(),
+            MAX_CONFIG_PRESET_SIZE
+        ));
+    }
+    let content = fs::read_to_string(&path).map_err(|e| format!("Failed to read file: {}", e))?;
+    read_config_preset(&content)
+}
+
+/// Writes the running config, or only `sections` of it (top-level keys such
+/// as "hotkeys", "voice" or "reminders"), to a preset file. Window placement
+/// and the OCR capture region are left out. Returns the exported sections.
+#[tauri::command]
+pub fn export_config(
+    path: String,
+    sections: Option<Vec<String>>,
+    state: State<AppState>,
+) -> Result<Vec<String>, String> {
+    let preset = {
+        let config = state.config.lock().map_err(|e| e.to_string())?;
+        export_config_preset(&config, sections.as_deref())?
+    };
+    let json = serde_json::to_string_pretty(&preset).map_err(|e| e.to_string())?;
+    atomic_write(&path, json).map_err(|e| format!("Failed to write file: {}", e))?;
+    Ok(preset.settings.keys().cloned().collect())
+}
+
+/// Reads a preset and reports what importing `sections` of it (all it has
+/// when None) would change, without changing anything.
+#[tauri::command]
+pub fn preview_config_import(
+    path: String,
+    sections: Option<Vec<String>>,
+    state: State<AppState>,
+) -> Result<ConfigImportPreview, String> {
+    let preset = open_config_preset(&path)?;
+    let config = state.config.lock().map_err(|e| e.to_string())?;
+    let (_, preview) = plan_config_import(&config, &preset, sections.as_deref())?;
+    Ok(preview)
+}
+
+/// Imports `sections` of a preset (all it has when None), saving and applying
+/// them like `save_config`. Returns what changed.
+#[tauri::command]
+pub fn import_config(
+    path: String,
+    sections: Option<Vec<String>>,
+    state: State<AppState>,
+    app: AppHandle,
+) -> Result<ConfigImportPreview, String> {
+    let preset = open_config_preset(&path)?;
+    let (previous, current, preview) = {
+        let mut config = state.config.lock().map_err(|e| e.to_string())?;
+        let (next, preview) = plan_config_import(&config, &preset, sections.as_deref())?;
+        if preview.changes.is_empty() {
+            return Ok(preview);
+        }
+        let previous = commit_config(&state, &mut config, next, &app)?;
+        (previous, config.clone(), preview)
+    };
+    apply_config_effects(&app, &previous, &current)?;
+    Ok(preview)
+}
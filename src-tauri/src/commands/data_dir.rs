@@ -0,0 +1,46 @@
+use crate::config::{
+    check_relocation_target, data_dir, data_dir_info, default_data_dir, relocate_data,
+    set_data_dir, DataDirInfo, DataDirSource, DATA_DIR_ENV,
+};
+use crate::state::AppState;
+use std::path::PathBuf;
+use tauri::State;
+
+/// Where config, profiles and build orders are stored and why.
+#[tauri::command]
+pub fn get_data_dir() -> DataDirInfo {
+    data_dir_info()
+}
+
+/// Copies all data to `target` and switches to it. The stores stay locked
+/// throughout, so no save lands in the old folder after the copy. If any file
+/// fails to copy, the partial copy is removed and the old folder stays in
+/// use. On success the old folder is kept untouched as a backup. Moving back
+/// to the default folder renames what was left there aside first.
+#[tauri::command]
+pub fn relocate_data_dir(target: String, state: State<AppState>) -> Result<DataDirInfo, String> {
+    let info = data_dir_info();
+    if !info.relocatable {
+        return Err(match info.source {
+            DataDirSource::Portable => "Data folder is fixed by portable mode".to_string(),
+            _ => format!("Data folder is set by {}", DATA_DIR_ENV),
+        });
+    }
+    let (current, _) = data_dir();
+    let target = PathBuf::from(target.trim());
+    check_relocation_target(&current, &target, &default_data_dir())?;
+
+    // Same order as everywhere else; held until the switch is recorded.
+    let _config = state.config.lock().map_err(|e| e.to_string())?;
+    let _global = state.global_config.lock().map_err(|e| e.to_string())?;
+    let _profiles = state.profiles.lock().map_err(|e| e.to_string())?;
+    let _orders = state.build_orders.lock().map_err(|e| e.to_string())?;
+    let _collections = state.collections.lock().map_err(|e| e.to_string())?;
+    let _plans = state.game_plans.lock().map_err(|e| e.to_string())?;
+    let _metadata = state.user_metadata.lock().map_err(|e| e.to_string())?;
+
+    if let Some(aside) = relocate_data(&current, &target, set_data_dir)? {
+        eprintln!("Moved previous contents of {:?} to {:?}", target, aside);
+    }
+    Ok(data_dir_info())
+}
@@ -0,0 +1,12 @@
+use crate::config::BuildOrder;
+use crate::economy::{self, EconomyData, EconomyReport};
+
+/// Projects the economy of `order` (saved or still being edited) and flags
+/// steps whose timing the villager allocation cannot support.
+#[tauri::command]
+pub fn simulate_build_order_economy(order: BuildOrder) -> Result<EconomyReport, String> {
+    if order.steps.is_empty() {
+        return Err("Build order has no steps".to_string());
+    }
+    Ok(economy::simulate_economy(&order, EconomyData::bundled()?))
+}
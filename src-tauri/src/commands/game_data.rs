@@ -0,0 +1,81 @@
+use crate::game_data::{EntityCategory, GameDataDiff, GameDatabase, GameEntityEntry};
+use crate::state::AppState;
+use tauri::State;
+
+/// `requested`, or the content version the app is configured for.
+fn resolve_content_version(
+    requested: Option<String>,
+    state: &State<AppState>,
+) -> Result<String, String> {
+    match requested.filter(|v| !v.trim().is_empty()) {
+        Some(version) => Ok(version),
+        None => Ok(state
+            .config
+            .lock()
+            .map_err(|e| e.to_string())?
+            .asset_content_version
+            .clone()),
+    }
+}
+
+#[tauri::command]
+pub fn get_game_data_versions() -> Result<Vec<String>, String> {
+    Ok(GameDatabase::bundled()?.versions.keys().cloned().collect())
+}
+
+#[tauri::command]
+pub fn get_game_entity(
+    id: String,
+    content_version: Option<String>,
+    state: State<AppState>,
+) -> Result<GameEntityEntry, String> {
+    let version = resolve_content_version(content_version, &state)?;
+    let (version, snapshot) = GameDatabase::bundled()?.resolve(&version);
+    let (category, entity) = snapshot
+        .find(&id)
+        .ok_or_else(|| format!("Unknown unit, building or technology \"{}\"", id))?;
+    Ok(GameEntityEntry {
+        content_version: version.to_string(),
+        category,
+        entity: entity.clone(),
+    })
+}
+
+/// Lists entities, optionally only one category and only those available to
+/// `civilization`.
+#[tauri::command]
+pub fn list_game_entities(
+    category: Option<EntityCategory>,
+    civilization: Option<String>,
+    content_version: Option<String>,
+    state: State<AppState>,
+) -> Result<Vec<GameEntityEntry>, String> {
+    let version = resolve_content_version(content_version, &state)?;
+    let (version, snapshot) = GameDatabase::bundled()?.resolve(&version);
+    Ok(snapshot
+        .entities()
+        .filter(|(c, _)| category.is_none_or(|wanted| *c == wanted))
+        .filter(|(_, e)| {
+            civilization
+                .as_deref()
+                .is_none_or(|civ| e.available_to(civ))
+        })
+        .map(|(category, entity)| GameEntityEntry {
+            content_version: version.to_string(),
+            category,
+            entity: entity.clone(),
+        })
+        .collect())
+}
+
+/// Entries added, removed or changed between two content versions. `to_version`
+/// defaults to the configured one.
+#[tauri::command]
+pub fn diff_game_data(
+    from_version: String,
+    to_version: Option<String>,
+    state: State<AppState>,
+) -> Result<GameDataDiff, String> {
+    let to_version = resolve_content_version(to_version, &state)?;
+    GameDatabase::bundled()?.diff(&from_version, &to_version)
+}
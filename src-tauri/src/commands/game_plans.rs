@@ -0,0 +1,133 @@
+use crate::config::{
+    atomic_write, get_game_plans_path, GamePlan, GamePlanPosition, GamePlanStage, GamePlanStore,
+    StepDirection,
+};
+use crate::state::AppState;
+use tauri::{AppHandle, Emitter, State};
+
+pub(super) const GAME_PLANS_CHANGED_EVENT: &str = "game-plans-changed";
+
+/// Writes the game plan store to disk and broadcasts it to all windows.
+pub(super) fn persist_game_plans(store: &GamePlanStore, app: &AppHandle) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(store).map_err(|e| e.to_string())?;
+    atomic_write(get_game_plans_path(), json).map_err(|e| e.to_string())?;
+    app.emit(GAME_PLANS_CHANGED_EVENT, store)
+        .map_err(|e| e.to_string())
+}
+
+/// Applies `update` to the game plan store under the lock, then persists and
+/// broadcasts the result. Nothing is written if `update` fails.
+fn update_game_plans<T, F>(state: &State<AppState>, app: &AppHandle, update: F) -> Result<T, String>
+where
+    F: FnOnce(&mut GamePlanStore) -> Result<T, String>,
+{
+    let mut store = state.game_plans.lock().map_err(|e| e.to_string())?;
+    let mut next = store.clone();
+    let result = update(&mut next)?;
+    persist_game_plans(&next, app)?;
+    *store = next;
+    Ok(result)
+}
+
+#[tauri::command]
+pub fn get_game_plans(state: State<AppState>) -> Result<GamePlanStore, String> {
+    let store = state.game_plans.lock().map_err(|e| e.to_string())?;
+    Ok(store.clone())
+}
+
+#[tauri::command]
+pub fn create_game_plan(
+    name: String,
+    civilization: String,
+    state: State<AppState>,
+    app: AppHandle,
+) -> Result<GamePlan, String> {
+    update_game_plans(&state, &app, |store| store.create(&name, &civilization))
+}
+
+#[tauri::command]
+pub fn rename_game_plan(
+    id: String,
+    name: String,
+    state: State<AppState>,
+    app: AppHandle,
+) -> Result<(), String> {
+    update_game_plans(&state, &app, |store| store.rename(&id, &name))
+}
+
+#[tauri::command]
+pub fn delete_game_plan(id: String, state: State<AppState>, app: AppHandle) -> Result<(), String> {
+    update_game_plans(&state, &app, |store| store.delete(&id))
+}
+
+/// Replaces a plan's ordered stages after checking every build exists and
+/// matches the plan's civilization.
+#[tauri::command]
+pub fn set_game_plan_stages(
+    id: String,
+    stages: Vec<GamePlanStage>,
+    state: State<AppState>,
+    app: AppHandle,
+) -> Result<(), String> {
+    let orders = state.build_orders.lock().map_err(|e| e.to_string())?;
+    update_game_plans(&state, &app, |store| store.set_stages(&id, stages, &orders))
+}
+
+/// Problems with a stored plan now, e.g. after one of its builds changed civ.
+#[tauri::command]
+pub fn validate_game_plan(id: String, state: State<AppState>) -> Result<Vec<String>, String> {
+    let orders = state.build_orders.lock().map_err(|e| e.to_string())?;
+    let store = state.game_plans.lock().map_err(|e| e.to_string())?;
+    let plan = store
+        .plans
+        .iter()
+        .find(|p| p.id == id)
+        .ok_or_else(|| format!("Game plan \"{}\" not found", id))?;
+    Ok(plan.problems(&orders))
+}
+
+/// Selects the plan next/previous step follow across builds (`None` = off).
+#[tauri::command]
+pub fn set_active_game_plan(
+    id: Option<String>,
+    state: State<AppState>,
+    app: AppHandle,
+) -> Result<(), String> {
+    update_game_plans(&state, &app, |store| store.set_active(id))
+}
+
+/// Where a next/previous step press lands when it crosses into another stage
+/// of the active plan, given the match timer. None means navigate inside the
+/// current build as usual.
+#[tauri::command]
+pub fn navigate_game_plan(
+    build_order_id: String,
+    step_index: usize,
+    active_branch_id: Option<String>,
+    direction: StepDirection,
+    elapsed_seconds: Option<u32>,
+    state: State<AppState>,
+) -> Result<Option<GamePlanPosition>, String> {
+    let orders = state.build_orders.lock().map_err(|e| e.to_string())?;
+    let store = state.game_plans.lock().map_err(|e| e.to_string())?;
+    Ok(store.active_plan().and_then(|plan| {
+        plan.navigate(
+            &build_order_id,
+            step_index,
+            active_branch_id.as_deref(),
+            direction,
+            elapsed_seconds,
+            &orders,
+        )
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_game_plans_changed_event_name() {
+        assert_eq!(GAME_PLANS_CHANGED_EVENT, "game-plans-changed");
+    }
+}
@@ -1,12 +1,42 @@
+mod attachments;
 mod build_order;
+mod bundle;
+mod collections;
 mod config_commands;
+mod config_preset;
+mod data_dir;
+mod economy;
+mod game_data;
+mod game_plans;
+mod profiles;
+mod recommendation;
+mod search;
+mod stats;
+mod step_inference;
+mod team;
 mod ui;
+mod user_metadata;
 mod window;
 
 // Re-export all commands
+pub use attachments::*;
 pub use build_order::*;
+pub use bundle::*;
+pub use collections::*;
 pub use config_commands::*;
+pub use config_preset::*;
+pub use data_dir::*;
+pub use economy::*;
+pub use game_data::*;
+pub use game_plans::*;
+pub use profiles::*;
+pub use recommendation::*;
+pub use search::*;
+pub use stats::*;
+pub use step_inference::*;
+pub use team::*;
 pub use ui::*;
+pub use user_metadata::*;
 pub use window::*;
 
 #[cfg(test)]
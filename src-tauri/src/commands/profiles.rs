@@ -0,0 +1,120 @@
+use super::config_commands::{apply_config_effects, install_live_config};
+use crate::config::{
+    atomic_write, get_profiles_path, validate_config, AppConfig, ConfigProfile, ProfileStore,
+};
+use crate::state::AppState;
+use crate::tray::refresh_tray_profiles;
+use tauri::{AppHandle, Emitter, State};
+
+pub(super) const PROFILES_CHANGED_EVENT: &str = "profiles-changed";
+
+/// Writes the profile store to disk, broadcasts it and rebuilds the tray's
+/// profile submenu.
+pub(super) fn persist_profiles(store: &ProfileStore, app: &AppHandle) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(store).map_err(|e| e.to_string())?;
+    atomic_write(get_profiles_path(), json).map_err(|e| e.to_string())?;
+    app.emit(PROFILES_CHANGED_EVENT, store)
+        .map_err(|e| e.to_string())?;
+    refresh_tray_profiles(app, store);
+    Ok(())
+}
+
+/// Applies `update` to the profile store under the lock, then re-resolves the
+/// live config against the global one and installs it the way `save_config`
+/// does, hotkeys included. Nothing changes if `update` or validation fails.
+fn update_profiles<T, F>(state: &State<AppState>, app: &AppHandle, update: F) -> Result<T, String>
+where
+    F: FnOnce(&mut ProfileStore, &AppConfig) -> Result<T, String>,
+{
+    let (result, previous, current) = {
+        let mut config = state.config.lock().map_err(|e| e.to_string())?;
+        let global = state.global_config.lock().map_err(|e| e.to_string())?;
+        let mut profiles = state.profiles.lock().map_err(|e| e.to_string())?;
+
+        let mut next = profiles.clone();
+        let result = update(&mut next, &config)?;
+        let mut live = next.resolve(&global)?;
+        validate_config(&mut live)?;
+
+        persist_profiles(&next, app)?;
+        *profiles = next;
+        let previous = install_live_config(state, &mut config, live, app)?;
+        (result, previous, config.clone())
+    };
+    apply_config_effects(app, &previous, &current)?;
+    Ok(result)
+}
+
+#[tauri::command]
+pub fn get_profiles(state: State<AppState>) -> Result<ProfileStore, String> {
+    let profiles = state.profiles.lock().map_err(|e| e.to_string())?;
+    Ok(profiles.clone())
+}
+
+/// Saves the current settings as a profile: only `fields` (top-level config
+/// keys such as "voice" or "overlay_opacity") for a partial profile, or
+/// everything when omitted.
+#[tauri::command]
+pub fn create_profile(
+    name: String,
+    fields: Option<Vec<String>>,
+    state: State<AppState>,
+    app: AppHandle,
+) -> Result<ConfigProfile, String> {
+    update_profiles(&state, &app, |store, current| {
+        store.create(&name, fields.as_deref(), current)
+    })
+}
+
+#[tauri::command]
+pub fn duplicate_profile(
+    id: String,
+    name: String,
+    state: State<AppState>,
+    app: AppHandle,
+) -> Result<ConfigProfile, String> {
+    update_profiles(&state, &app, |store, _| store.duplicate(&id, &name))
+}
+
+#[tauri::command]
+pub fn rename_profile(
+    id: String,
+    name: String,
+    state: State<AppState>,
+    app: AppHandle,
+) -> Result<(), String> {
+    update_profiles(&state, &app, |store, _| store.rename(&id, &name))
+}
+
+/// Deleting the active profile falls back to the global config.
+#[tauri::command]
+pub fn delete_profile(id: String, state: State<AppState>, app: AppHandle) -> Result<(), String> {
+    update_profiles(&state, &app, |store, _| store.delete(&id))
+}
+
+/// Layers a profile on the global config (`None` = global config only).
+#[tauri::command]
+pub fn activate_profile(
+    id: Option<String>,
+    state: State<AppState>,
+    app: AppHandle,
+) -> Result<(), String> {
+    update_profiles(&state, &app, |store, _| store.set_active(id))
+}
+
+/// Switches to the next profile, for the cycle-profile hotkey. Returns the
+/// now active profile id.
+#[tauri::command]
+pub fn cycle_profile(state: State<AppState>, app: AppHandle) -> Result<Option<String>, String> {
+    update_profiles(&state, &app, |store, _| Ok(store.cycle()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profiles_changed_event_name() {
+        assert_eq!(PROFILES_CHANGED_EVENT, "profiles-changed");
+    }
+}
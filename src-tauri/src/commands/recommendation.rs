@@ -0,0 +1,21 @@
+use crate::recommendation::{self, Recommendation, RecommendationQuery};
+use crate::state::AppState;
+use tauri::State;
+
+/// Ranks the player's openers for a matchup so the right one can be picked
+/// during the loading screen.
+#[tauri::command]
+pub fn recommend_build_orders(
+    query: RecommendationQuery,
+    state: State<AppState>,
+) -> Result<Vec<Recommendation>, String> {
+    if query.civilization.trim().is_empty() {
+        return Err("Civilization is required".to_string());
+    }
+    let orders = state.build_orders.lock().map_err(|e| e.to_string())?;
+    let metadata = state.user_metadata.lock().map_err(|e| e.to_string())?;
+    Ok(recommendation::recommend_build_orders(
+        &metadata.merge_all(&orders),
+        &query,
+    ))
+}
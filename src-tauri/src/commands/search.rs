@@ -0,0 +1,17 @@
+use crate::search::{SearchQuery, SearchResults};
+use crate::state::AppState;
+use tauri::State;
+
+/// Searches the build order library with full-text matching, filters, sorting
+/// and pagination, so the webview only receives the page it renders.
+#[tauri::command]
+pub fn search_build_orders(
+    query: SearchQuery,
+    state: State<AppState>,
+) -> Result<SearchResults, String> {
+    let orders = state.build_orders.lock().map_err(|e| e.to_string())?;
+    let index = state.search_index.lock().map_err(|e| e.to_string())?;
+    // Pinned/favorite filters read the user's flags, not the build files
+    let metadata = state.user_metadata.lock().map_err(|e| e.to_string())?;
+    Ok(index.search(&metadata.merge_all(&orders), &query))
+}
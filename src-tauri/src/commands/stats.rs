@@ -0,0 +1,18 @@
+use crate::state::AppState;
+use crate::stats::{self, BuildOrderStats};
+use tauri::State;
+
+/// Derived metrics (age-up times, villager counts, step density, branches)
+/// for a library build.
+#[tauri::command]
+pub fn get_build_order_stats(
+    id: String,
+    state: State<AppState>,
+) -> Result<BuildOrderStats, String> {
+    let orders = state.build_orders.lock().map_err(|e| e.to_string())?;
+    let order = orders
+        .iter()
+        .find(|o| o.id == id)
+        .ok_or_else(|| format!("Build order \"{}\" does not exist", id))?;
+    Ok(stats::compute_build_order_stats(order))
+}
@@ -0,0 +1,27 @@
+use crate::config::BuildOrder;
+use crate::game_data::GameDatabase;
+use crate::state::AppState;
+use crate::step_inference::{self, StepResourceSuggestion};
+use tauri::State;
+
+/// Proposes icons, and reports the cost, for steps from the units, buildings and
+/// technologies they mention. Nothing is applied; the author picks what to keep.
+#[tauri::command]
+pub fn suggest_step_resources(
+    order: BuildOrder,
+    include_existing: Option<bool>,
+    state: State<AppState>,
+) -> Result<Vec<StepResourceSuggestion>, String> {
+    let content_version = state
+        .config
+        .lock()
+        .map_err(|e| e.to_string())?
+        .asset_content_version
+        .clone();
+    let (_, snapshot) = GameDatabase::bundled()?.resolve(&content_version);
+    Ok(step_inference::suggest_step_resources(
+        &order,
+        snapshot,
+        include_existing.unwrap_or(false),
+    ))
+}
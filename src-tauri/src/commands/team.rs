@@ -0,0 +1,31 @@
+use super::build_order::served_build_order;
+use crate::config::BuildOrder;
+use crate::state::AppState;
+use crate::team::{self, CheckpointIssue};
+use tauri::State;
+
+/// One player's view of a library team build, playable like any solo build.
+/// The view has its own id, so it carries none of the team build's user
+/// metadata and saving it never overwrites the team build.
+#[tauri::command]
+pub fn get_team_role_view(
+    id: String,
+    role_id: String,
+    state: State<AppState>,
+) -> Result<BuildOrder, String> {
+    let orders = state.build_orders.lock().map_err(|e| e.to_string())?;
+    let metadata = state.user_metadata.lock().map_err(|e| e.to_string())?;
+    let order = orders
+        .iter()
+        .find(|o| o.id == id)
+        .ok_or_else(|| format!("Build order \"{}\" does not exist", id))?;
+    let view = team::extract_role_view(order, &role_id)?;
+    served_build_order(&view, &metadata)
+}
+
+/// Checkpoints whose steps do not line up across roles, for the build being
+/// edited (saved or not).
+#[tauri::command]
+pub fn check_team_checkpoints(order: BuildOrder) -> Vec<CheckpointIssue> {
+    team::checkpoint_issues(&order)
+}
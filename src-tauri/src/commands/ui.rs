@@ -1,7 +1,7 @@
-use super::config_commands::CONFIG_CHANGED_EVENT;
-use crate::config::{atomic_write, get_config_path, AppConfig};
+use super::config_commands::commit_config;
+use crate::config::{AppConfig, OverlayPosition};
 use crate::state::AppState;
-use tauri::{AppHandle, Emitter, Manager, State, Window};
+use tauri::{AppHandle, Manager, State, Window};
 
 #[tauri::command]
 pub fn set_click_through(window: Window, enabled: bool) -> Result<(), String> {
@@ -21,19 +21,12 @@ where
     F: FnOnce(&mut AppConfig) -> &mut bool,
 {
     let mut config = state.config.lock().map_err(|e| e.to_string())?;
-    let field = toggle_field(&mut config);
+    let mut next = config.clone();
+    let field = toggle_field(&mut next);
     *field = !*field;
     let new_state = *field;
 
-    // Save to file
-    let config_path = get_config_path();
-    let json = serde_json::to_string_pretty(&*config).map_err(|e| e.to_string())?;
-    atomic_write(&config_path, json).map_err(|e| e.to_string())?;
-
-    // Broadcast config change
-    app.emit(CONFIG_CHANGED_EVENT, &*config)
-        .map_err(|e| e.to_string())?;
-
+    commit_config(state, &mut config, next, app)?;
     Ok(new_state)
 }
 
@@ -56,10 +49,49 @@ pub fn toggle_compact_mode(app: AppHandle, state: State<AppState>) -> Result<boo
     toggle_config_bool(&state, &app, |c| &mut c.compact_mode)
 }
 
+/// Docks the overlay to the corner `position` names on its current monitor's
+/// work area. `Custom` leaves the window where the user dragged it.
+pub(super) fn place_overlay(app: &AppHandle, position: OverlayPosition) -> Result<(), String> {
+    let Some(window) = app.get_webview_window("overlay") else {
+        return Ok(());
+    };
+    let Some(monitor) = window.current_monitor().map_err(|e| e.to_string())? else {
+        return Ok(());
+    };
+    let area = monitor.work_area();
+    let size = window.outer_size().map_err(|e| e.to_string())?;
+    let monitor_rect = (
+        area.position.x,
+        area.position.y,
+        area.size.width,
+        area.size.height,
+    );
+    match position.place(monitor_rect, (size.width, size.height)) {
+        Some((x, y)) => window
+            .set_position(tauri::Position::Physical(tauri::PhysicalPosition { x, y }))
+            .map_err(|e| e.to_string()),
+        None => Ok(()),
+    }
+}
+
+/// Re-docks the overlay to the configured corner, e.g. after a resolution change.
+#[tauri::command]
+pub fn apply_overlay_position(app: AppHandle, state: State<AppState>) -> Result<(), String> {
+    let position = state
+        .config
+        .lock()
+        .map_err(|e| e.to_string())?
+        .overlay_position;
+    place_overlay(&app, position)
+}
+
 /// Applies content protection (exclude-from-capture) to a live overlay window.
 /// Uses the native `WDA_EXCLUDEFROMCAPTURE` path on Windows and Tauri's
 /// cross-platform `set_content_protected` elsewhere.
-fn apply_content_protection(window: &tauri::WebviewWindow, enabled: bool) -> Result<(), String> {
+pub(super) fn apply_content_protection(
+    window: &tauri::WebviewWindow,
+    enabled: bool,
+) -> Result<(), String> {
     #[cfg(target_os = "windows")]
     {
         crate::windows::set_content_protection(window, enabled)
@@ -84,12 +116,11 @@ pub fn set_content_protection(
     // Persist + broadcast so both windows stay in sync.
     {
         let mut config = state.config.lock().map_err(|e| e.to_string())?;
-        config.content_protection = enabled;
-        let config_path = get_config_path();
-        let json = serde_json::to_string_pretty(&*config).map_err(|e| e.to_string())?;
-        atomic_write(&config_path, json).map_err(|e| e.to_string())?;
-        app.emit(CONFIG_CHANGED_EVENT, &*config)
-            .map_err(|e| e.to_string())?;
+        let next = AppConfig {
+            content_protection: enabled,
+            ..config.clone()
+        };
+        commit_config(&state, &mut config, next, &app)?;
     }
 
     // Apply to the live overlay window.
@@ -0,0 +1,159 @@
+use super::build_order::broadcast_build_orders;
+use crate::config::{
+    save_user_metadata, validate_notes, validate_step_annotation, BuildSettingsOverrides,
+    UserBuildMetadata, UserMetadataStore,
+};
+use crate::state::AppState;
+use tauri::{AppHandle, State};
+
+/// Applies `update` to the metadata store for an existing build, persists it,
+/// and re-broadcasts the merged library. Nothing is written if `update` fails.
+fn update_user_metadata<F>(
+    id: &str,
+    state: &State<AppState>,
+    app: &AppHandle,
+    update: F,
+) -> Result<UserBuildMetadata, String>
+where
+    F: FnOnce(&mut UserMetadataStore) -> Result<(), String>,
+{
+    let orders = state.build_orders.lock().map_err(|e| e.to_string())?;
+    if !orders.iter().any(|o| o.id == id) {
+        return Err(format!("Build order \"{}\" does not exist", id));
+    }
+
+    let mut metadata = state.user_metadata.lock().map_err(|e| e.to_string())?;
+    let mut next = metadata.clone();
+    update(&mut next)?;
+    save_user_metadata(&next)?;
+    *metadata = next;
+
+    broadcast_build_orders(app, &orders, &metadata)?;
+    Ok(metadata.get(id))
+}
+
+/// Sets the pinned and/or favorite flag. Pinning one build unpins the rest.
+#[tauri::command]
+pub fn set_build_order_flags(
+    id: String,
+    pinned: Option<bool>,
+    favorite: Option<bool>,
+    state: State<AppState>,
+    app: AppHandle,
+) -> Result<UserBuildMetadata, String> {
+    update_user_metadata(&id, &state, &app, |store| {
+        if let Some(pinned) = pinned {
+            store.set_pinned(&id, pinned);
+        }
+        if let Some(favorite) = favorite {
+            store.update(&id, |m| m.favorite = favorite);
+        }
+        Ok(())
+    })
+}
+
+#[tauri::command]
+pub fn set_build_order_notes(
+    id: String,
+    notes: Option<String>,
+    state: State<AppState>,
+    app: AppHandle,
+) -> Result<UserBuildMetadata, String> {
+    let notes = notes.filter(|n| !n.trim().is_empty());
+    if let Some(notes) = &notes {
+        validate_notes(notes)?;
+    }
+    update_user_metadata(&id, &state, &app, |store| {
+        store.update(&id, |m| m.notes = notes);
+        Ok(())
+    })
+}
+
+/// Sets or clears (`None`/blank) the user's annotation on one step.
+#[tauri::command]
+pub fn set_step_annotation(
+    id: String,
+    step_id: String,
+    annotation: Option<String>,
+    state: State<AppState>,
+    app: AppHandle,
+) -> Result<UserBuildMetadata, String> {
+    let annotation = annotation.filter(|a| !a.trim().is_empty());
+    if let Some(annotation) = &annotation {
+        validate_step_annotation(annotation)?;
+    }
+    {
+        let orders = state.build_orders.lock().map_err(|e| e.to_string())?;
+        let has_step = orders
+            .iter()
+            .filter(|o| o.id == id)
+            .flat_map(|o| {
+                o.steps.iter().chain(
+                    o.branches
+                        .iter()
+                        .flatten()
+                        .flat_map(|branch| branch.steps.iter()),
+                )
+            })
+            .any(|step| step.id == step_id);
+        if !has_step && orders.iter().any(|o| o.id == id) {
+            return Err(format!("Step \"{}\" not found in build order", step_id));
+        }
+    }
+    update_user_metadata(&id, &state, &app, |store| {
+        store.update(&id, |m| match annotation {
+            Some(annotation) => {
+                m.step_annotations.insert(step_id, annotation);
+            }
+            None => {
+                m.step_annotations.remove(&step_id);
+            }
+        });
+        Ok(())
+    })
+}
+
+/// Bumps the play count and last-played time when a session starts.
+#[tauri::command]
+pub fn record_build_order_played(
+    id: String,
+    state: State<AppState>,
+    app: AppHandle,
+) -> Result<UserBuildMetadata, String> {
+    update_user_metadata(&id, &state, &app, |store| {
+        store.record_played(&id);
+        Ok(())
+    })
+}
+
+/// Records the outcome of a game played with this build, used to rank
+/// recommendations for the same matchup.
+#[tauri::command]
+pub fn record_build_order_result(
+    id: String,
+    opponent_civilization: Option<String>,
+    won: bool,
+    state: State<AppState>,
+    app: AppHandle,
+) -> Result<UserBuildMetadata, String> {
+    update_user_metadata(&id, &state, &app, |store| {
+        store.record_result(&id, opponent_civilization.as_deref(), won);
+        Ok(())
+    })
+}
+
+/// Replaces the settings this build overrides; an empty set makes it follow
+/// the config again. Values are clamped like the config's own.
+#[tauri::command]
+pub fn set_build_settings_overrides(
+    id: String,
+    overrides: BuildSettingsOverrides,
+    state: State<AppState>,
+    app: AppHandle,
+) -> Result<UserBuildMetadata, String> {
+    let overrides = overrides.validated()?;
+    update_user_metadata(&id, &state, &app, |store| {
+        store.update(&id, |m| m.settings_overrides = overrides);
+        Ok(())
+    })
+}
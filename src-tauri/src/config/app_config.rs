@@ -1,3 +1,6 @@
+use super::appearance::{FontSize, OverlayPosition, OverlayPreset, Theme};
+use super::difficulty::{deserialize_difficulty_filter, DifficultyLevel};
+use super::migrations::CONFIG_SCHEMA_VERSION;
 use serde::{Deserialize, Serialize};
 
 pub const MAX_BUILD_ORDER_STEPS: usize = 200;
@@ -5,13 +8,16 @@ pub const MAX_BUILD_ORDER_STEPS: usize = 200;
 // Configuration types
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct AppConfig {
+    /// Layout version of the stored file; older files are migrated on load.
+    #[serde(default = "default_schema_version", rename = "schemaVersion")]
+    pub schema_version: u32,
     pub overlay_opacity: f64,
     #[serde(default = "default_ui_scale")]
     pub ui_scale: f64,
-    pub font_size: String,
-    pub theme: String,
+    pub font_size: FontSize,
+    pub theme: Theme,
     #[serde(default = "default_overlay_preset")]
-    pub overlay_preset: String,
+    pub overlay_preset: OverlayPreset,
     #[serde(
         default = "default_asset_content_version",
         rename = "assetContentVersion"
@@ -30,9 +36,10 @@ pub struct AppConfig {
     pub content_protection: bool,
     pub auto_advance: AutoAdvanceConfig,
     pub filter_civilization: Option<String>,
-    pub filter_difficulty: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_difficulty_filter")]
+    pub filter_difficulty: Option<DifficultyLevel>,
     #[serde(default)]
-    pub overlay_position: String,
+    pub overlay_position: OverlayPosition,
     #[serde(default)]
     pub floating_style: bool,
     #[serde(default)]
@@ -53,7 +60,7 @@ pub struct AppConfig {
     pub game_detection: Option<GameDetectionConfig>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct HotkeyConfig {
     pub toggle_overlay: String,
     pub previous_step: String,
@@ -76,38 +83,49 @@ pub struct HotkeyConfig {
     pub activate_branch_4: String,
     #[serde(default = "default_toggle_counters")]
     pub toggle_counters: String,
+    /// Switches to the next config profile, then back to the global config.
+    #[serde(default = "default_cycle_profile")]
+    pub cycle_profile: String,
 }
 
 fn default_branch_main() -> String {
-    "Alt+0".to_string()
+    "Ctrl+Alt+0".to_string()
 }
 fn default_branch_1() -> String {
-    "Alt+1".to_string()
+    "Ctrl+Alt+1".to_string()
 }
 fn default_branch_2() -> String {
-    "Alt+2".to_string()
+    "Ctrl+Alt+2".to_string()
 }
 fn default_branch_3() -> String {
-    "Alt+3".to_string()
+    "Ctrl+Alt+3".to_string()
 }
 fn default_branch_4() -> String {
-    "Alt+4".to_string()
+    "Ctrl+Alt+4".to_string()
 }
 
 fn default_toggle_counters() -> String {
     "Ctrl+Alt+TAB".to_string()
 }
 
+fn default_cycle_profile() -> String {
+    "Ctrl+Alt+F9".to_string()
+}
+
 fn default_toggle_pause() -> String {
     "Ctrl+Alt+F8".to_string()
 }
 
+fn default_schema_version() -> u32 {
+    CONFIG_SCHEMA_VERSION
+}
+
 fn default_ui_scale() -> f64 {
     1.0
 }
 
-fn default_overlay_preset() -> String {
-    "build-order".to_string()
+fn default_overlay_preset() -> OverlayPreset {
+    OverlayPreset::BuildOrder
 }
 
 fn default_asset_content_version() -> String {
@@ -126,13 +144,13 @@ pub struct WindowSize {
     pub height: u32,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct AutoAdvanceConfig {
     pub enabled: bool,
     pub delay_seconds: u32,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct VoiceConfig {
     pub enabled: bool,
@@ -140,6 +158,10 @@ pub struct VoiceConfig {
     pub speak_steps: bool,
     pub speak_reminders: bool,
     pub speak_delta: bool,
+    /// Abbreviation → spoken-form rules applied by the Rust TTS path before the
+    /// text reaches the platform speech engine. Seeded with common AoE4 shorthand.
+    #[serde(default = "default_pronunciations")]
+    pub pronunciations: Vec<PronunciationEntry>,
 }
 
 impl Default for VoiceConfig {
@@ -150,28 +172,65 @@ impl Default for VoiceConfig {
             speak_steps: true,
             speak_reminders: true,
             speak_delta: true,
+            pronunciations: default_pronunciations(),
         }
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+/// One entry of the user-editable pronunciation dictionary.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PronunciationEntry {
+    /// Text as written in step descriptions (e.g. "vils", "TC", "->").
+    pub term: String,
+    /// Replacement handed to the speech engine (e.g. "villagers").
+    pub spoken: String,
+    #[serde(default)]
+    pub case_sensitive: bool,
+}
+
+fn default_pronunciations() -> Vec<PronunciationEntry> {
+    [
+        ("vils", "villagers"),
+        ("vills", "villagers"),
+        ("vil", "villager"),
+        ("vill", "villager"),
+        ("TCs", "town centers"),
+        ("TC", "town center"),
+        ("FC", "fast castle"),
+        ("FI", "fast imperial"),
+        ("eco", "economy"),
+        ("->", "to"),
+        ("→", "to"),
+        ("+", "and"),
+    ]
+    .into_iter()
+    .map(|(term, spoken)| PronunciationEntry {
+        term: term.to_string(),
+        spoken: spoken.to_string(),
+        case_sensitive: false,
+    })
+    .collect()
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct ReminderItemConfig {
     pub enabled: bool,
     pub interval_seconds: u32,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct SacredSitesConfig {
     pub enabled: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct MatchupAlertsConfig {
     pub enabled: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct ReminderConfig {
     pub enabled: bool,
@@ -203,7 +262,7 @@ fn default_matchup_alerts() -> MatchupAlertsConfig {
     MatchupAlertsConfig { enabled: true }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct UpgradeBadgeConfig {
     pub id: String,
@@ -213,7 +272,7 @@ pub struct UpgradeBadgeConfig {
     pub enabled: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct UpgradeBadgesConfig {
     pub enabled: bool,
@@ -372,10 +431,11 @@ impl Default for ReminderConfig {
 impl Default for AppConfig {
     fn default() -> Self {
         Self {
+            schema_version: CONFIG_SCHEMA_VERSION,
             overlay_opacity: 0.8,
             ui_scale: default_ui_scale(),
-            font_size: "medium".to_string(),
-            theme: "dark".to_string(),
+            font_size: FontSize::Medium,
+            theme: Theme::Dark,
             overlay_preset: default_overlay_preset(),
             asset_content_version: default_asset_content_version(),
             hotkeys: HotkeyConfig {
@@ -393,6 +453,7 @@ impl Default for AppConfig {
                 activate_branch_3: "Ctrl+Alt+3".to_string(),
                 activate_branch_4: "Ctrl+Alt+4".to_string(),
                 toggle_counters: "Ctrl+Alt+TAB".to_string(),
+                cycle_profile: default_cycle_profile(),
             },
             window_position: None,
             window_size: None,
@@ -406,7 +467,7 @@ impl Default for AppConfig {
             },
             filter_civilization: None,
             filter_difficulty: None,
-            overlay_position: "top-right".to_string(),
+            overlay_position: OverlayPosition::TopRight,
             floating_style: true,
             voice: Some(VoiceConfig::default()),
             reminders: Some(ReminderConfig::default()),
@@ -433,8 +494,8 @@ mod tests {
     fn test_default_config() {
         let config = AppConfig::default();
         assert_eq!(config.overlay_opacity, 0.8);
-        assert_eq!(config.font_size, "medium");
-        assert_eq!(config.theme, "dark");
+        assert_eq!(config.font_size, FontSize::Medium);
+        assert_eq!(config.theme, Theme::Dark);
         assert!(!config.click_through);
         assert!(!config.compact_mode);
         assert_eq!(config.hotkeys.toggle_overlay, "Ctrl+Alt+F1");
@@ -446,6 +507,17 @@ mod tests {
         assert!(voice.enabled);
         assert_eq!(voice.rate, 1.0);
         assert!(voice.speak_steps);
+        assert!(voice
+            .pronunciations
+            .iter()
+            .any(|p| p.term == "vils" && p.spoken == "villagers"));
+    }
+
+    #[test]
+    fn test_voice_config_without_pronunciations_gets_defaults() {
+        let json = r#"{"enabled":true,"rate":1.0,"speakSteps":true,"speakReminders":true,"speakDelta":false}"#;
+        let voice: VoiceConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(voice.pronunciations, default_pronunciations());
     }
 
     #[test]
@@ -453,6 +525,16 @@ mod tests {
         assert_eq!(default_toggle_pause(), "Ctrl+Alt+F8");
     }
 
+    #[test]
+    fn test_branch_hotkey_serde_defaults_match_default_config() {
+        let hotkeys = AppConfig::default().hotkeys;
+        assert_eq!(default_branch_main(), hotkeys.activate_branch_main);
+        assert_eq!(default_branch_1(), hotkeys.activate_branch_1);
+        assert_eq!(default_branch_2(), hotkeys.activate_branch_2);
+        assert_eq!(default_branch_3(), hotkeys.activate_branch_3);
+        assert_eq!(default_branch_4(), hotkeys.activate_branch_4);
+    }
+
     #[test]
     fn test_default_ui_scale() {
         assert_eq!(default_ui_scale(), 1.0);
@@ -460,7 +542,7 @@ mod tests {
 
     #[test]
     fn test_default_overlay_preset() {
-        assert_eq!(default_overlay_preset(), "build-order");
+        assert_eq!(default_overlay_preset(), OverlayPreset::BuildOrder);
     }
 
     #[test]
@@ -512,6 +594,7 @@ mod tests {
         assert_eq!(config.hotkeys.reset_build_order, "Ctrl+Alt+F7");
         assert_eq!(config.hotkeys.toggle_pause, "Ctrl+Alt+F8");
         assert_eq!(config.hotkeys.toggle_counters, "Ctrl+Alt+TAB");
+        assert_eq!(config.hotkeys.cycle_profile, "Ctrl+Alt+F9");
     }
 
     #[test]
@@ -535,6 +618,18 @@ mod tests {
         assert!(config.filter_difficulty.is_none());
     }
 
+    #[test]
+    fn test_filter_difficulty_reads_labels_leniently() {
+        let mut value = serde_json::to_value(AppConfig::default()).unwrap();
+        value["filter_difficulty"] = serde_json::json!("Hard");
+        let config: AppConfig = serde_json::from_value(value.clone()).unwrap();
+        assert_eq!(config.filter_difficulty, Some(DifficultyLevel::Advanced));
+
+        value["filter_difficulty"] = serde_json::json!("Spicy");
+        let config: AppConfig = serde_json::from_value(value).unwrap();
+        assert!(config.filter_difficulty.is_none());
+    }
+
     #[test]
     fn test_max_build_order_steps_constant() {
         assert_eq!(MAX_BUILD_ORDER_STEPS, 200);
@@ -627,6 +722,7 @@ mod tests {
             activate_branch_3: "3".to_string(),
             activate_branch_4: "4".to_string(),
             toggle_counters: "Ctrl+Alt+TAB".to_string(),
+            cycle_profile: "Ctrl+Alt+F10".to_string(),
         };
         assert_eq!(config.toggle_overlay, "Ctrl+Alt+F1");
         assert_eq!(config.toggle_pause, "Ctrl+Alt+F8");
@@ -0,0 +1,242 @@
+//! Enumerated appearance settings. Each reads leniently: case, spaces,
+//! `-`/`_` and a few legacy spellings are accepted, and anything else falls
+//! back to the default with a warning instead of failing the whole config.
+
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+
+/// Lowercase letters and digits only, so "Top_Right" and "topRight" compare equal.
+fn squash(value: &str) -> String {
+    value
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+fn parse_lenient<T: Copy + Default + Debug>(field: &str, raw: &str, names: &[(&str, T)]) -> T {
+    let key = squash(raw);
+    if key.is_empty() {
+        return T::default();
+    }
+    match names.iter().find(|(name, _)| *name == key) {
+        Some((_, value)) => *value,
+        None => {
+            let fallback = T::default();
+            eprintln!(
+                "Unknown {} \"{}\" in config; using {:?}",
+                field, raw, fallback
+            );
+            fallback
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase", from = "String")]
+pub enum FontSize {
+    Small,
+    #[default]
+    Medium,
+    Large,
+}
+
+impl From<String> for FontSize {
+    fn from(raw: String) -> Self {
+        parse_lenient(
+            "font_size",
+            &raw,
+            &[
+                ("small", Self::Small),
+                ("sm", Self::Small),
+                ("medium", Self::Medium),
+                ("md", Self::Medium),
+                ("normal", Self::Medium),
+                ("large", Self::Large),
+                ("lg", Self::Large),
+            ],
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase", from = "String")]
+pub enum Theme {
+    #[default]
+    Dark,
+    Light,
+    System,
+}
+
+impl From<String> for Theme {
+    fn from(raw: String) -> Self {
+        parse_lenient(
+            "theme",
+            &raw,
+            &[
+                ("dark", Self::Dark),
+                ("light", Self::Light),
+                ("system", Self::System),
+                ("auto", Self::System),
+            ],
+        )
+    }
+}
+
+/// Screen corner the overlay docks to; `Custom` keeps `window_position`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", from = "String")]
+pub enum OverlayPosition {
+    TopLeft,
+    #[default]
+    TopRight,
+    BottomLeft,
+    BottomRight,
+    Custom,
+}
+
+impl From<String> for OverlayPosition {
+    fn from(raw: String) -> Self {
+        parse_lenient(
+            "overlay_position",
+            &raw,
+            &[
+                ("topleft", Self::TopLeft),
+                ("topright", Self::TopRight),
+                ("bottomleft", Self::BottomLeft),
+                ("bottomright", Self::BottomRight),
+                ("custom", Self::Custom),
+            ],
+        )
+    }
+}
+
+/// Gap between a docked overlay and the monitor edges, in physical pixels.
+pub const OVERLAY_EDGE_MARGIN: i32 = 16;
+
+impl OverlayPosition {
+    /// Top-left corner for a `window` (width, height) docked on a monitor
+    /// whose work area is `monitor` (x, y, width, height). None for `Custom`.
+    pub fn place(&self, monitor: (i32, i32, u32, u32), window: (u32, u32)) -> Option<(i32, i32)> {
+        let (mx, my, mw, mh) = monitor;
+        let left = mx + OVERLAY_EDGE_MARGIN;
+        let top = my + OVERLAY_EDGE_MARGIN;
+        // Never past the left/top margin, even if the window is wider than the monitor.
+        let right = (mx + mw as i32 - window.0 as i32 - OVERLAY_EDGE_MARGIN).max(left);
+        let bottom = (my + mh as i32 - window.1 as i32 - OVERLAY_EDGE_MARGIN).max(top);
+        match self {
+            OverlayPosition::TopLeft => Some((left, top)),
+            OverlayPosition::TopRight => Some((right, top)),
+            OverlayPosition::BottomLeft => Some((left, bottom)),
+            OverlayPosition::BottomRight => Some((right, bottom)),
+            OverlayPosition::Custom => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(from = "String")]
+pub enum OverlayPreset {
+    #[serde(rename = "minimal")]
+    Minimal,
+    #[serde(rename = "coach")]
+    Coach,
+    #[default]
+    #[serde(rename = "build-order")]
+    BuildOrder,
+    #[serde(rename = "matchup")]
+    Matchup,
+    #[serde(rename = "stream")]
+    Stream,
+    #[serde(rename = "info_dense")]
+    InfoDense,
+}
+
+impl From<String> for OverlayPreset {
+    fn from(raw: String) -> Self {
+        parse_lenient(
+            "overlay_preset",
+            &raw,
+            &[
+                ("minimal", Self::Minimal),
+                ("coach", Self::Coach),
+                ("buildorder", Self::BuildOrder),
+                ("matchup", Self::Matchup),
+                ("stream", Self::Stream),
+                ("infodense", Self::InfoDense),
+                ("dense", Self::InfoDense),
+            ],
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse<T: serde::de::DeserializeOwned>(json: &str) -> T {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_lenient_legacy_spellings() {
+        assert_eq!(parse::<FontSize>("\"Large\""), FontSize::Large);
+        assert_eq!(parse::<Theme>("\"auto\""), Theme::System);
+        assert_eq!(
+            parse::<OverlayPosition>("\"Bottom_Left\""),
+            OverlayPosition::BottomLeft
+        );
+        assert_eq!(
+            parse::<OverlayPosition>("\"topRight\""),
+            OverlayPosition::TopRight
+        );
+        assert_eq!(
+            parse::<OverlayPreset>("\"info-dense\""),
+            OverlayPreset::InfoDense
+        );
+    }
+
+    #[test]
+    fn test_unknown_values_fall_back_to_default() {
+        assert_eq!(parse::<FontSize>("\"huge\""), FontSize::Medium);
+        assert_eq!(parse::<Theme>("\"solarized\""), Theme::Dark);
+        assert_eq!(parse::<OverlayPosition>("\"\""), OverlayPosition::TopRight);
+        assert_eq!(parse::<OverlayPreset>("\"zen\""), OverlayPreset::BuildOrder);
+    }
+
+    #[test]
+    fn test_serializes_to_frontend_values() {
+        let json = serde_json::to_string(&(
+            FontSize::Small,
+            Theme::System,
+            OverlayPosition::BottomRight,
+            OverlayPreset::BuildOrder,
+            OverlayPreset::InfoDense,
+        ))
+        .unwrap();
+        assert_eq!(
+            json,
+            r#"["small","system","bottom-right","build-order","info_dense"]"#
+        );
+    }
+
+    #[test]
+    fn test_place_docks_to_corners() {
+        let monitor = (1920, 0, 1920, 1080);
+        let window = (400, 300);
+        assert_eq!(
+            OverlayPosition::TopLeft.place(monitor, window),
+            Some((1936, 16))
+        );
+        assert_eq!(
+            OverlayPosition::BottomRight.place(monitor, window),
+            Some((3424, 764))
+        );
+        assert_eq!(OverlayPosition::Custom.place(monitor, window), None);
+        // A window larger than the monitor stays at the top-left margin.
+        assert_eq!(
+            OverlayPosition::BottomRight.place((0, 0, 300, 200), window),
+            Some((16, 16))
+        );
+    }
+}
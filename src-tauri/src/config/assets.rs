@@ -0,0 +1,382 @@
+use super::build_order::{BuildOrder, BuildOrderStep};
+use super::persistence::{atomic_write, get_build_orders_dir};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeSet;
+use std::fs;
+use std::io::{ErrorKind, Read};
+use std::path::{Path, PathBuf};
+
+pub const MAX_ATTACHMENT_SIZE: u64 = 5 * 1024 * 1024; // 5MB limit
+pub const MAX_ATTACHMENTS_PER_STEP: usize = 4;
+pub const MAX_ATTACHMENT_CAPTION_LEN: usize = 200;
+const MAX_ATTACHMENT_STEM_LEN: usize = 80;
+
+/// Image formats accepted as step attachments, identified by content rather
+/// than by the extension the user's file happened to have.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageType {
+    Png,
+    Jpeg,
+    Gif,
+    Webp,
+}
+
+impl ImageType {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ImageType::Png => "png",
+            ImageType::Jpeg => "jpg",
+            ImageType::Gif => "gif",
+            ImageType::Webp => "webp",
+        }
+    }
+
+    fn from_extension(ext: &str) -> Option<Self> {
+        match ext {
+            "png" => Some(ImageType::Png),
+            "jpg" | "jpeg" => Some(ImageType::Jpeg),
+            "gif" => Some(ImageType::Gif),
+            "webp" => Some(ImageType::Webp),
+            _ => None,
+        }
+    }
+
+    /// Sniffs the magic bytes at the start of `bytes`.
+    pub fn detect(bytes: &[u8]) -> Option<Self> {
+        if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+            Some(ImageType::Png)
+        } else if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+            Some(ImageType::Jpeg)
+        } else if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+            Some(ImageType::Gif)
+        } else if bytes.len() >= 12 && &bytes[..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+            Some(ImageType::Webp)
+        } else {
+            None
+        }
+    }
+}
+
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Folder holding one build's attachment files. Not created until needed.
+pub fn get_build_assets_dir(build_order_id: &str) -> PathBuf {
+    get_build_orders_dir().join("assets").join(build_order_id)
+}
+
+/// Attachment names are plain file names (`[A-Za-z0-9_-]+.<image ext>`), so
+/// they can never point outside the build's assets folder.
+pub fn validate_attachment_file_name(name: &str) -> Result<(), String> {
+    let invalid = || format!("Invalid attachment file name \"{}\"", name);
+    let (stem, ext) = name.rsplit_once('.').ok_or_else(invalid)?;
+    if stem.is_empty()
+        || stem.len() > MAX_ATTACHMENT_STEM_LEN
+        || !stem
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        || ImageType::from_extension(ext).is_none()
+    {
+        return Err(invalid());
+    }
+    Ok(())
+}
+
+/// Checks size and content type of an image about to be stored.
+pub fn validate_attachment_image(bytes: &[u8]) -> Result<ImageType, String> {
+    if bytes.len() as u64 > MAX_ATTACHMENT_SIZE {
+        return Err(format!(
+            "Image too large: {} bytes (max {} bytes)",
+            bytes.len(),
+            MAX_ATTACHMENT_SIZE
+        ));
+    }
+    ImageType::detect(bytes)
+        .ok_or_else(|| "Unsupported image type (use PNG, JPEG, GIF or WebP)".to_string())
+}
+
+pub fn validate_step_attachments(step: &BuildOrderStep) -> Result<(), String> {
+    let Some(attachments) = &step.attachments else {
+        return Ok(());
+    };
+    if attachments.len() > MAX_ATTACHMENTS_PER_STEP {
+        return Err(format!(
+            "Step \"{}\" exceeds maximum of {} attachments",
+            step.id, MAX_ATTACHMENTS_PER_STEP
+        ));
+    }
+    for attachment in attachments {
+        validate_attachment_file_name(&attachment.file_name)?;
+        if attachment
+            .caption
+            .as_ref()
+            .is_some_and(|c| c.chars().count() > MAX_ATTACHMENT_CAPTION_LEN)
+        {
+            return Err(format!(
+                "Attachment caption exceeds max length of {} characters",
+                MAX_ATTACHMENT_CAPTION_LEN
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Content-addressed name for a new attachment: the same image attached twice
+/// to a step is stored once.
+pub fn attachment_file_name(step_id: &str, bytes: &[u8], image_type: ImageType) -> String {
+    let mut stem: String = step_id
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '_')
+        .take(40)
+        .collect();
+    if stem.is_empty() {
+        stem = "step".to_string();
+    }
+    format!(
+        "{}-{}.{}",
+        stem,
+        &sha256_hex(bytes)[..12],
+        image_type.extension()
+    )
+}
+
+/// Reads an image from outside the library, refusing oversized files before
+/// loading them, and checks its content type.
+pub fn read_attachment_image(path: &Path) -> Result<(Vec<u8>, ImageType), String> {
+    let metadata = fs::metadata(path).map_err(|e| format!("Cannot access file: {}", e))?;
+    if !metadata.is_file() {
+        return Err("Path must be a regular file".to_string());
+    }
+    if metadata.len() > MAX_ATTACHMENT_SIZE {
+        return Err(format!(
+            "Image too large: {} bytes (max {} bytes)",
+            metadata.len(),
+            MAX_ATTACHMENT_SIZE
+        ));
+    }
+    let mut bytes = Vec::new();
+    fs::File::open(path)
+        .and_then(|file| file.take(MAX_ATTACHMENT_SIZE + 1).read_to_end(&mut bytes))
+        .map_err(|e| format!("Failed to read file: {}", e))?;
+    let image_type = validate_attachment_image(&bytes)?;
+    Ok((bytes, image_type))
+}
+
+/// Writes an attachment into the build's assets folder.
+pub fn store_attachment(build_order_id: &str, file_name: &str, bytes: &[u8]) -> Result<(), String> {
+    validate_attachment_file_name(file_name)?;
+    let dir = get_build_assets_dir(build_order_id);
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    atomic_write(dir.join(file_name), bytes).map_err(|e| e.to_string())
+}
+
+/// Reads a stored attachment of a library build, if present and still valid.
+pub fn load_attachment(build_order_id: &str, file_name: &str) -> Option<Vec<u8>> {
+    validate_attachment_file_name(file_name).ok()?;
+    let (bytes, _) =
+        read_attachment_image(&get_build_assets_dir(build_order_id).join(file_name)).ok()?;
+    Some(bytes)
+}
+
+/// Folder written next to a single exported build file (`rush.json` ->
+/// `rush-assets/`) so its images travel with it.
+pub fn sidecar_assets_dir(json_path: &Path) -> PathBuf {
+    let stem = json_path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("build-order");
+    json_path.with_file_name(format!("{}-assets", stem))
+}
+
+/// Drops attachments for which `keep` returns false, recording a warning on
+/// the build for each one.
+pub fn retain_attachments<F>(order: &mut BuildOrder, mut keep: F)
+where
+    F: FnMut(&str) -> bool,
+{
+    let mut dropped = Vec::new();
+    for step in all_steps_mut(order) {
+        if let Some(attachments) = step.attachments.as_mut() {
+            attachments.retain(|a| {
+                let kept = keep(&a.file_name);
+                if !kept {
+                    dropped.push(format!(
+                        "Attachment \"{}\" on step \"{}\" could not be imported",
+                        a.file_name, step.id
+                    ));
+                }
+                kept
+            });
+            if attachments.is_empty() {
+                step.attachments = None;
+            }
+        }
+    }
+    if !dropped.is_empty() {
+        order.warnings.get_or_insert_with(Vec::new).extend(dropped);
+    }
+}
+
+/// Every step (base and branches) of `order`, mutably.
+pub fn all_steps_mut(order: &mut BuildOrder) -> impl Iterator<Item = &mut BuildOrderStep> {
+    order.steps.iter_mut().chain(
+        order
+            .branches
+            .iter_mut()
+            .flatten()
+            .flat_map(|branch| branch.steps.iter_mut()),
+    )
+}
+
+/// File names referenced by any step of `order`.
+pub fn referenced_attachments(order: &BuildOrder) -> BTreeSet<String> {
+    order
+        .steps
+        .iter()
+        .chain(
+            order
+                .branches
+                .iter()
+                .flatten()
+                .flat_map(|branch| branch.steps.iter()),
+        )
+        .flat_map(|step| step.attachments.iter().flatten())
+        .map(|a| a.file_name.clone())
+        .collect()
+}
+
+/// Deletes files in the build's assets folder that no step references any
+/// more, and the folder itself once empty.
+pub fn remove_orphaned_assets(order: &BuildOrder) -> std::io::Result<()> {
+    let dir = get_build_assets_dir(&order.id);
+    let entries = match fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e),
+    };
+
+    let referenced = referenced_attachments(order);
+    let mut remaining = 0;
+    for entry in entries.flatten() {
+        let keep = entry
+            .file_name()
+            .to_str()
+            .is_some_and(|name| referenced.contains(name));
+        if keep {
+            remaining += 1;
+        } else if entry.path().is_file() {
+            fs::remove_file(entry.path())?;
+        }
+    }
+    if remaining == 0 {
+        fs::remove_dir(&dir).ok();
+    }
+    Ok(())
+}
+
+pub fn remove_build_assets(build_order_id: &str) -> std::io::Result<()> {
+    match fs::remove_dir_all(get_build_assets_dir(build_order_id)) {
+        Err(e) if e.kind() != ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::StepAttachment;
+
+    const PNG: &[u8] = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+
+    fn step_with(count: usize) -> BuildOrderStep {
+        BuildOrderStep {
+            id: "s1".to_string(),
+            description: "Wall".to_string(),
+            attachments: Some(
+                (0..count)
+                    .map(|i| StepAttachment {
+                        file_name: format!("s1-{}.png", i),
+                        caption: None,
+                    })
+                    .collect(),
+            ),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_detect_image_type() {
+        assert_eq!(ImageType::detect(PNG), Some(ImageType::Png));
+        assert_eq!(
+            ImageType::detect(&[0xFF, 0xD8, 0xFF, 0xE0]),
+            Some(ImageType::Jpeg)
+        );
+        assert_eq!(ImageType::detect(b"GIF89a..."), Some(ImageType::Gif));
+        assert_eq!(
+            ImageType::detect(b"RIFF\0\0\0\0WEBPVP8 "),
+            Some(ImageType::Webp)
+        );
+        assert_eq!(ImageType::detect(b"<svg></svg>"), None);
+    }
+
+    #[test]
+    fn test_validate_attachment_image_limits() {
+        assert!(validate_attachment_image(PNG).is_ok());
+        assert!(validate_attachment_image(b"not an image").is_err());
+        let mut huge = PNG.to_vec();
+        huge.resize(MAX_ATTACHMENT_SIZE as usize + 1, 0);
+        assert!(validate_attachment_image(&huge)
+            .unwrap_err()
+            .contains("too large"));
+    }
+
+    #[test]
+    fn test_validate_attachment_file_name() {
+        assert!(validate_attachment_file_name("step-1-abc123.png").is_ok());
+        assert!(validate_attachment_file_name("map.jpeg").is_ok());
+        for bad in [
+            "../evil.png",
+            "dir/evil.png",
+            "evil.svg",
+            "evil",
+            ".png",
+            "a b.png",
+            "evil.PNG.exe",
+        ] {
+            assert!(validate_attachment_file_name(bad).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_validate_step_attachments() {
+        assert!(validate_step_attachments(&step_with(MAX_ATTACHMENTS_PER_STEP)).is_ok());
+        assert!(validate_step_attachments(&step_with(MAX_ATTACHMENTS_PER_STEP + 1)).is_err());
+
+        let mut step = step_with(1);
+        step.attachments.as_mut().unwrap()[0].caption =
+            Some("c".repeat(MAX_ATTACHMENT_CAPTION_LEN + 1));
+        assert!(validate_step_attachments(&step).is_err());
+    }
+
+    #[test]
+    fn test_sidecar_assets_dir() {
+        assert_eq!(
+            sidecar_assets_dir(Path::new("/tmp/exports/rush.json")),
+            PathBuf::from("/tmp/exports/rush-assets")
+        );
+    }
+
+    #[test]
+    fn test_attachment_file_name_is_content_addressed() {
+        let a = attachment_file_name("step 1/..", PNG, ImageType::Png);
+        assert_eq!(a, attachment_file_name("step 1/..", PNG, ImageType::Png));
+        assert!(a.starts_with("step1-"));
+        assert!(a.ends_with(".png"));
+        assert!(validate_attachment_file_name(&a).is_ok());
+        assert!(attachment_file_name("", PNG, ImageType::Png).starts_with("step-"));
+    }
+}
@@ -1,6 +1,15 @@
 use super::app_config::MAX_BUILD_ORDER_STEPS;
+use super::assets::validate_step_attachments;
+use super::difficulty::DifficultyEstimate;
+use super::user_metadata::UserBuildMetadata;
 use serde::{Deserialize, Serialize};
 
+pub const MAX_BUILD_ORDER_TAGS: usize = 20;
+pub const MAX_TAG_LEN: usize = 32;
+pub const MAX_MATCHUP_TARGETS: usize = 24;
+/// Largest team a build can coordinate (4v4).
+pub const MAX_TEAM_ROLES: usize = 4;
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct BuildOrderBranch {
@@ -12,7 +21,7 @@ pub struct BuildOrderBranch {
     pub steps: Vec<BuildOrderStep>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
 pub struct BuildOrder {
     pub id: String,
     pub name: String,
@@ -25,6 +34,15 @@ pub struct BuildOrder {
     pub pinned: bool,
     #[serde(default)]
     pub favorite: bool,
+    /// Free-form labels ("cheese", "ranked", "tower rush") used for search and filtering.
+    #[serde(default)]
+    pub tags: Option<Vec<String>>,
+    /// Opponent civilizations this opener is meant for. None means "any".
+    #[serde(default, rename = "opponentCivilizations")]
+    pub opponent_civilizations: Option<Vec<String>>,
+    /// Opponent strategies this opener answers ("tower rush", "fast castle").
+    #[serde(default, rename = "opponentStrategies")]
+    pub opponent_strategies: Option<Vec<String>>,
     #[serde(default)]
     pub branches: Option<Vec<BuildOrderBranch>>,
     #[serde(default)]
@@ -33,6 +51,35 @@ pub struct BuildOrder {
     pub content_version: Option<String>,
     #[serde(default)]
     pub warnings: Option<Vec<String>>,
+    /// Personal data merged in from `user-metadata.json` when served to the UI.
+    /// Never written to build files; see `strip_user_data`.
+    #[serde(
+        default,
+        rename = "userMetadata",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub user_metadata: Option<UserBuildMetadata>,
+    /// Derived from the steps on load and save; `difficulty` stays the
+    /// author's label.
+    #[serde(
+        default,
+        rename = "difficultyEstimate",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub difficulty_estimate: Option<DifficultyEstimate>,
+    /// Player roles of a team build ("P1 Feudal rush", "P2 Fast castle"). None
+    /// for solo builds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub roles: Option<Vec<TeamRole>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamRole {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -46,20 +93,84 @@ pub struct BuildOrderSourceMetadata {
     pub raw_civilization: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
 pub struct BuildOrderStep {
     pub id: String,
     pub description: String,
     pub timing: Option<String>,
     pub resources: Option<Resources>,
+    /// Text read aloud instead of `description` when voice coaching announces
+    /// this step. Lets authors keep terse visual text ("5 vils -> gold").
+    #[serde(default)]
+    pub speech: Option<String>,
+    /// Images (map screenshots, placement diagrams) stored in the build's
+    /// assets folder; see `get_build_assets_dir`.
+    #[serde(default)]
+    pub attachments: Option<Vec<StepAttachment>>,
+    /// `description` split into text and canonical icons, filled in when the
+    /// library is served to the UI. Never written to build files.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub segments: Option<Vec<DescriptionSegment>>,
+    /// Team builds: the role (`TeamRole::id`) that plays this step. None means
+    /// every player does.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub role: Option<String>,
+    /// Team builds: a coordination point ("feudal-hit") that every role reaches
+    /// at the same time; see `team::checkpoint_issues`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checkpoint: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum DescriptionSegment {
+    Text {
+        text: String,
+    },
+    /// `id` is the canonical icon id; `known` is false for ids that are not in
+    /// the icon registry.
+    Icon {
+        id: String,
+        known: bool,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct StepAttachment {
+    /// File name inside the build's assets folder, never a path.
+    pub file_name: String,
+    #[serde(default)]
+    pub caption: Option<String>,
+}
+
+/// Villagers assigned to each resource at a step, not resource amounts.
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
 pub struct Resources {
     pub food: Option<i32>,
     pub wood: Option<i32>,
     pub gold: Option<i32>,
     pub stone: Option<i32>,
+    /// Total villager count.
+    #[serde(default)]
+    pub villagers: Option<i32>,
+    /// Villagers building the current building or landmark.
+    #[serde(default)]
+    pub builders: Option<i32>,
+}
+
+/// The steps a player walks with `branch_id` active: the main line up to the
+/// branch's start, then the branch. No branch, or an unknown one, is the main
+/// line. Matches `resolveActiveSteps` in the overlay.
+pub fn active_steps<'a>(order: &'a BuildOrder, branch_id: Option<&str>) -> Vec<&'a BuildOrderStep> {
+    let branch = branch_id.and_then(|id| order.branches.iter().flatten().find(|b| b.id == id));
+    match branch {
+        Some(branch) => {
+            let start = (branch.start_step_index as usize).min(order.steps.len());
+            order.steps[..start].iter().chain(&branch.steps).collect()
+        }
+        None => order.steps.iter().collect(),
+    }
 }
 
 pub fn validate_build_order_id(id: &str) -> Result<(), String> {
@@ -82,6 +193,14 @@ pub fn validate_build_order_id(id: &str) -> Result<(), String> {
     Ok(())
 }
 
+/// Parses and validates a single build order file, as used by every import path.
+pub fn parse_build_order_json(content: &str) -> Result<BuildOrder, String> {
+    let order: BuildOrder =
+        serde_json::from_str(content).map_err(|e| format!("Invalid build order format: {}", e))?;
+    validate_build_order(&order)?;
+    Ok(order)
+}
+
 pub fn validate_build_order(order: &BuildOrder) -> Result<(), String> {
     validate_build_order_id(&order.id)?;
 
@@ -103,6 +222,21 @@ pub fn validate_build_order(order: &BuildOrder) -> Result<(), String> {
         if step.description.trim().is_empty() {
             return Err(format!("Step {} is missing a description", idx + 1));
         }
+        validate_step_attachments(step)?;
+        validate_step_team(step, &format!("Step {}", idx + 1), order)?;
+    }
+    if let Some(roles) = &order.roles {
+        validate_team_roles(roles)?;
+    }
+
+    if let Some(tags) = &order.tags {
+        validate_labels(tags, "tag", MAX_BUILD_ORDER_TAGS)?;
+    }
+    if let Some(civs) = &order.opponent_civilizations {
+        validate_labels(civs, "opponent civilization", MAX_MATCHUP_TARGETS)?;
+    }
+    if let Some(strategies) = &order.opponent_strategies {
+        validate_labels(strategies, "opponent strategy", MAX_MATCHUP_TARGETS)?;
     }
 
     if let Some(branches) = &order.branches {
@@ -130,6 +264,12 @@ pub fn validate_build_order(order: &BuildOrder) -> Result<(), String> {
                         idx + 1
                     ));
                 }
+                validate_step_attachments(step)?;
+                validate_step_team(
+                    step,
+                    &format!("Branch {} step {}", branch.name, idx + 1),
+                    order,
+                )?;
             }
         }
     }
@@ -137,6 +277,101 @@ pub fn validate_build_order(order: &BuildOrder) -> Result<(), String> {
     Ok(())
 }
 
+fn validate_team_roles(roles: &[TeamRole]) -> Result<(), String> {
+    if roles.is_empty() || roles.len() > MAX_TEAM_ROLES {
+        return Err(format!(
+            "A team build needs 1 to {} roles (has {})",
+            MAX_TEAM_ROLES,
+            roles.len()
+        ));
+    }
+    for (idx, role) in roles.iter().enumerate() {
+        if role.id.trim().is_empty() || role.name.trim().is_empty() {
+            return Err(format!("Role {} needs an id and a name", idx + 1));
+        }
+        if role.name.chars().count() > MAX_TAG_LEN {
+            return Err(format!(
+                "Role \"{}\" exceeds max length of {} characters",
+                role.name, MAX_TAG_LEN
+            ));
+        }
+        if roles[..idx].iter().any(|r| r.id == role.id) {
+            return Err(format!("Duplicate role id \"{}\"", role.id));
+        }
+    }
+    Ok(())
+}
+
+/// A step's role must be one the build declares; checkpoint names follow the
+/// tag length limit.
+fn validate_step_team(
+    step: &BuildOrderStep,
+    label: &str,
+    order: &BuildOrder,
+) -> Result<(), String> {
+    if let Some(role) = &step.role {
+        let declared = order
+            .roles
+            .as_ref()
+            .is_some_and(|roles| roles.iter().any(|r| r.id == *role));
+        if !declared {
+            return Err(format!("{} uses unknown role \"{}\"", label, role));
+        }
+    }
+    if let Some(checkpoint) = &step.checkpoint {
+        let trimmed = checkpoint.trim();
+        if trimmed.is_empty() || trimmed.chars().count() > MAX_TAG_LEN {
+            return Err(format!(
+                "{} checkpoint must be 1 to {} characters",
+                label, MAX_TAG_LEN
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Shared rules for short free-form label lists (tags, matchup targets):
+/// non-empty, at most `MAX_TAG_LEN` chars, no case-insensitive duplicates.
+fn validate_labels(labels: &[String], kind: &str, max_count: usize) -> Result<(), String> {
+    if labels.len() > max_count {
+        return Err(format!(
+            "Build order exceeds maximum of {} {} entries (has {})",
+            max_count,
+            kind,
+            labels.len()
+        ));
+    }
+    for (idx, label) in labels.iter().enumerate() {
+        let trimmed = label.trim();
+        if trimmed.is_empty() {
+            return Err(format!("{} {} is empty", capitalize(kind), idx + 1));
+        }
+        if trimmed.chars().count() > MAX_TAG_LEN {
+            return Err(format!(
+                "{} \"{}\" exceeds max length of {} characters",
+                capitalize(kind),
+                trimmed,
+                MAX_TAG_LEN
+            ));
+        }
+        if labels[..idx]
+            .iter()
+            .any(|l| l.trim().eq_ignore_ascii_case(trimmed))
+        {
+            return Err(format!("Duplicate {} \"{}\"", kind, trimmed));
+        }
+    }
+    Ok(())
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
 fn default_branch_start() -> u32 {
     0
 }
@@ -215,6 +450,7 @@ mod tests {
                 description: "First step".to_string(),
                 timing: Some("0:00".to_string()),
                 resources: None,
+                ..Default::default()
             }],
             enabled: true,
             pinned: false,
@@ -223,6 +459,7 @@ mod tests {
             source: None,
             content_version: None,
             warnings: None,
+            ..Default::default()
         }
     }
 
@@ -250,6 +487,7 @@ mod tests {
                 description: format!("Step {}", i),
                 timing: None,
                 resources: None,
+                ..Default::default()
             })
             .collect();
         assert!(validate_build_order(&order).is_ok());
@@ -264,6 +502,7 @@ mod tests {
                 description: format!("Step {}", i),
                 timing: None,
                 resources: None,
+                ..Default::default()
             })
             .collect();
         let result = validate_build_order(&order);
@@ -329,11 +568,36 @@ mod tests {
                 description: "Branch step".to_string(),
                 timing: None,
                 resources: None,
+                ..Default::default()
             }],
         }]);
         assert!(validate_build_order(&order).is_ok());
     }
 
+    #[test]
+    fn test_validate_build_order_team_roles() {
+        let mut order = create_valid_build_order();
+        order.steps[0].role = Some("p1".to_string());
+        let err = validate_build_order(&order).unwrap_err();
+        assert!(err.contains("unknown role \"p1\""), "{}", err);
+
+        let role = |id: &str| TeamRole {
+            id: id.to_string(),
+            name: format!("Player {}", id),
+            description: None,
+        };
+        order.roles = Some(vec![role("p1"), role("p2")]);
+        order.steps[0].checkpoint = Some("feudal-hit".to_string());
+        assert!(validate_build_order(&order).is_ok());
+
+        order.roles = Some(vec![role("p1"), role("p1")]);
+        assert!(validate_build_order(&order)
+            .unwrap_err()
+            .contains("Duplicate role id"));
+        order.roles = Some((0..=MAX_TEAM_ROLES).map(|i| role(&i.to_string())).collect());
+        assert!(validate_build_order(&order).is_err());
+    }
+
     #[test]
     fn test_validate_build_order_branch_exceeds_max_steps() {
         let mut order = create_valid_build_order();
@@ -348,6 +612,7 @@ mod tests {
                     description: format!("Step {}", i),
                     timing: None,
                     resources: None,
+                    ..Default::default()
                 })
                 .collect(),
         }]);
@@ -371,6 +636,7 @@ mod tests {
                 description: "Valid description".to_string(),
                 timing: None,
                 resources: None,
+                ..Default::default()
             }],
         }]);
         let result = validate_build_order(&order);
@@ -393,6 +659,7 @@ mod tests {
                 description: "".to_string(),
                 timing: None,
                 resources: None,
+                ..Default::default()
             }],
         }]);
         let result = validate_build_order(&order);
@@ -414,6 +681,7 @@ mod tests {
                     description: "Branch 1 step".to_string(),
                     timing: None,
                     resources: None,
+                    ..Default::default()
                 }],
             },
             BuildOrderBranch {
@@ -426,6 +694,7 @@ mod tests {
                     description: "Branch 2 step".to_string(),
                     timing: None,
                     resources: None,
+                    ..Default::default()
                 }],
             },
         ]);
@@ -451,6 +720,7 @@ mod tests {
             wood: Some(200),
             gold: Some(300),
             stone: Some(400),
+            ..Default::default()
         };
         assert_eq!(resources.food, Some(100));
         assert_eq!(resources.wood, Some(200));
@@ -465,6 +735,7 @@ mod tests {
             wood: None,
             gold: None,
             stone: None,
+            ..Default::default()
         };
         assert!(resources.food.is_some());
         assert!(resources.wood.is_none());
@@ -477,6 +748,7 @@ mod tests {
             wood: Some(-50),
             gold: None,
             stone: None,
+            ..Default::default()
         };
         assert_eq!(resources.food, Some(-100));
     }
@@ -488,6 +760,7 @@ mod tests {
             wood: Some(0),
             gold: Some(0),
             stone: Some(0),
+            ..Default::default()
         };
         assert_eq!(resources.food, Some(0));
     }
@@ -499,6 +772,7 @@ mod tests {
             description: "Do something".to_string(),
             timing: None,
             resources: None,
+            ..Default::default()
         };
         assert!(step.timing.is_none());
         assert!(step.resources.is_none());
@@ -511,6 +785,7 @@ mod tests {
             description: "Build house".to_string(),
             timing: Some("1:30".to_string()),
             resources: None,
+            ..Default::default()
         };
         assert_eq!(step.timing, Some("1:30".to_string()));
     }
@@ -526,7 +801,9 @@ mod tests {
                 wood: Some(50),
                 gold: None,
                 stone: None,
+                ..Default::default()
             }),
+            ..Default::default()
         };
         assert!(step.resources.is_some());
         assert_eq!(step.resources.unwrap().wood, Some(50));
@@ -579,12 +856,14 @@ mod tests {
                 description: "First".to_string(),
                 timing: None,
                 resources: None,
+                ..Default::default()
             },
             BuildOrderStep {
                 id: "".to_string(),
                 description: "Second".to_string(),
                 timing: None,
                 resources: None,
+                ..Default::default()
             },
         ];
         let result = validate_build_order(&order);
@@ -593,6 +872,54 @@ mod tests {
         assert!(err_msg.contains("Step 2"));
     }
 
+    #[test]
+    fn test_validate_build_order_tags() {
+        let mut order = create_valid_build_order();
+        order.tags = Some(vec!["ranked".to_string(), "Cheese".to_string()]);
+        assert!(validate_build_order(&order).is_ok());
+
+        order.tags = Some(vec!["  ".to_string()]);
+        assert!(validate_build_order(&order).unwrap_err().contains("empty"));
+
+        order.tags = Some(vec!["Rush".to_string(), "rush ".to_string()]);
+        assert!(validate_build_order(&order)
+            .unwrap_err()
+            .contains("Duplicate tag"));
+
+        order.tags = Some(vec!["x".repeat(MAX_TAG_LEN + 1)]);
+        assert!(validate_build_order(&order)
+            .unwrap_err()
+            .contains("max length"));
+
+        order.tags = Some(
+            (0..=MAX_BUILD_ORDER_TAGS)
+                .map(|i| format!("t{}", i))
+                .collect(),
+        );
+        assert!(validate_build_order(&order)
+            .unwrap_err()
+            .contains("maximum of 20 tag entries"));
+    }
+
+    #[test]
+    fn test_validate_build_order_matchup_targets() {
+        let mut order = create_valid_build_order();
+        order.opponent_civilizations = Some(vec!["French".to_string(), "Mongols".to_string()]);
+        order.opponent_strategies = Some(vec!["tower rush".to_string()]);
+        assert!(validate_build_order(&order).is_ok());
+
+        order.opponent_civilizations = Some(vec!["French".to_string(), "french".to_string()]);
+        assert!(validate_build_order(&order)
+            .unwrap_err()
+            .contains("Duplicate opponent civilization"));
+
+        order.opponent_civilizations = None;
+        order.opponent_strategies = Some(vec![String::new()]);
+        assert!(validate_build_order(&order)
+            .unwrap_err()
+            .contains("Opponent strategy 1 is empty"));
+    }
+
     #[test]
     fn test_build_order_any_civilization() {
         let mut order = create_valid_build_order();
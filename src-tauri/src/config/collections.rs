@@ -0,0 +1,317 @@
+use super::build_order::BuildOrder;
+use serde::{Deserialize, Serialize};
+
+pub const MAX_COLLECTIONS: usize = 100;
+pub const MAX_COLLECTION_NAME_LEN: usize = 64;
+
+/// A user-defined, ordered playlist of build orders ("Ranked pocket",
+/// "Practice this week"). Only ids are stored; the builds live in the library.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildOrderCollection {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub build_order_ids: Vec<String>,
+}
+
+/// Persisted as `collections.json` next to `config.json`.
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CollectionStore {
+    #[serde(default)]
+    pub collections: Vec<BuildOrderCollection>,
+    /// When set, the cycle-build-order hotkey only walks this collection.
+    #[serde(default)]
+    pub active_collection_id: Option<String>,
+}
+
+impl CollectionStore {
+    fn find_mut(&mut self, id: &str) -> Result<&mut BuildOrderCollection, String> {
+        self.collections
+            .iter_mut()
+            .find(|c| c.id == id)
+            .ok_or_else(|| format!("Collection \"{}\" not found", id))
+    }
+
+    pub fn create(&mut self, name: &str) -> Result<BuildOrderCollection, String> {
+        let name = validate_collection_name(name)?;
+        if self.collections.len() >= MAX_COLLECTIONS {
+            return Err(format!(
+                "Cannot create more than {} collections",
+                MAX_COLLECTIONS
+            ));
+        }
+
+        let base = slugify(&name, "collection");
+        let mut id = base.clone();
+        let mut suffix = 2;
+        while self.collections.iter().any(|c| c.id == id) {
+            id = format!("{}-{}", base, suffix);
+            suffix += 1;
+        }
+
+        let collection = BuildOrderCollection {
+            id,
+            name,
+            build_order_ids: Vec::new(),
+        };
+        self.collections.push(collection.clone());
+        Ok(collection)
+    }
+
+    pub fn rename(&mut self, id: &str, name: &str) -> Result<(), String> {
+        let name = validate_collection_name(name)?;
+        self.find_mut(id)?.name = name;
+        Ok(())
+    }
+
+    pub fn delete(&mut self, id: &str) -> Result<(), String> {
+        let before = self.collections.len();
+        self.collections.retain(|c| c.id != id);
+        if self.collections.len() == before {
+            return Err(format!("Collection \"{}\" not found", id));
+        }
+        if self.active_collection_id.as_deref() == Some(id) {
+            self.active_collection_id = None;
+        }
+        Ok(())
+    }
+
+    /// Reorders the collections themselves; `ids` must list every collection once.
+    pub fn reorder(&mut self, ids: &[String]) -> Result<(), String> {
+        let is_permutation = ids.len() == self.collections.len()
+            && self.collections.iter().all(|c| ids.contains(&c.id));
+        if !is_permutation {
+            return Err("Collection order must list every collection exactly once".to_string());
+        }
+        self.collections
+            .sort_by_key(|c| ids.iter().position(|id| *id == c.id));
+        Ok(())
+    }
+
+    /// Replaces a collection's members (covers add, remove and reorder).
+    /// Duplicates are dropped and every id must exist in `library`.
+    pub fn set_build_orders(
+        &mut self,
+        id: &str,
+        build_order_ids: Vec<String>,
+        library: &[BuildOrder],
+    ) -> Result<(), String> {
+        let mut members: Vec<String> = Vec::with_capacity(build_order_ids.len());
+        for build_id in build_order_ids {
+            if !library.iter().any(|o| o.id == build_id) {
+                return Err(format!("Build order \"{}\" does not exist", build_id));
+            }
+            if !members.contains(&build_id) {
+                members.push(build_id);
+            }
+        }
+        self.find_mut(id)?.build_order_ids = members;
+        Ok(())
+    }
+
+    pub fn set_active(&mut self, id: Option<String>) -> Result<(), String> {
+        if let Some(id) = &id {
+            if !self.collections.iter().any(|c| c.id == *id) {
+                return Err(format!("Collection \"{}\" not found", id));
+            }
+        }
+        self.active_collection_id = id;
+        Ok(())
+    }
+
+    /// Drops a deleted build from every collection. Returns true if anything changed.
+    pub fn remove_build_order(&mut self, build_id: &str) -> bool {
+        let mut changed = false;
+        for collection in &mut self.collections {
+            let before = collection.build_order_ids.len();
+            collection.build_order_ids.retain(|id| id != build_id);
+            changed |= collection.build_order_ids.len() != before;
+        }
+        changed
+    }
+
+    /// Ids the cycle-build-order hotkey steps through: the enabled members of the
+    /// active collection in collection order, or every enabled build when no
+    /// collection is active (or the active one has nothing playable).
+    pub fn cycle_build_order_ids(&self, library: &[BuildOrder]) -> Vec<String> {
+        let enabled = |id: &str| library.iter().any(|o| o.id == id && o.enabled);
+
+        let from_collection: Vec<String> = self
+            .active_collection_id
+            .as_deref()
+            .and_then(|active| self.collections.iter().find(|c| c.id == active))
+            .map(|c| {
+                c.build_order_ids
+                    .iter()
+                    .filter(|id| enabled(id))
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        if !from_collection.is_empty() {
+            return from_collection;
+        }
+        library
+            .iter()
+            .filter(|o| o.enabled)
+            .map(|o| o.id.clone())
+            .collect()
+    }
+}
+
+fn validate_collection_name(name: &str) -> Result<String, String> {
+    let trimmed = name.trim();
+    if trimmed.is_empty() {
+        return Err("Collection name is required".to_string());
+    }
+    if trimmed.chars().count() > MAX_COLLECTION_NAME_LEN {
+        return Err(format!(
+            "Collection name exceeds max length of {} characters",
+            MAX_COLLECTION_NAME_LEN
+        ));
+    }
+    Ok(trimmed.to_string())
+}
+
+/// Derives an id that passes `validate_build_order_id`-style rules from a name,
+/// or `fallback` when the name has no usable characters.
+pub(super) fn slugify(name: &str, fallback: &str) -> String {
+    let mut slug = String::new();
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.ends_with('-') && !slug.is_empty() {
+            slug.push('-');
+        }
+    }
+    let slug: String = slug.trim_end_matches('-').chars().take(48).collect();
+    if slug.is_empty() {
+        fallback.to_string()
+    } else {
+        slug
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::BuildOrderStep;
+
+    fn build(id: &str, enabled: bool) -> BuildOrder {
+        BuildOrder {
+            id: id.to_string(),
+            name: id.to_string(),
+            civilization: "English".to_string(),
+            difficulty: "Beginner".to_string(),
+            steps: vec![BuildOrderStep {
+                id: "s1".to_string(),
+                description: "Step".to_string(),
+                ..Default::default()
+            }],
+            enabled,
+            ..Default::default()
+        }
+    }
+
+    fn library() -> Vec<BuildOrder> {
+        vec![build("a", true), build("b", true), build("c", false)]
+    }
+
+    #[test]
+    fn test_create_generates_unique_ids() {
+        let mut store = CollectionStore::default();
+        let first = store.create("Ranked pocket").unwrap();
+        let second = store.create("Ranked Pocket!").unwrap();
+        assert_eq!(first.id, "ranked-pocket");
+        assert_eq!(second.id, "ranked-pocket-2");
+        assert_eq!(store.create("!!!").unwrap().id, "collection");
+    }
+
+    #[test]
+    fn test_create_rejects_bad_names() {
+        let mut store = CollectionStore::default();
+        assert!(store.create("   ").is_err());
+        assert!(store
+            .create(&"x".repeat(MAX_COLLECTION_NAME_LEN + 1))
+            .is_err());
+    }
+
+    #[test]
+    fn test_rename_and_delete() {
+        let mut store = CollectionStore::default();
+        let c = store.create("Practice").unwrap();
+        store.set_active(Some(c.id.clone())).unwrap();
+        store.rename(&c.id, "Practice this week").unwrap();
+        assert_eq!(store.collections[0].name, "Practice this week");
+
+        store.delete(&c.id).unwrap();
+        assert!(store.collections.is_empty());
+        assert!(store.active_collection_id.is_none());
+        assert!(store.delete(&c.id).is_err());
+    }
+
+    #[test]
+    fn test_reorder_requires_permutation() {
+        let mut store = CollectionStore::default();
+        store.create("One").unwrap();
+        store.create("Two").unwrap();
+        assert!(store.reorder(&["two".to_string()]).is_err());
+        store
+            .reorder(&["two".to_string(), "one".to_string()])
+            .unwrap();
+        assert_eq!(store.collections[0].id, "two");
+    }
+
+    #[test]
+    fn test_set_build_orders_validates_and_dedupes() {
+        let mut store = CollectionStore::default();
+        let c = store.create("Mix").unwrap();
+        let lib = library();
+        assert!(store
+            .set_build_orders(&c.id, vec!["missing".to_string()], &lib)
+            .is_err());
+        store
+            .set_build_orders(
+                &c.id,
+                vec!["b".to_string(), "a".to_string(), "b".to_string()],
+                &lib,
+            )
+            .unwrap();
+        assert_eq!(store.collections[0].build_order_ids, vec!["b", "a"]);
+
+        assert!(store.remove_build_order("b"));
+        assert!(!store.remove_build_order("b"));
+        assert_eq!(store.collections[0].build_order_ids, vec!["a"]);
+    }
+
+    #[test]
+    fn test_cycle_ids_follow_active_collection() {
+        let mut store = CollectionStore::default();
+        let lib = library();
+        assert_eq!(store.cycle_build_order_ids(&lib), vec!["a", "b"]);
+
+        let c = store.create("Pocket").unwrap();
+        store
+            .set_build_orders(&c.id, vec!["c".to_string(), "b".to_string()], &lib)
+            .unwrap();
+        store.set_active(Some(c.id.clone())).unwrap();
+        // Disabled "c" is skipped
+        assert_eq!(store.cycle_build_order_ids(&lib), vec!["b"]);
+    }
+
+    #[test]
+    fn test_set_active_unknown_collection() {
+        let mut store = CollectionStore::default();
+        assert!(store.set_active(Some("nope".to_string())).is_err());
+        assert!(store.set_active(None).is_ok());
+    }
+
+    #[test]
+    fn test_store_deserializes_with_defaults() {
+        let store: CollectionStore = serde_json::from_str("{}").unwrap();
+        assert_eq!(store, CollectionStore::default());
+    }
+}
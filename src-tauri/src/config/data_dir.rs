@@ -0,0 +1,315 @@
+//! Where the app keeps its data. By default that is `aoe4-overlay` in the
+//! user's config folder, but the root can be moved:
+//!
+//! 1. `AOE4_OVERLAY_DATA_DIR` in the environment,
+//! 2. a `portable` file next to the executable, which keeps data in `data/`
+//!    beside it (for USB-stick installs),
+//! 3. a folder picked in the app, remembered in `data-location.json` in the
+//!    default folder.
+
+use super::persistence::atomic_write;
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+pub const DATA_DIR_ENV: &str = "AOE4_OVERLAY_DATA_DIR";
+pub const PORTABLE_MARKER: &str = "portable";
+const PORTABLE_DATA_DIR: &str = "data";
+const DATA_LOCATION_FILE: &str = "data-location.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DataDirSource {
+    Environment,
+    Portable,
+    Setting,
+    Default,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataDirInfo {
+    pub path: String,
+    pub source: DataDirSource,
+    pub default_path: String,
+    /// False when the environment or portable mode pins the folder.
+    pub relocatable: bool,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DataLocation {
+    data_dir: Option<PathBuf>,
+}
+
+/// Resolved once, then replaced only by `set_data_dir`.
+static DATA_DIR: RwLock<Option<(PathBuf, DataDirSource)>> = RwLock::new(None);
+
+pub fn default_data_dir() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("aoe4-overlay")
+}
+
+fn data_location_path() -> PathBuf {
+    default_data_dir().join(DATA_LOCATION_FILE)
+}
+
+fn read_data_location() -> Option<PathBuf> {
+    let content = fs::read_to_string(data_location_path()).ok()?;
+    match serde_json::from_str::<DataLocation>(&content) {
+        Ok(location) => location.data_dir,
+        Err(err) => {
+            eprintln!("Ignoring invalid {}: {}", DATA_LOCATION_FILE, err);
+            None
+        }
+    }
+}
+
+/// The data root by precedence: environment, portable marker, saved setting,
+/// default.
+fn choose_data_dir(
+    env_dir: Option<OsString>,
+    exe_dir: Option<&Path>,
+    setting: impl FnOnce() -> Option<PathBuf>,
+) -> (PathBuf, DataDirSource) {
+    if let Some(dir) = env_dir.filter(|dir| !dir.is_empty()) {
+        return (PathBuf::from(dir), DataDirSource::Environment);
+    }
+    if let Some(exe_dir) = exe_dir.filter(|dir| dir.join(PORTABLE_MARKER).is_file()) {
+        return (exe_dir.join(PORTABLE_DATA_DIR), DataDirSource::Portable);
+    }
+    if let Some(dir) = setting() {
+        return (dir, DataDirSource::Setting);
+    }
+    (default_data_dir(), DataDirSource::Default)
+}
+
+/// The current data root and what chose it.
+pub fn data_dir() -> (PathBuf, DataDirSource) {
+    if let Some(resolved) = DATA_DIR.read().ok().and_then(|dir| dir.clone()) {
+        return resolved;
+    }
+    let exe_dir = env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(Path::to_path_buf));
+    let resolved = choose_data_dir(
+        env::var_os(DATA_DIR_ENV),
+        exe_dir.as_deref(),
+        read_data_location,
+    );
+    if let Ok(mut dir) = DATA_DIR.write() {
+        *dir = Some(resolved.clone());
+    }
+    resolved
+}
+
+pub fn data_dir_info() -> DataDirInfo {
+    let (path, source) = data_dir();
+    DataDirInfo {
+        path: path.to_string_lossy().into_owned(),
+        source,
+        default_path: default_data_dir().to_string_lossy().into_owned(),
+        relocatable: matches!(source, DataDirSource::Setting | DataDirSource::Default),
+    }
+}
+
+/// Remembers `dir` as the data root from now on, or forgets the setting
+/// when `dir` is the default folder.
+pub fn set_data_dir(dir: &Path) -> Result<(), String> {
+    let location = data_location_path();
+    let source = if dir == default_data_dir() {
+        if location.exists() {
+            fs::remove_file(&location).map_err(|e| e.to_string())?;
+        }
+        DataDirSource::Default
+    } else {
+        fs::create_dir_all(default_data_dir()).map_err(|e| e.to_string())?;
+        let json = serde_json::to_string_pretty(&DataLocation {
+            data_dir: Some(dir.to_path_buf()),
+        })
+        .map_err(|e| e.to_string())?;
+        atomic_write(&location, json).map_err(|e| e.to_string())?;
+        DataDirSource::Setting
+    };
+    let mut current = DATA_DIR.write().map_err(|e| e.to_string())?;
+    *current = Some((dir.to_path_buf(), source));
+    Ok(())
+}
+
+/// Checks that data in `current` can be moved to `target`: an absolute path
+/// elsewhere, not nested either way, and empty or not yet created, so
+/// nothing there is overwritten. `default` may be occupied, by the pointer
+/// file and the copy left behind when data first moved away; `relocate_data`
+/// sets that aside.
+pub fn check_relocation_target(
+    current: &Path,
+    target: &Path,
+    default: &Path,
+) -> Result<(), String> {
+    if !target.is_absolute() {
+        return Err("Data folder must be an absolute path".to_string());
+    }
+    if target == current {
+        return Err("Data is already stored in that folder".to_string());
+    }
+    if target.starts_with(current) || current.starts_with(target) {
+        return Err("Data folder cannot be inside the current one or contain it".to_string());
+    }
+    if target.is_file() {
+        return Err(format!("{:?} is a file", target));
+    }
+    let occupied = fs::read_dir(target).is_ok_and(|mut entries| entries.next().is_some());
+    if occupied && target != default {
+        return Err(format!("{:?} is not empty", target));
+    }
+    Ok(())
+}
+
+/// Renames an occupied `dir` to the first free `<name>-backup-<n>` beside it,
+/// returning where it went.
+fn move_aside(dir: &Path) -> std::io::Result<Option<PathBuf>> {
+    let occupied = fs::read_dir(dir).is_ok_and(|mut entries| entries.next().is_some());
+    if !occupied {
+        return Ok(None);
+    }
+    let name = dir.file_name().unwrap_or_default().to_string_lossy();
+    let aside = (1..)
+        .map(|n| dir.with_file_name(format!("{}-backup-{}", name, n)))
+        .find(|path| !path.exists())
+        .expect("unbounded range");
+    fs::rename(dir, &aside)?;
+    Ok(Some(aside))
+}
+
+/// Copies the data in `current` to `target`, which has passed
+/// `check_relocation_target`, then calls `switch` to start using it. When
+/// `target` is the occupied default folder, its old contents are first renamed
+/// aside, and where they went is returned. On failure the partial copy is
+/// removed and anything set aside is put back.
+pub fn relocate_data(
+    current: &Path,
+    target: &Path,
+    switch: impl FnOnce(&Path) -> Result<(), String>,
+) -> Result<Option<PathBuf>, String> {
+    let fail = |err: String| format!("Failed to move data to {:?}: {}", target, err);
+    let aside = move_aside(target).map_err(|e| fail(e.to_string()))?;
+    let created = !target.exists();
+    let copied = copy_data_dir(current, target)
+        .map_err(|e| e.to_string())
+        .and_then(|_| switch(target));
+    if let Err(err) = copied {
+        if created {
+            let _ = fs::remove_dir_all(target);
+        }
+        if let Some(aside) = &aside {
+            let _ = fs::rename(aside, target);
+        }
+        return Err(fail(err));
+    }
+    Ok(aside)
+}
+
+/// Copies the contents of `from` into `to` recursively, returning the
+/// number of files copied. The relocation pointer is not part of the data
+/// and is skipped.
+pub fn copy_data_dir(from: &Path, to: &Path) -> std::io::Result<usize> {
+    fs::create_dir_all(to)?;
+    let mut copied = 0;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let source = entry.path();
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copied += copy_data_dir(&source, &target)?;
+        } else if entry.file_name() != DATA_LOCATION_FILE {
+            let bytes = fs::copy(&source, &target)?;
+            if bytes != entry.metadata()?.len() {
+                return Err(std::io::Error::other(format!(
+                    "Incomplete copy of {:?}",
+                    source
+                )));
+            }
+            copied += 1;
+        }
+    }
+    Ok(copied)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_choose_data_dir_precedence() {
+        let setting = || Some(PathBuf::from("/picked"));
+
+        let (dir, source) = choose_data_dir(Some("/from-env".into()), None, setting);
+        assert_eq!(dir, PathBuf::from("/from-env"));
+        assert_eq!(source, DataDirSource::Environment);
+
+        let (dir, source) = choose_data_dir(Some("".into()), None, setting);
+        assert_eq!(dir, PathBuf::from("/picked"));
+        assert_eq!(source, DataDirSource::Setting);
+
+        // No portable marker in a folder that does not exist.
+        let (_, source) = choose_data_dir(None, Some(Path::new("/no/such/dir")), || None);
+        assert_eq!(source, DataDirSource::Default);
+    }
+
+    #[test]
+    fn test_relocation_target_checks() {
+        let current = default_data_dir();
+        let check = |target: &Path| check_relocation_target(&current, target, &current);
+        assert!(check(Path::new("relative")).is_err());
+        assert!(check(&current).is_err());
+        assert!(check(&current.join("nested")).is_err());
+        if let Some(parent) = current.parent() {
+            assert!(check(parent).is_err());
+        }
+    }
+
+    #[test]
+    fn test_relocate_away_and_back_to_default() {
+        let root = env::temp_dir().join(format!("aoe4-overlay-relocate-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let home = root.join("home");
+        let away = root.join("away");
+        fs::create_dir_all(home.join("build-orders")).unwrap();
+        fs::write(home.join("config.json"), "old").unwrap();
+        fs::write(home.join("build-orders/a.json"), "{}").unwrap();
+
+        check_relocation_target(&home, &away, &home).unwrap();
+        assert_eq!(relocate_data(&home, &away, |_| Ok(())).unwrap(), None);
+        // What the setting leaves behind in the default folder.
+        fs::write(home.join(DATA_LOCATION_FILE), "{}").unwrap();
+        fs::write(away.join("config.json"), "new").unwrap();
+
+        // Occupied folders other than the default are refused.
+        let elsewhere = root.join("elsewhere");
+        fs::create_dir_all(&elsewhere).unwrap();
+        fs::write(elsewhere.join("x"), "").unwrap();
+        assert!(check_relocation_target(&away, &elsewhere, &home).is_err());
+
+        // A failed switch puts the default folder back as it was.
+        let failed = relocate_data(&away, &home, |_| Err("no".to_string()));
+        assert!(failed.is_err());
+        assert_eq!(fs::read_to_string(home.join("config.json")).unwrap(), "old");
+        assert!(home.join(DATA_LOCATION_FILE).exists());
+
+        check_relocation_target(&away, &home, &home).unwrap();
+        let aside = relocate_data(&away, &home, |_| Ok(())).unwrap().unwrap();
+        assert_eq!(fs::read_to_string(home.join("config.json")).unwrap(), "new");
+        assert!(home.join("build-orders/a.json").exists());
+        assert!(!home.join(DATA_LOCATION_FILE).exists());
+        assert_eq!(
+            fs::read_to_string(aside.join("config.json")).unwrap(),
+            "old"
+        );
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
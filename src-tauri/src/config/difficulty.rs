@@ -0,0 +1,101 @@
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// Normalized difficulty. `BuildOrder.difficulty` stays a free label; this is
+/// what labels and estimates are compared on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum DifficultyLevel {
+    Beginner,
+    Intermediate,
+    Advanced,
+    Expert,
+}
+
+impl DifficultyLevel {
+    /// Maps an author label ("Easy", "beginner", "Hard", "Pro") onto a level.
+    /// Unknown labels give None.
+    pub fn from_label(label: &str) -> Option<Self> {
+        let label = label.trim().to_lowercase();
+        let level = match label.as_str() {
+            "beginner" | "easy" | "novice" | "basic" => DifficultyLevel::Beginner,
+            "intermediate" | "medium" | "normal" | "moderate" => DifficultyLevel::Intermediate,
+            "advanced" | "hard" => DifficultyLevel::Advanced,
+            "expert" | "pro" | "very hard" | "insane" => DifficultyLevel::Expert,
+            _ => return None,
+        };
+        Some(level)
+    }
+
+    /// 0 for Beginner up to 3 for Expert.
+    pub fn rank(&self) -> u8 {
+        *self as u8
+    }
+}
+
+/// Reads the `filter_difficulty` setting through `from_label`, so legacy
+/// labels ("Hard") still filter. An unknown label clears the filter.
+pub fn deserialize_difficulty_filter<'de, D>(
+    deserializer: D,
+) -> Result<Option<DifficultyLevel>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let Some(label) = Option::<String>::deserialize(deserializer)? else {
+        return Ok(None);
+    };
+    let level = DifficultyLevel::from_label(&label);
+    if level.is_none() && !label.trim().is_empty() {
+        eprintln!(
+            "Unknown filter_difficulty \"{}\" in config; showing all difficulties",
+            label
+        );
+    }
+    Ok(level)
+}
+
+/// Difficulty derived from a build's structure, kept next to the author's
+/// label so the two can be compared.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DifficultyEstimate {
+    pub level: DifficultyLevel,
+    /// 0.0 (trivial) to 3.0 (hardest), the scale `level` is cut from.
+    pub score: f32,
+    /// Factors that raised the score, most significant first.
+    #[serde(default)]
+    pub reasons: Vec<String>,
+    /// The author's label, normalized. None when it is not a known label.
+    #[serde(default)]
+    pub label_level: Option<DifficultyLevel>,
+    /// True when the label is two or more levels away from the estimate.
+    #[serde(default)]
+    pub disagrees_with_label: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_label_accepts_aliases() {
+        assert_eq!(
+            DifficultyLevel::from_label("Easy"),
+            Some(DifficultyLevel::Beginner)
+        );
+        assert_eq!(
+            DifficultyLevel::from_label(" expert "),
+            Some(DifficultyLevel::Expert)
+        );
+        assert_eq!(
+            DifficultyLevel::from_label("Hard"),
+            Some(DifficultyLevel::Advanced)
+        );
+        assert_eq!(DifficultyLevel::from_label("Spicy"), None);
+    }
+
+    #[test]
+    fn test_serializes_as_label() {
+        let json = serde_json::to_string(&DifficultyLevel::Intermediate).unwrap();
+        assert_eq!(json, "\"Intermediate\"");
+        assert_eq!(DifficultyLevel::Expert.rank(), 3);
+    }
+}
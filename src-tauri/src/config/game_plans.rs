@@ -0,0 +1,554 @@
+use super::build_order::{active_steps, BuildOrder};
+use super::collections::slugify;
+use serde::{Deserialize, Serialize};
+
+pub const MAX_GAME_PLANS: usize = 50;
+pub const MAX_GAME_PLAN_STAGES: usize = 8;
+pub const MAX_GAME_PLAN_NAME_LEN: usize = 64;
+pub const MAX_TRANSITION_NOTE_LEN: usize = 120;
+
+/// When a stage hands over to the next one.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum TransitionCondition {
+    /// After the stage's last step.
+    #[default]
+    EndOfBuild,
+    /// After the given main-line step, skipping the rest of the build.
+    #[serde(rename_all = "camelCase")]
+    AtStep { step_id: String },
+    /// On the first next-step press once the match timer reaches this many
+    /// seconds, or after the stage's last step if that comes first.
+    AtTime { seconds: u32 },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GamePlanStage {
+    pub build_order_id: String,
+    #[serde(default)]
+    pub transition: TransitionCondition,
+    /// Shown when the plan moves on ("Switch once the second TC is up").
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+/// An ordered chain of builds for one civilization (opening, follow-up,
+/// late-game checklist). Only ids are stored; the builds live in the library.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GamePlan {
+    pub id: String,
+    pub name: String,
+    pub civilization: String,
+    #[serde(default)]
+    pub stages: Vec<GamePlanStage>,
+}
+
+/// Persisted as `game-plans.json` next to `config.json`.
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GamePlanStore {
+    #[serde(default)]
+    pub plans: Vec<GamePlan>,
+    /// When set, next/previous step cross from one stage's build to the next.
+    #[serde(default)]
+    pub active_plan_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum StepDirection {
+    Next,
+    Previous,
+}
+
+/// Where step navigation lands after crossing a stage boundary.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GamePlanPosition {
+    pub plan_id: String,
+    pub stage_index: usize,
+    pub build_order_id: String,
+    pub step_index: usize,
+    pub note: Option<String>,
+}
+
+impl GamePlan {
+    /// Everything wrong with the plan against `library`: missing builds,
+    /// builds for another civilization, repeated builds and transition steps
+    /// that are not in their build.
+    pub fn problems(&self, library: &[BuildOrder]) -> Vec<String> {
+        let mut problems = Vec::new();
+        for (index, stage) in self.stages.iter().enumerate() {
+            let label = format!("Stage {}", index + 1);
+            if self.stages[..index]
+                .iter()
+                .any(|s| s.build_order_id == stage.build_order_id)
+            {
+                problems.push(format!(
+                    "{}: build order \"{}\" is already in the plan",
+                    label, stage.build_order_id
+                ));
+            }
+            let Some(order) = library.iter().find(|o| o.id == stage.build_order_id) else {
+                problems.push(format!(
+                    "{}: build order \"{}\" does not exist",
+                    label, stage.build_order_id
+                ));
+                continue;
+            };
+            if !order
+                .civilization
+                .trim()
+                .eq_ignore_ascii_case(self.civilization.trim())
+            {
+                problems.push(format!(
+                    "{}: \"{}\" is a {} build, not {}",
+                    label, order.name, order.civilization, self.civilization
+                ));
+            }
+            if let TransitionCondition::AtStep { step_id } = &stage.transition {
+                if !order.steps.iter().any(|s| s.id == *step_id) {
+                    problems.push(format!(
+                        "{}: step \"{}\" is not in \"{}\"",
+                        label, step_id, order.name
+                    ));
+                }
+            }
+            if stage
+                .note
+                .as_ref()
+                .is_some_and(|n| n.chars().count() > MAX_TRANSITION_NOTE_LEN)
+            {
+                problems.push(format!(
+                    "{}: note exceeds max length of {} characters",
+                    label, MAX_TRANSITION_NOTE_LEN
+                ));
+            }
+        }
+        problems
+    }
+
+    /// Index of the step after which `stage` hands over, within the steps the
+    /// player walks with `branch_id` active. A transition step the branch
+    /// skips hands over at the end instead. None when the build is missing.
+    fn handover_step(
+        &self,
+        stage: usize,
+        branch_id: Option<&str>,
+        library: &[BuildOrder],
+    ) -> Option<usize> {
+        let stage = &self.stages[stage];
+        let order = library.iter().find(|o| o.id == stage.build_order_id)?;
+        let steps = active_steps(order, branch_id);
+        let at_step = match &stage.transition {
+            TransitionCondition::AtStep { step_id } => steps.iter().position(|s| s.id == *step_id),
+            _ => None,
+        };
+        Some(at_step.unwrap_or(steps.len().saturating_sub(1)))
+    }
+
+    /// The position one step `direction` from `step_index` of `build_order_id`
+    /// (walked with `branch_id` active) when that crosses into another stage.
+    /// `elapsed_seconds` is the match timer, for `AtTime` transitions. None
+    /// when the move stays inside the build (the overlay handles it as usual)
+    /// or the build is not in the plan. Builds are entered on their main line.
+    pub fn navigate(
+        &self,
+        build_order_id: &str,
+        step_index: usize,
+        branch_id: Option<&str>,
+        direction: StepDirection,
+        elapsed_seconds: Option<u32>,
+        library: &[BuildOrder],
+    ) -> Option<GamePlanPosition> {
+        let stage = self
+            .stages
+            .iter()
+            .position(|s| s.build_order_id == build_order_id)?;
+        let target = match direction {
+            StepDirection::Next => {
+                let next = stage + 1;
+                if next >= self.stages.len() {
+                    return None;
+                }
+                let time_up = match self.stages[stage].transition {
+                    TransitionCondition::AtTime { seconds } => {
+                        elapsed_seconds.is_some_and(|elapsed| elapsed >= seconds)
+                    }
+                    _ => false,
+                };
+                if !time_up && step_index < self.handover_step(stage, branch_id, library)? {
+                    return None;
+                }
+                (next, 0, stage)
+            }
+            StepDirection::Previous => {
+                if stage == 0 || step_index > 0 {
+                    return None;
+                }
+                let previous = stage - 1;
+                (
+                    previous,
+                    self.handover_step(previous, None, library)?,
+                    previous,
+                )
+            }
+        };
+        // The note belongs to the stage that hands over, whichever way we cross
+        let (stage_index, step_index, handover) = target;
+        Some(GamePlanPosition {
+            plan_id: self.id.clone(),
+            stage_index,
+            build_order_id: self.stages[stage_index].build_order_id.clone(),
+            step_index,
+            note: self.stages[handover].note.clone(),
+        })
+    }
+}
+
+impl GamePlanStore {
+    fn find_mut(&mut self, id: &str) -> Result<&mut GamePlan, String> {
+        self.plans
+            .iter_mut()
+            .find(|p| p.id == id)
+            .ok_or_else(|| format!("Game plan \"{}\" not found", id))
+    }
+
+    pub fn create(&mut self, name: &str, civilization: &str) -> Result<GamePlan, String> {
+        let name = validate_game_plan_name(name)?;
+        let civilization = civilization.trim();
+        if civilization.is_empty() {
+            return Err("Game plan civilization is required".to_string());
+        }
+        if self.plans.len() >= MAX_GAME_PLANS {
+            return Err(format!(
+                "Cannot create more than {} game plans",
+                MAX_GAME_PLANS
+            ));
+        }
+
+        let base = slugify(&name, "game-plan");
+        let mut id = base.clone();
+        let mut suffix = 2;
+        while self.plans.iter().any(|p| p.id == id) {
+            id = format!("{}-{}", base, suffix);
+            suffix += 1;
+        }
+
+        let plan = GamePlan {
+            id,
+            name,
+            civilization: civilization.to_string(),
+            stages: Vec::new(),
+        };
+        self.plans.push(plan.clone());
+        Ok(plan)
+    }
+
+    pub fn rename(&mut self, id: &str, name: &str) -> Result<(), String> {
+        let name = validate_game_plan_name(name)?;
+        self.find_mut(id)?.name = name;
+        Ok(())
+    }
+
+    pub fn delete(&mut self, id: &str) -> Result<(), String> {
+        let before = self.plans.len();
+        self.plans.retain(|p| p.id != id);
+        if self.plans.len() == before {
+            return Err(format!("Game plan \"{}\" not found", id));
+        }
+        if self.active_plan_id.as_deref() == Some(id) {
+            self.active_plan_id = None;
+        }
+        Ok(())
+    }
+
+    /// Replaces a plan's stages. Rejected unless every stage passes
+    /// `GamePlan::problems` against `library`.
+    pub fn set_stages(
+        &mut self,
+        id: &str,
+        stages: Vec<GamePlanStage>,
+        library: &[BuildOrder],
+    ) -> Result<(), String> {
+        if stages.len() > MAX_GAME_PLAN_STAGES {
+            return Err(format!(
+                "A game plan cannot have more than {} stages",
+                MAX_GAME_PLAN_STAGES
+            ));
+        }
+        let plan = self.find_mut(id)?;
+        let candidate = GamePlan {
+            stages,
+            ..plan.clone()
+        };
+        let problems = candidate.problems(library);
+        if !problems.is_empty() {
+            return Err(problems.join("; "));
+        }
+        *plan = candidate;
+        Ok(())
+    }
+
+    pub fn set_active(&mut self, id: Option<String>) -> Result<(), String> {
+        if let Some(id) = &id {
+            if !self.plans.iter().any(|p| p.id == *id) {
+                return Err(format!("Game plan \"{}\" not found", id));
+            }
+        }
+        self.active_plan_id = id;
+        Ok(())
+    }
+
+    pub fn active_plan(&self) -> Option<&GamePlan> {
+        let id = self.active_plan_id.as_deref()?;
+        self.plans.iter().find(|p| p.id == id)
+    }
+
+    /// Drops stages that use a deleted build. Returns true if anything changed.
+    pub fn remove_build_order(&mut self, build_id: &str) -> bool {
+        let mut changed = false;
+        for plan in &mut self.plans {
+            let before = plan.stages.len();
+            plan.stages.retain(|s| s.build_order_id != build_id);
+            changed |= plan.stages.len() != before;
+        }
+        changed
+    }
+}
+
+fn validate_game_plan_name(name: &str) -> Result<String, String> {
+    let trimmed = name.trim();
+    if trimmed.is_empty() {
+        return Err("Game plan name is required".to_string());
+    }
+    if trimmed.chars().count() > MAX_GAME_PLAN_NAME_LEN {
+        return Err(format!(
+            "Game plan name exceeds max length of {} characters",
+            MAX_GAME_PLAN_NAME_LEN
+        ));
+    }
+    Ok(trimmed.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{BuildOrderBranch, BuildOrderStep};
+
+    fn build(id: &str, civilization: &str, steps: usize) -> BuildOrder {
+        BuildOrder {
+            id: id.to_string(),
+            name: id.to_string(),
+            civilization: civilization.to_string(),
+            difficulty: "Beginner".to_string(),
+            steps: (0..steps)
+                .map(|i| BuildOrderStep {
+                    id: format!("s{}", i),
+                    description: "Step".to_string(),
+                    ..Default::default()
+                })
+                .collect(),
+            enabled: true,
+            ..Default::default()
+        }
+    }
+
+    fn library() -> Vec<BuildOrder> {
+        vec![
+            build("opening", "English", 5),
+            build("midgame", "english", 3),
+            build("late", "English", 2),
+            build("french", "French", 2),
+        ]
+    }
+
+    fn stage(id: &str, transition: TransitionCondition) -> GamePlanStage {
+        GamePlanStage {
+            build_order_id: id.to_string(),
+            transition,
+            note: None,
+        }
+    }
+
+    fn plan_store() -> (GamePlanStore, String) {
+        let mut store = GamePlanStore::default();
+        let plan = store.create("Longbow into castle", "English").unwrap();
+        store
+            .set_stages(
+                &plan.id,
+                vec![
+                    stage(
+                        "opening",
+                        TransitionCondition::AtStep {
+                            step_id: "s3".to_string(),
+                        },
+                    ),
+                    stage("midgame", TransitionCondition::AtTime { seconds: 600 }),
+                    stage("late", TransitionCondition::EndOfBuild),
+                ],
+                &library(),
+            )
+            .unwrap();
+        (store, plan.id)
+    }
+
+    #[test]
+    fn test_set_stages_validates_builds_and_civilization() {
+        let mut store = GamePlanStore::default();
+        let plan = store.create("Plan", "English").unwrap();
+        let lib = library();
+        let err = store
+            .set_stages(
+                &plan.id,
+                vec![
+                    stage("missing", TransitionCondition::EndOfBuild),
+                    stage("french", TransitionCondition::EndOfBuild),
+                    stage(
+                        "opening",
+                        TransitionCondition::AtStep {
+                            step_id: "nope".to_string(),
+                        },
+                    ),
+                    stage("opening", TransitionCondition::EndOfBuild),
+                ],
+                &lib,
+            )
+            .unwrap_err();
+        assert!(err.contains("\"missing\" does not exist"), "{}", err);
+        assert!(err.contains("is a French build"), "{}", err);
+        assert!(err.contains("step \"nope\""), "{}", err);
+        assert!(err.contains("already in the plan"), "{}", err);
+        assert!(store.plans[0].stages.is_empty());
+    }
+
+    #[test]
+    fn test_next_crosses_at_transition_step() {
+        let (mut store, id) = plan_store();
+        store.set_active(Some(id)).unwrap();
+        let plan = store.active_plan().unwrap();
+        let lib = library();
+
+        assert_eq!(
+            plan.navigate("opening", 2, None, StepDirection::Next, None, &lib),
+            None
+        );
+        let position = plan
+            .navigate("opening", 3, None, StepDirection::Next, None, &lib)
+            .unwrap();
+        assert_eq!(position.build_order_id, "midgame");
+        assert_eq!(position.step_index, 0);
+
+        let position = plan
+            .navigate("midgame", 2, None, StepDirection::Next, None, &lib)
+            .unwrap();
+        assert_eq!((position.stage_index, position.step_index), (2, 0));
+        assert_eq!(
+            plan.navigate("late", 1, None, StepDirection::Next, None, &lib),
+            None
+        );
+    }
+
+    #[test]
+    fn test_next_crosses_once_match_timer_reaches_transition_time() {
+        let (store, _) = plan_store();
+        let plan = &store.plans[0];
+        let lib = library();
+        let next = |step, elapsed| {
+            plan.navigate("midgame", step, None, StepDirection::Next, elapsed, &lib)
+        };
+
+        assert_eq!(next(0, None), None);
+        assert_eq!(next(0, Some(599)), None);
+        assert_eq!(next(0, Some(600)).unwrap().build_order_id, "late");
+        // The end of the build still hands over before the time.
+        assert_eq!(next(2, Some(30)).unwrap().build_order_id, "late");
+        // Time transitions only apply to the stage being played.
+        assert_eq!(
+            plan.navigate("opening", 0, None, StepDirection::Next, Some(900), &lib),
+            None
+        );
+    }
+
+    #[test]
+    fn test_handover_counts_steps_of_the_active_branch() {
+        let (mut store, id) = plan_store();
+        store.set_active(Some(id)).unwrap();
+        let plan = store.active_plan().unwrap();
+        let mut lib = library();
+        lib[0].branches = Some(vec![BuildOrderBranch {
+            id: "rush".to_string(),
+            name: "Rush".to_string(),
+            trigger: None,
+            start_step_index: 2,
+            steps: (0..6)
+                .map(|i| BuildOrderStep {
+                    id: format!("b{}", i),
+                    ..lib[0].steps[0].clone()
+                })
+                .collect(),
+        }]);
+
+        // The branch skips the transition step s3, so the opening hands over
+        // after the branch's last step: 2 main-line steps + 6 branch steps.
+        assert_eq!(
+            plan.navigate("opening", 6, Some("rush"), StepDirection::Next, None, &lib),
+            None
+        );
+        let position = plan
+            .navigate("opening", 7, Some("rush"), StepDirection::Next, None, &lib)
+            .unwrap();
+        assert_eq!(position.build_order_id, "midgame");
+    }
+
+    #[test]
+    fn test_previous_returns_to_handover_step() {
+        let (store, _) = plan_store();
+        let plan = &store.plans[0];
+        let lib = library();
+
+        let position = plan
+            .navigate("midgame", 0, None, StepDirection::Previous, None, &lib)
+            .unwrap();
+        assert_eq!(position.build_order_id, "opening");
+        assert_eq!(position.step_index, 3);
+        let position = plan
+            .navigate("late", 0, None, StepDirection::Previous, None, &lib)
+            .unwrap();
+        assert_eq!(position.step_index, 2);
+        assert_eq!(
+            plan.navigate("midgame", 1, None, StepDirection::Previous, None, &lib),
+            None
+        );
+        assert_eq!(
+            plan.navigate("opening", 0, None, StepDirection::Previous, None, &lib),
+            None
+        );
+    }
+
+    #[test]
+    fn test_delete_and_removed_builds() {
+        let (mut store, id) = plan_store();
+        assert!(store.remove_build_order("midgame"));
+        assert!(!store.remove_build_order("midgame"));
+        assert_eq!(store.plans[0].stages.len(), 2);
+
+        store.set_active(Some(id.clone())).unwrap();
+        store.delete(&id).unwrap();
+        assert!(store.active_plan_id.is_none());
+        assert!(store.set_active(Some(id)).is_err());
+    }
+
+    #[test]
+    fn test_transition_serialization() {
+        let json = serde_json::to_string(&TransitionCondition::AtStep {
+            step_id: "s1".to_string(),
+        })
+        .unwrap();
+        assert_eq!(json, r#"{"type":"atStep","stepId":"s1"}"#);
+        let stage: GamePlanStage = serde_json::from_str(r#"{"buildOrderId":"a"}"#).unwrap();
+        assert_eq!(stage.transition, TransitionCondition::EndOfBuild);
+    }
+}
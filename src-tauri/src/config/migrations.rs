@@ -0,0 +1,260 @@
+use serde_json::{Map, Value};
+
+/// Schema version written into `config.json` as `schemaVersion`. Files from
+/// releases up to 1.9.0 have no version and count as 0.
+pub const CONFIG_SCHEMA_VERSION: u32 = 2;
+
+const SCHEMA_VERSION_KEY: &str = "schemaVersion";
+
+/// One step in the upgrade path. Steps run in order, each once, for every
+/// file older than `to_version`.
+struct ConfigMigration {
+    to_version: u32,
+    description: &'static str,
+    apply: fn(&mut Map<String, Value>),
+}
+
+const MIGRATIONS: &[ConfigMigration] = &[
+    ConfigMigration {
+        to_version: 1,
+        description: "rename snake_case section keys to their serialized names",
+        apply: rename_section_keys,
+    },
+    ConfigMigration {
+        to_version: 2,
+        description: "remap the old Alt+0..4 branch hotkey defaults to Ctrl+Alt+0..4",
+        apply: remap_branch_hotkeys,
+    },
+];
+
+/// Version recorded in a raw config object; 0 when absent or unreadable.
+pub fn stored_schema_version(config: &Map<String, Value>) -> u32 {
+    config
+        .get(SCHEMA_VERSION_KEY)
+        .and_then(Value::as_u64)
+        .and_then(|v| u32::try_from(v).ok())
+        .unwrap_or(0)
+}
+
+/// Brings a raw config object up to `CONFIG_SCHEMA_VERSION`, returning the
+/// version it started from, or None if it was already current. Files from a
+/// newer release are left alone; unknown keys are ignored when parsed.
+pub fn migrate_config(config: &mut Map<String, Value>) -> Option<u32> {
+    let from = stored_schema_version(config);
+    if from >= CONFIG_SCHEMA_VERSION {
+        return None;
+    }
+    for migration in MIGRATIONS.iter().filter(|m| m.to_version > from) {
+        eprintln!(
+            "Migrating config to schema v{}: {}",
+            migration.to_version, migration.description
+        );
+        (migration.apply)(config);
+    }
+    config.insert(SCHEMA_VERSION_KEY.to_string(), CONFIG_SCHEMA_VERSION.into());
+    Some(from)
+}
+
+/// `migrate_config` over JSON text: the migrated text and the version it
+/// started from, or None when the text is current or not a JSON object.
+pub fn migrate_config_json(content: &str) -> Option<(String, u32)> {
+    let Ok(Value::Object(mut config)) = serde_json::from_str(content) else {
+        return None;
+    };
+    let from = migrate_config(&mut config)?;
+    let json = serde_json::to_string_pretty(&config).ok()?;
+    Some((json, from))
+}
+
+/// Moves the value at `from` to `to` (key paths from the root), creating
+/// intermediate sections as needed. Renames are moves within one object.
+/// Nothing happens if `from` is absent or `to` already holds a value, so a
+/// step is safe to re-run.
+fn move_value(config: &mut Map<String, Value>, from: &[&str], to: &[&str]) {
+    let Some((from_key, from_parents)) = from.split_last() else {
+        return;
+    };
+    let Some((to_key, to_parents)) = to.split_last() else {
+        return;
+    };
+    let exists = to_parents
+        .iter()
+        .try_fold(&*config, |map, key| map.get(*key)?.as_object())
+        .is_some_and(|map| map.contains_key(*to_key));
+    if exists {
+        return;
+    }
+
+    let Some(source) = from_parents
+        .iter()
+        .try_fold(&mut *config, |map, key| map.get_mut(*key)?.as_object_mut())
+    else {
+        return;
+    };
+    let Some(value) = source.remove(*from_key) else {
+        return;
+    };
+
+    let mut target = config;
+    for key in to_parents {
+        let entry = target
+            .entry(key.to_string())
+            .or_insert_with(|| Value::Object(Map::new()));
+        if !entry.is_object() {
+            *entry = Value::Object(Map::new());
+        }
+        target = entry.as_object_mut().expect("just made an object");
+    }
+    target.insert(to_key.to_string(), value);
+}
+
+/// Top-level keys are the Rust field names except these sections, which are
+/// serialized in camelCase. Hand-edited files using the field names had the
+/// whole section silently ignored.
+fn rename_section_keys(config: &mut Map<String, Value>) {
+    for (old, new) in [
+        ("asset_content_version", "assetContentVersion"),
+        ("upgrade_badges", "upgradeBadges"),
+        ("timer_drift", "timerDrift"),
+        ("ocr_assist", "ocrAssist"),
+        ("stream_overlay", "streamOverlay"),
+        ("game_detection", "gameDetection"),
+    ] {
+        move_value(config, &[old], &[new]);
+    }
+}
+
+/// Older releases filled missing branch hotkeys with `Alt+0..4` while fresh
+/// installs got `Ctrl+Alt+0..4`. Only the untouched old defaults are remapped;
+/// a user's own binding stays as it is.
+fn remap_branch_hotkeys(config: &mut Map<String, Value>) {
+    let Some(hotkeys) = config.get_mut("hotkeys").and_then(Value::as_object_mut) else {
+        return;
+    };
+    for (key, digit) in [
+        ("activate_branch_main", 0),
+        ("activate_branch_1", 1),
+        ("activate_branch_2", 2),
+        ("activate_branch_3", 3),
+        ("activate_branch_4", 4),
+    ] {
+        let old_default = format!("Alt+{}", digit);
+        if hotkeys.get(key).and_then(Value::as_str) == Some(old_default.as_str()) {
+            hotkeys.insert(key.to_string(), format!("Ctrl+Alt+{}", digit).into());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::app_config::AppConfig;
+    use super::super::appearance::FontSize;
+    use super::super::recovery::{recover_config, ConfigLoadStatus};
+    use super::*;
+
+    const RELEASE_FIXTURES: &[(&str, &str)] = &[
+        ("1.5.0", include_str!("fixtures/config-1.5.0.json")),
+        ("1.6.0", include_str!("fixtures/config-1.6.0.json")),
+        ("1.7.0", include_str!("fixtures/config-1.7.0.json")),
+        ("1.8.0", include_str!("fixtures/config-1.8.0.json")),
+        ("1.9.0", include_str!("fixtures/config-1.9.0.json")),
+    ];
+
+    fn load(content: &str) -> AppConfig {
+        let (migrated, from) = migrate_config_json(content).expect("fixture needs migrating");
+        assert_eq!(from, 0);
+        let (config, report) = recover_config(&migrated);
+        assert_eq!(report.status, ConfigLoadStatus::Clean, "{:?}", report);
+        config
+    }
+
+    #[test]
+    fn test_release_fixtures_load_cleanly() {
+        for (release, content) in RELEASE_FIXTURES {
+            let config = load(content);
+            assert_eq!(config.schema_version, CONFIG_SCHEMA_VERSION, "{}", release);
+            assert_eq!(config.overlay_opacity, 0.65, "{}", release);
+            assert_eq!(config.font_size, FontSize::Large, "{}", release);
+            assert_eq!(config.hotkeys.toggle_overlay, "Ctrl+Alt+F1", "{}", release);
+            assert_eq!(
+                config.hotkeys.activate_branch_main, "Ctrl+Alt+0",
+                "{}",
+                release
+            );
+            assert_eq!(
+                config.hotkeys.activate_branch_4, "Ctrl+Alt+4",
+                "{}",
+                release
+            );
+            assert!(config.auto_advance.enabled, "{}", release);
+            assert_eq!(config.voice.unwrap().rate, 1.2, "{}", release);
+        }
+    }
+
+    #[test]
+    fn test_release_fixture_specifics() {
+        let v15 = load(RELEASE_FIXTURES[0].1);
+        assert!(!v15.content_protection);
+        assert_eq!(v15.hotkeys.toggle_pause, "Ctrl+Alt+F8");
+        assert!(v15.game_detection.is_none());
+
+        // A binding the user changed away from the old default is kept.
+        let v17 = load(RELEASE_FIXTURES[2].1);
+        assert_eq!(v17.hotkeys.activate_branch_2, "Ctrl+Shift+2");
+        assert!(v17.content_protection);
+
+        let v19 = load(RELEASE_FIXTURES[4].1);
+        let detection = v19.game_detection.unwrap();
+        assert_eq!(
+            detection.process_names,
+            vec!["RelicCardinal.exe", "AoE2DE_s.exe"]
+        );
+        assert_eq!(detection.poll_interval_ms, 2000);
+    }
+
+    #[test]
+    fn test_current_and_newer_files_are_not_migrated() {
+        let current = serde_json::to_string(&AppConfig::default()).unwrap();
+        assert!(migrate_config_json(&current).is_none());
+
+        let newer = format!(
+            "{{\"{}\": {}}}",
+            SCHEMA_VERSION_KEY,
+            CONFIG_SCHEMA_VERSION + 1
+        );
+        assert!(migrate_config_json(&newer).is_none());
+        assert!(migrate_config_json("[1]").is_none());
+    }
+
+    #[test]
+    fn test_snake_case_sections_are_renamed() {
+        let mut config = serde_json::json!({
+            "timer_drift": { "enabled": false },
+            "gameDetection": { "enabled": false },
+            "game_detection": { "enabled": true },
+        })
+        .as_object()
+        .unwrap()
+        .clone();
+        assert_eq!(migrate_config(&mut config), Some(0));
+        assert_eq!(config["timerDrift"]["enabled"], false);
+        assert!(!config.contains_key("timer_drift"));
+        // An existing serialized key wins over the misspelt one.
+        assert_eq!(config["gameDetection"]["enabled"], false);
+        assert_eq!(config[SCHEMA_VERSION_KEY], CONFIG_SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn test_move_value_into_nested_section() {
+        let mut config = serde_json::json!({ "speak_delta": true, "voice": 3 })
+            .as_object()
+            .unwrap()
+            .clone();
+        move_value(&mut config, &["speak_delta"], &["voice", "speakDelta"]);
+        assert_eq!(config["voice"]["speakDelta"], true);
+        assert!(!config.contains_key("speak_delta"));
+
+        move_value(&mut config, &["missing"], &["voice", "rate"]);
+        assert!(config["voice"].get("rate").is_none());
+    }
+}
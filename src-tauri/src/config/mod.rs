@@ -1,8 +1,33 @@
 mod app_config;
+mod appearance;
+mod assets;
 mod build_order;
+mod collections;
+mod data_dir;
+mod difficulty;
+mod game_plans;
+mod migrations;
+mod patch;
 mod persistence;
+mod preset;
+mod profiles;
+mod recovery;
+mod user_metadata;
+mod validation;
 
 // Re-export all public types and functions
 pub use app_config::*;
+pub use appearance::*;
+pub use assets::*;
 pub use build_order::*;
+pub use collections::*;
+pub use data_dir::*;
+pub use difficulty::*;
+pub use game_plans::*;
+pub use patch::*;
 pub use persistence::*;
+pub use preset::*;
+pub use profiles::*;
+pub use recovery::*;
+pub use user_metadata::*;
+pub use validation::*;
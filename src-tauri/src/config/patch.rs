@@ -0,0 +1,98 @@
+use super::app_config::AppConfig;
+use super::profiles::apply_merge_patch;
+use serde::Serialize;
+use serde_json::Value;
+
+/// Payload of `config-changed`: the new live config, its revision and the
+/// JSON paths that differ from the previous one (e.g. "voice.rate").
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigChange {
+    pub revision: u64,
+    pub changed_paths: Vec<String>,
+    pub config: AppConfig,
+}
+
+/// Applies a JSON merge patch (RFC 7396) to `config`. Keys use the names
+/// stored in `config.json`; `schemaVersion` is managed by the backend and
+/// ignored. Fails if the result no longer fits the config's types.
+pub fn patch_config(config: &AppConfig, patch: &Value) -> Result<AppConfig, String> {
+    let Value::Object(patch) = patch else {
+        return Err("Config patch must be a JSON object".to_string());
+    };
+    let mut patch = patch.clone();
+    patch.remove("schemaVersion");
+
+    let mut value = serde_json::to_value(config).map_err(|e| e.to_string())?;
+    apply_merge_patch(&mut value, &Value::Object(patch));
+    serde_json::from_value(value).map_err(|e| format!("Invalid config patch: {}", e))
+}
+
+/// Dotted paths of the leaves that differ between two configs. Objects are
+/// compared key by key; arrays and scalars as a whole.
+pub fn changed_paths(before: &AppConfig, after: &AppConfig) -> Vec<String> {
+    let mut paths = Vec::new();
+    if let (Ok(before), Ok(after)) = (serde_json::to_value(before), serde_json::to_value(after)) {
+        diff_values("", &before, &after, &mut paths);
+    }
+    paths
+}
+
+fn diff_values(path: &str, before: &Value, after: &Value, paths: &mut Vec<String>) {
+    match (before, after) {
+        (Value::Object(before), Value::Object(after)) => {
+            let mut keys: Vec<&String> = before.keys().chain(after.keys()).collect();
+            keys.sort();
+            keys.dedup();
+            for key in keys {
+                let child = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", path, key)
+                };
+                diff_values(
+                    &child,
+                    before.get(key).unwrap_or(&Value::Null),
+                    after.get(key).unwrap_or(&Value::Null),
+                    paths,
+                );
+            }
+        }
+        _ if before != after => paths.push(path.to_string()),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_patch_merges_sections() {
+        let config = AppConfig::default();
+        let patched = patch_config(
+            &config,
+            &json!({ "compact_mode": true, "voice": { "rate": 1.5 }, "schemaVersion": 0 }),
+        )
+        .unwrap();
+        assert!(patched.compact_mode);
+        let voice = patched.voice.as_ref().unwrap();
+        assert_eq!(voice.rate, 1.5);
+        assert_eq!(voice.enabled, config.voice.as_ref().unwrap().enabled);
+        assert_eq!(patched.schema_version, config.schema_version);
+
+        assert_eq!(
+            changed_paths(&config, &patched),
+            vec!["compact_mode", "voice.rate"]
+        );
+        assert!(changed_paths(&config, &config).is_empty());
+    }
+
+    #[test]
+    fn test_patch_rejects_wrong_types() {
+        let config = AppConfig::default();
+        assert!(patch_config(&config, &json!({ "compact_mode": "yes" })).is_err());
+        assert!(patch_config(&config, &json!([1])).is_err());
+    }
+}
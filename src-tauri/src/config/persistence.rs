@@ -1,37 +1,187 @@
+use serde::de::DeserializeOwned;
 use std::fs;
 use std::io::{ErrorKind, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use super::app_config::AppConfig;
 use super::build_order::{validate_build_order, BuildOrder};
+use super::collections::CollectionStore;
+use super::data_dir::data_dir;
+use super::game_plans::GamePlanStore;
+use super::migrations::migrate_config_json;
+use super::profiles::ProfileStore;
+use super::recovery::{recover_config, ConfigLoadReport, ConfigLoadStatus};
+use super::user_metadata::UserMetadataStore;
+use super::validation::{normalize_config, validate_config};
+
+fn get_app_dir() -> PathBuf {
+    let (app_dir, _) = data_dir();
+    fs::create_dir_all(&app_dir).ok();
+    app_dir
+}
 
 pub fn get_config_path() -> PathBuf {
-    let config_dir = dirs::config_dir()
-        .unwrap_or_else(|| PathBuf::from("."))
-        .join("aoe4-overlay");
-    fs::create_dir_all(&config_dir).ok();
-    config_dir.join("config.json")
+    get_app_dir().join("config.json")
+}
+
+pub fn get_collections_path() -> PathBuf {
+    get_app_dir().join("collections.json")
+}
+
+pub fn get_game_plans_path() -> PathBuf {
+    get_app_dir().join("game-plans.json")
+}
+
+pub fn get_profiles_path() -> PathBuf {
+    get_app_dir().join("profiles.json")
+}
+
+pub fn get_user_metadata_path() -> PathBuf {
+    get_app_dir().join("user-metadata.json")
 }
 
 pub fn get_build_orders_dir() -> PathBuf {
-    let config_dir = dirs::config_dir()
-        .unwrap_or_else(|| PathBuf::from("."))
-        .join("aoe4-overlay")
-        .join("build-orders");
+    let config_dir = get_app_dir().join("build-orders");
     fs::create_dir_all(&config_dir).ok();
     config_dir
 }
 
-pub fn load_config() -> AppConfig {
+/// Loads `config.json`, migrating files from older releases and salvaging
+/// what it can from a damaged one. Before either rewrite the original is
+/// copied aside (see `backup_config_file`) so it is never lost.
+pub fn load_config() -> (AppConfig, ConfigLoadReport) {
     let config_path = get_config_path();
-    if config_path.exists() {
-        if let Ok(content) = fs::read_to_string(&config_path) {
-            if let Ok(config) = serde_json::from_str(&content) {
-                return config;
-            }
+    let content = match fs::read_to_string(&config_path) {
+        Ok(content) => content,
+        Err(err) if err.kind() == ErrorKind::NotFound => {
+            let report = ConfigLoadReport {
+                status: ConfigLoadStatus::Missing,
+                ..ConfigLoadReport::default()
+            };
+            return (AppConfig::default(), report);
+        }
+        Err(err) => {
+            let report = ConfigLoadReport {
+                status: ConfigLoadStatus::Reset,
+                error: Some(err.to_string()),
+                ..ConfigLoadReport::default()
+            };
+            return (AppConfig::default(), report);
+        }
+    };
+
+    let migrated = migrate_config_json(&content);
+    let migrated_from = migrated.as_ref().map(|(_, from)| *from);
+    if let Some(from) = migrated_from {
+        if let Err(err) = backup_config_file(&config_path, &format!("v{}", from)) {
+            eprintln!(
+                "Failed to back up config {:?} before migrating: {}",
+                config_path, err
+            );
         }
     }
-    AppConfig::default()
+    let content = migrated.map(|(json, _)| json).unwrap_or(content);
+
+    let (mut config, mut report) = recover_config(&content);
+    report.migrated_from = migrated_from;
+    report.adjusted_fields = normalize_config(&mut config);
+    for issue in &report.adjusted_fields {
+        eprintln!("Config {:?}: {}", config_path, issue.message);
+    }
+    if report.needs_backup() {
+        match backup_config_file(&config_path, "corrupt") {
+            Ok(backup) => report.backup_path = Some(backup.to_string_lossy().into_owned()),
+            Err(err) => eprintln!(
+                "Failed to back up damaged config {:?}: {}",
+                config_path, err
+            ),
+        }
+        eprintln!(
+            "Recovered config {:?} ({:?}), reset fields: {:?}",
+            config_path, report.status, report.recovered_fields
+        );
+    }
+    if migrated_from.is_some() {
+        // Persist the migration so it runs once, not on every start.
+        let json = serde_json::to_string_pretty(&config).map_err(|e| e.to_string());
+        if let Err(err) =
+            json.and_then(|json| atomic_write(&config_path, json).map_err(|e| e.to_string()))
+        {
+            eprintln!("Failed to save migrated config {:?}: {}", config_path, err);
+        }
+    }
+    (config, report)
+}
+
+/// Reads `config.json` after it was edited outside the app. Older layouts
+/// are migrated in memory and out-of-range values clamped, but unlike
+/// `load_config` nothing is salvaged field by field: a file with any
+/// unreadable or unusable value is rejected whole, so a half-finished edit
+/// never resets settings. The file itself is left untouched.
+pub fn read_config_file(path: &Path) -> Result<AppConfig, String> {
+    let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let content = migrate_config_json(&content)
+        .map(|(json, _)| json)
+        .unwrap_or(content);
+    let mut config: AppConfig = serde_json::from_str(&content).map_err(|e| e.to_string())?;
+    validate_config(&mut config)?;
+    Ok(config)
+}
+
+/// Copies `path` to `<stem>.<label>-<unix seconds>.json` next to it.
+pub fn backup_config_file(path: &Path, label: &str) -> std::io::Result<PathBuf> {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    let stem = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("config");
+    let backup = path.with_file_name(format!("{}.{}-{}.json", stem, label, seconds));
+    fs::copy(path, &backup)?;
+    Ok(backup)
+}
+
+/// Reads a JSON store such as `user-metadata.json`; a missing file is an
+/// empty store. A file that exists but cannot be parsed is copied aside (see
+/// `backup_config_file`) and reported as an error, so the caller can start
+/// empty without a later save destroying what was there.
+fn load_store<T: DeserializeOwned + Default>(path: &Path, what: &str) -> Result<T, String> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(T::default()),
+        Err(err) => return Err(format!("Failed to read {} {:?}: {}", what, path, err)),
+    };
+    serde_json::from_str(&content).map_err(|err| {
+        let backup = match backup_config_file(path, "corrupt") {
+            Ok(backup) => format!("copied to {:?}", backup),
+            Err(e) => format!("backup failed: {}", e),
+        };
+        format!("Invalid {} {:?} ({}): {}", what, path, backup, err)
+    })
+}
+
+pub fn load_collections() -> Result<CollectionStore, String> {
+    load_store(&get_collections_path(), "collections file")
+}
+
+pub fn load_profiles() -> Result<ProfileStore, String> {
+    load_store(&get_profiles_path(), "profiles file")
+}
+
+pub fn load_game_plans() -> Result<GamePlanStore, String> {
+    load_store(&get_game_plans_path(), "game plans file")
+}
+
+pub fn load_user_metadata() -> Result<UserMetadataStore, String> {
+    load_store(&get_user_metadata_path(), "user metadata file")
+}
+
+pub fn save_user_metadata(store: &UserMetadataStore) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(store).map_err(|e| e.to_string())?;
+    atomic_write(get_user_metadata_path(), json).map_err(|e| e.to_string())
 }
 
 pub fn atomic_write<P: AsRef<std::path::Path>, C: AsRef<[u8]>>(
@@ -101,3 +251,33 @@ pub fn load_build_orders() -> Vec<BuildOrder> {
     }
     orders
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_store_backs_up_unparsable_file() {
+        let dir = std::env::temp_dir().join(format!("aoe4-overlay-store-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("user-metadata.json");
+
+        let missing: UserMetadataStore = load_store(&path, "test store").unwrap();
+        assert_eq!(missing, UserMetadataStore::default());
+
+        fs::write(&path, "{ not json").unwrap();
+        let err = load_store::<UserMetadataStore>(&path, "test store").unwrap_err();
+        assert!(err.contains("copied to"), "{}", err);
+        let backups: Vec<_> = fs::read_dir(&dir)
+            .unwrap()
+            .flatten()
+            .filter(|e| e.file_name().to_string_lossy().contains(".corrupt-"))
+            .collect();
+        assert_eq!(backups.len(), 1);
+        assert_eq!(fs::read_to_string(backups[0].path()).unwrap(), "{ not json");
+        // The original is left for the user; nothing was written over it.
+        assert_eq!(fs::read_to_string(&path).unwrap(), "{ not json");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,311 @@
+//! Config presets: a JSON file with some or all config sections, for moving
+//! settings between machines or copying a coach's voice and reminder setup.
+//! Settings tied to one machine's screen are never written or read.
+
+use super::app_config::AppConfig;
+use super::migrations::{migrate_config, CONFIG_SCHEMA_VERSION};
+use super::patch::changed_paths;
+use super::profiles::config_object;
+use super::validation::{validate_config, ConfigFieldIssue};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+pub const CONFIG_PRESET_FORMAT: &str = "aoe4-overlay-config";
+pub const MAX_CONFIG_PRESET_SIZE: u64 = 1024 * 1024; // 1MB limit
+
+/// Key paths (as stored in `config.json`) that only make sense on the
+/// machine they were set on.
+const MACHINE_SPECIFIC_FIELDS: &[&[&str]] = &[
+    &["window_position"],
+    &["window_size"],
+    &["ocrAssist", "captureRegion"],
+];
+
+const SCHEMA_VERSION_KEY: &str = "schemaVersion";
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigPreset {
+    pub format: String,
+    /// Config schema the settings were written with; older ones are migrated.
+    pub schema_version: u32,
+    /// App version that wrote the preset, for diagnostics only.
+    #[serde(default)]
+    pub app_version: String,
+    /// Top-level config keys, e.g. "hotkeys", "voice", "reminders".
+    pub settings: Map<String, Value>,
+}
+
+/// One setting an import would change.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigValueChange {
+    /// JSON path, e.g. "voice.rate".
+    pub path: String,
+    pub current: Value,
+    pub incoming: Value,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigImportPreview {
+    /// Sections the import would take from the preset.
+    pub sections: Vec<String>,
+    pub changes: Vec<ConfigValueChange>,
+    /// Preset values clamped into range.
+    pub adjusted_fields: Vec<ConfigFieldIssue>,
+}
+
+fn remove_path(map: &mut Map<String, Value>, path: &[&str]) -> Option<Value> {
+    let (key, parents) = path.split_last()?;
+    let parent = parents
+        .iter()
+        .try_fold(map, |map, key| map.get_mut(*key)?.as_object_mut())?;
+    parent.remove(*key)
+}
+
+fn insert_path(map: &mut Map<String, Value>, path: &[&str], value: Value) {
+    let Some((key, parents)) = path.split_last() else {
+        return;
+    };
+    if let Some(parent) = parents
+        .iter()
+        .try_fold(map, |map, key| map.get_mut(*key)?.as_object_mut())
+    {
+        parent.insert(key.to_string(), value);
+    }
+}
+
+fn value_at<'a>(value: &'a Value, path: &str) -> &'a Value {
+    path.split('.')
+        .try_fold(value, |value, key| value.get(key))
+        .unwrap_or(&Value::Null)
+}
+
+fn strip_machine_specific(settings: &mut Map<String, Value>) {
+    for path in MACHINE_SPECIFIC_FIELDS {
+        remove_path(settings, path);
+    }
+}
+
+fn is_machine_specific(section: &str) -> bool {
+    MACHINE_SPECIFIC_FIELDS
+        .iter()
+        .any(|path| path.len() == 1 && path[0] == section)
+}
+
+/// `config` as a preset holding `sections` (every section when None).
+pub fn export_config_preset(
+    config: &AppConfig,
+    sections: Option<&[String]>,
+) -> Result<ConfigPreset, String> {
+    let mut all = config_object(config)?;
+    all.remove(SCHEMA_VERSION_KEY);
+    strip_machine_specific(&mut all);
+
+    let settings = match sections {
+        None => all,
+        Some(sections) => {
+            let mut selected = Map::new();
+            for section in sections {
+                if is_machine_specific(section) {
+                    return Err(format!(
+                        "\"{}\" is specific to this machine and cannot be exported",
+                        section
+                    ));
+                }
+                let value = all
+                    .get(section)
+                    .ok_or_else(|| format!("Unknown config section \"{}\"", section))?;
+                selected.insert(section.clone(), value.clone());
+            }
+            selected
+        }
+    };
+    if settings.is_empty() {
+        return Err("Nothing to export".to_string());
+    }
+    Ok(ConfigPreset {
+        format: CONFIG_PRESET_FORMAT.to_string(),
+        schema_version: CONFIG_SCHEMA_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        settings,
+    })
+}
+
+/// Parses a preset file, bringing settings from older releases up to date.
+pub fn read_config_preset(content: &str) -> Result<ConfigPreset, String> {
+    let mut preset: ConfigPreset =
+        serde_json::from_str(content).map_err(|e| format!("Invalid config preset: {}", e))?;
+    if preset.format != CONFIG_PRESET_FORMAT {
+        return Err(format!(
+            "Not a config preset (format \"{}\")",
+            preset.format
+        ));
+    }
+    if preset.schema_version > CONFIG_SCHEMA_VERSION {
+        return Err(format!(
+            "Preset is from a newer version of the app (config schema v{})",
+            preset.schema_version
+        ));
+    }
+    if preset.schema_version < CONFIG_SCHEMA_VERSION {
+        preset
+            .settings
+            .insert(SCHEMA_VERSION_KEY.to_string(), preset.schema_version.into());
+        migrate_config(&mut preset.settings);
+        preset.schema_version = CONFIG_SCHEMA_VERSION;
+    }
+    preset.settings.remove(SCHEMA_VERSION_KEY);
+    strip_machine_specific(&mut preset.settings);
+    Ok(preset)
+}
+
+/// The config importing `sections` of `preset` (all it has when None) would
+/// produce, and what would change. Sections are copied whole, so the result
+/// matches the exporting machine exactly; this machine's window placement and
+/// capture region are kept. Fails without changing anything if a value does
+/// not fit or is unusable.
+pub fn plan_config_import(
+    current: &AppConfig,
+    preset: &ConfigPreset,
+    sections: Option<&[String]>,
+) -> Result<(AppConfig, ConfigImportPreview), String> {
+    let sections: Vec<String> = match sections {
+        None => preset.settings.keys().cloned().collect(),
+        Some(sections) => sections.to_vec(),
+    };
+
+    let original = config_object(current)?;
+    let mut merged = original.clone();
+    for section in &sections {
+        let value = preset
+            .settings
+            .get(section)
+            .ok_or_else(|| format!("Preset has no \"{}\" section", section))?;
+        if !merged.contains_key(section) {
+            return Err(format!("Unknown config section \"{}\"", section));
+        }
+        merged.insert(section.clone(), value.clone());
+    }
+    for path in MACHINE_SPECIFIC_FIELDS {
+        let mut original = original.clone();
+        match remove_path(&mut original, path) {
+            Some(value) => insert_path(&mut merged, path, value),
+            None => {
+                remove_path(&mut merged, path);
+            }
+        }
+    }
+
+    let mut next: AppConfig = serde_json::from_value(Value::Object(merged))
+        .map_err(|e| format!("Preset does not fit the config: {}", e))?;
+    let adjusted_fields = validate_config(&mut next)?;
+
+    let (before, after) = (
+        Value::Object(original),
+        Value::Object(config_object(&next)?),
+    );
+    let changes = changed_paths(current, &next)
+        .into_iter()
+        .map(|path| ConfigValueChange {
+            current: value_at(&before, &path).clone(),
+            incoming: value_at(&after, &path).clone(),
+            path,
+        })
+        .collect();
+    let preview = ConfigImportPreview {
+        sections,
+        changes,
+        adjusted_fields,
+    };
+    Ok((next, preview))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{OcrCaptureRegion, WindowPosition};
+
+    fn coach() -> AppConfig {
+        let mut config = AppConfig {
+            window_position: Some(WindowPosition { x: 5, y: 5 }),
+            ..AppConfig::default()
+        };
+        config.voice.as_mut().unwrap().rate = 1.4;
+        config.reminders.as_mut().unwrap().enabled = true;
+        config
+    }
+
+    #[test]
+    fn test_export_skips_machine_specific_fields() {
+        let preset = export_config_preset(&coach(), None).unwrap();
+        assert!(!preset.settings.contains_key("window_position"));
+        assert!(!preset.settings.contains_key("schemaVersion"));
+        assert!(preset.settings.contains_key("hotkeys"));
+
+        let sections = ["voice".to_string(), "reminders".to_string()];
+        let preset = export_config_preset(&coach(), Some(&sections)).unwrap();
+        assert_eq!(preset.settings.len(), 2);
+
+        assert!(export_config_preset(&coach(), Some(&["window_size".to_string()])).is_err());
+        assert!(export_config_preset(&coach(), Some(&["nope".to_string()])).is_err());
+    }
+
+    #[test]
+    fn test_import_copies_sections_and_keeps_local_placement() {
+        let sections = ["voice".to_string(), "reminders".to_string()];
+        let preset = export_config_preset(&coach(), Some(&sections)).unwrap();
+        let json = serde_json::to_string(&preset).unwrap();
+        let preset = read_config_preset(&json).unwrap();
+
+        let mut current = AppConfig {
+            window_position: Some(WindowPosition { x: 900, y: 40 }),
+            ..AppConfig::default()
+        };
+        if let Some(ocr) = current.ocr_assist.as_mut() {
+            ocr.capture_region = Some(OcrCaptureRegion {
+                x: 1,
+                y: 2,
+                width: 3,
+                height: 4,
+            });
+        }
+
+        let (next, preview) = plan_config_import(&current, &preset, None).unwrap();
+        assert_eq!(next.voice.as_ref().unwrap().rate, 1.4);
+        assert!(next.reminders.as_ref().unwrap().enabled);
+        assert_eq!(next.window_position.as_ref().unwrap().x, 900);
+        assert_eq!(
+            serde_json::to_value(&next.ocr_assist).unwrap(),
+            serde_json::to_value(&current.ocr_assist).unwrap()
+        );
+        let rate = preview
+            .changes
+            .iter()
+            .find(|c| c.path == "voice.rate")
+            .unwrap();
+        assert_eq!(rate.incoming.as_f64().map(|r| r as f32), Some(1.4));
+
+        let hotkeys = ["hotkeys".to_string()];
+        assert!(plan_config_import(&current, &preset, Some(&hotkeys)).is_err());
+    }
+
+    #[test]
+    fn test_read_rejects_foreign_and_bad_values() {
+        assert!(read_config_preset("{}").is_err());
+        let newer = format!(
+            r#"{{"format":"{}","schemaVersion":{},"settings":{{}}}}"#,
+            CONFIG_PRESET_FORMAT,
+            CONFIG_SCHEMA_VERSION + 1
+        );
+        assert!(read_config_preset(&newer).is_err());
+
+        let bad = format!(
+            r#"{{"format":"{}","schemaVersion":0,"settings":{{"overlay_opacity":"high"}}}}"#,
+            CONFIG_PRESET_FORMAT
+        );
+        let preset = read_config_preset(&bad).unwrap();
+        assert!(plan_config_import(&AppConfig::default(), &preset, None).is_err());
+    }
+}
@@ -0,0 +1,399 @@
+use super::app_config::AppConfig;
+use super::collections::slugify;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+pub const MAX_PROFILES: usize = 20;
+pub const MAX_PROFILE_NAME_LEN: usize = 64;
+
+/// A named set of settings layered on the global config, e.g. "Ranked" or
+/// "Streaming". `overlay` holds top-level config keys as stored in
+/// `config.json`; a full profile has all of them, a partial one only those it
+/// changes. Nested sections merge, so `{"voice": {"rate": 1.4}}` keeps the
+/// rest of `voice`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigProfile {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub overlay: Map<String, Value>,
+}
+
+/// Persisted as `profiles.json` next to `config.json`, which keeps holding
+/// the global config the active profile is layered on.
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileStore {
+    #[serde(default)]
+    pub profiles: Vec<ConfigProfile>,
+    #[serde(default)]
+    pub active_profile_id: Option<String>,
+}
+
+/// Applies an RFC 7396 JSON merge patch: objects merge key by key, `null`
+/// removes a key and anything else replaces the target.
+pub fn apply_merge_patch(target: &mut Value, patch: &Value) {
+    let Value::Object(patch) = patch else {
+        *target = patch.clone();
+        return;
+    };
+    if !target.is_object() {
+        *target = Value::Object(Map::new());
+    }
+    let target = target.as_object_mut().expect("just made an object");
+    for (key, value) in patch {
+        if value.is_null() {
+            target.remove(key);
+        } else {
+            apply_merge_patch(target.entry(key.clone()).or_insert(Value::Null), value);
+        }
+    }
+}
+
+pub(super) fn config_object(config: &AppConfig) -> Result<Map<String, Value>, String> {
+    match serde_json::to_value(config).map_err(|e| e.to_string())? {
+        Value::Object(map) => Ok(map),
+        _ => Err("Config did not serialize to an object".to_string()),
+    }
+}
+
+/// Routes each value of `live` to `overlay` if it overrides that key, or to
+/// `global` otherwise. Sections that are objects in both are split key by key.
+fn split_into(
+    overlay: &mut Map<String, Value>,
+    global: &mut Map<String, Value>,
+    live: Map<String, Value>,
+) {
+    for (key, value) in live {
+        match (overlay.get_mut(&key), value) {
+            (Some(Value::Object(section)), Value::Object(value)) => {
+                let global_section = global
+                    .entry(key)
+                    .or_insert_with(|| Value::Object(Map::new()));
+                if !global_section.is_object() {
+                    *global_section = Value::Object(Map::new());
+                }
+                let global_section = global_section.as_object_mut().expect("just made an object");
+                split_into(section, global_section, value);
+            }
+            (Some(slot), value) => *slot = value,
+            (None, value) => {
+                global.insert(key, value);
+            }
+        }
+    }
+}
+
+impl ProfileStore {
+    fn find_mut(&mut self, id: &str) -> Result<&mut ConfigProfile, String> {
+        self.profiles
+            .iter_mut()
+            .find(|p| p.id == id)
+            .ok_or_else(|| format!("Profile \"{}\" not found", id))
+    }
+
+    pub fn active_profile(&self) -> Option<&ConfigProfile> {
+        let id = self.active_profile_id.as_deref()?;
+        self.profiles.iter().find(|p| p.id == id)
+    }
+
+    /// The live config: `global` with the active profile layered on.
+    pub fn resolve(&self, global: &AppConfig) -> Result<AppConfig, String> {
+        let Some(profile) = self.active_profile() else {
+            return Ok(global.clone());
+        };
+        let mut value = Value::Object(config_object(global)?);
+        apply_merge_patch(&mut value, &Value::Object(profile.overlay.clone()));
+        serde_json::from_value(value).map_err(|e| {
+            format!(
+                "Profile \"{}\" does not fit the config: {}",
+                profile.name, e
+            )
+        })
+    }
+
+    /// Splits an edited live config back into a new global config and the
+    /// active profile: settings the profile overrides are written into its
+    /// overlay, everything else into the global config. Within a section the
+    /// profile only partly overrides, only those keys go to the profile. A
+    /// missing active profile is ignored, as in `resolve`.
+    pub fn split(&mut self, global: &AppConfig, live: &AppConfig) -> Result<AppConfig, String> {
+        let Some(id) = self.active_profile_id.clone() else {
+            return Ok(live.clone());
+        };
+        let Ok(profile) = self.find_mut(&id) else {
+            return Ok(live.clone());
+        };
+        let mut global_map = config_object(global)?;
+        split_into(&mut profile.overlay, &mut global_map, config_object(live)?);
+        serde_json::from_value(Value::Object(global_map)).map_err(|e| e.to_string())
+    }
+
+    /// Captures `fields` (top-level config keys) of `current` as a new
+    /// profile, or every setting when `fields` is None.
+    pub fn create(
+        &mut self,
+        name: &str,
+        fields: Option<&[String]>,
+        current: &AppConfig,
+    ) -> Result<ConfigProfile, String> {
+        let name = validate_profile_name(name)?;
+        if self.profiles.len() >= MAX_PROFILES {
+            return Err(format!("Cannot create more than {} profiles", MAX_PROFILES));
+        }
+
+        let mut all = config_object(current)?;
+        all.remove("schemaVersion");
+        let overlay = match fields {
+            None => all,
+            Some(fields) => {
+                let mut overlay = Map::new();
+                for field in fields {
+                    let value = all
+                        .get(field)
+                        .ok_or_else(|| format!("Unknown config field \"{}\"", field))?;
+                    overlay.insert(field.clone(), value.clone());
+                }
+                overlay
+            }
+        };
+
+        let profile = ConfigProfile {
+            id: self.unique_id(&name),
+            name,
+            overlay,
+        };
+        self.profiles.push(profile.clone());
+        Ok(profile)
+    }
+
+    pub fn duplicate(&mut self, id: &str, name: &str) -> Result<ConfigProfile, String> {
+        let name = validate_profile_name(name)?;
+        if self.profiles.len() >= MAX_PROFILES {
+            return Err(format!("Cannot create more than {} profiles", MAX_PROFILES));
+        }
+        let overlay = self.find_mut(id)?.overlay.clone();
+        let profile = ConfigProfile {
+            id: self.unique_id(&name),
+            name,
+            overlay,
+        };
+        self.profiles.push(profile.clone());
+        Ok(profile)
+    }
+
+    fn unique_id(&self, name: &str) -> String {
+        let base = slugify(name, "profile");
+        let mut id = base.clone();
+        let mut suffix = 2;
+        while self.profiles.iter().any(|p| p.id == id) {
+            id = format!("{}-{}", base, suffix);
+            suffix += 1;
+        }
+        id
+    }
+
+    pub fn rename(&mut self, id: &str, name: &str) -> Result<(), String> {
+        let name = validate_profile_name(name)?;
+        self.find_mut(id)?.name = name;
+        Ok(())
+    }
+
+    pub fn delete(&mut self, id: &str) -> Result<(), String> {
+        let before = self.profiles.len();
+        self.profiles.retain(|p| p.id != id);
+        if self.profiles.len() == before {
+            return Err(format!("Profile \"{}\" not found", id));
+        }
+        if self.active_profile_id.as_deref() == Some(id) {
+            self.active_profile_id = None;
+        }
+        Ok(())
+    }
+
+    /// Selects the profile layered on the global config (`None` = global only).
+    pub fn set_active(&mut self, id: Option<String>) -> Result<(), String> {
+        if let Some(id) = &id {
+            self.find_mut(id)?;
+        }
+        self.active_profile_id = id;
+        Ok(())
+    }
+
+    /// Moves to the next profile in list order, passing through "no profile"
+    /// after the last one. Returns the new active id.
+    pub fn cycle(&mut self) -> Option<String> {
+        let next = match self.active_profile_id.as_deref() {
+            None => self.profiles.first(),
+            Some(id) => self
+                .profiles
+                .iter()
+                .position(|p| p.id == id)
+                .and_then(|i| self.profiles.get(i + 1)),
+        };
+        self.active_profile_id = next.map(|p| p.id.clone());
+        self.active_profile_id.clone()
+    }
+}
+
+fn validate_profile_name(name: &str) -> Result<String, String> {
+    let trimmed = name.trim();
+    if trimmed.is_empty() {
+        return Err("Profile name is required".to_string());
+    }
+    if trimmed.chars().count() > MAX_PROFILE_NAME_LEN {
+        return Err(format!(
+            "Profile name exceeds max length of {} characters",
+            MAX_PROFILE_NAME_LEN
+        ));
+    }
+    Ok(trimmed.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn streaming(store: &mut ProfileStore) -> String {
+        let mut current = AppConfig::default();
+        current.voice.as_mut().unwrap().enabled = false;
+        current.content_protection = true;
+        let fields = ["voice".to_string(), "content_protection".to_string()];
+        store
+            .create("Streaming", Some(&fields), &current)
+            .unwrap()
+            .id
+    }
+
+    #[test]
+    fn test_merge_patch_merges_objects_and_removes_nulls() {
+        let mut target = json!({ "a": 1, "voice": { "rate": 1.0, "enabled": true } });
+        apply_merge_patch(
+            &mut target,
+            &json!({ "a": null, "voice": { "rate": 1.4 }, "b": [1] }),
+        );
+        assert_eq!(
+            target,
+            json!({ "voice": { "rate": 1.4, "enabled": true }, "b": [1] })
+        );
+    }
+
+    #[test]
+    fn test_partial_profile_layers_on_global() {
+        let mut store = ProfileStore::default();
+        let id = streaming(&mut store);
+        assert_eq!(store.profiles[0].overlay.len(), 2);
+
+        let global = AppConfig {
+            overlay_opacity: 0.5,
+            ..AppConfig::default()
+        };
+        assert!(!store.resolve(&global).unwrap().content_protection);
+
+        store.set_active(Some(id)).unwrap();
+        let live = store.resolve(&global).unwrap();
+        assert!(live.content_protection);
+        assert!(!live.voice.unwrap().enabled);
+        assert_eq!(live.overlay_opacity, 0.5);
+    }
+
+    #[test]
+    fn test_split_routes_edits_to_profile_or_global() {
+        let mut store = ProfileStore::default();
+        let id = streaming(&mut store);
+        store.set_active(Some(id)).unwrap();
+        let global = AppConfig::default();
+
+        let mut live = store.resolve(&global).unwrap();
+        live.content_protection = false;
+        live.overlay_opacity = 0.4;
+        let new_global = store.split(&global, &live).unwrap();
+
+        assert_eq!(new_global.overlay_opacity, 0.4);
+        assert!(!new_global.content_protection);
+        assert_eq!(store.profiles[0].overlay["content_protection"], false);
+        assert_eq!(store.resolve(&new_global).unwrap().overlay_opacity, 0.4);
+    }
+
+    #[test]
+    fn test_split_keeps_partial_sections_partial() {
+        let mut store = ProfileStore {
+            profiles: vec![ConfigProfile {
+                id: "fast".to_string(),
+                name: "Fast".to_string(),
+                overlay: json!({ "voice": { "rate": 1.4 } })
+                    .as_object()
+                    .unwrap()
+                    .clone(),
+            }],
+            active_profile_id: Some("fast".to_string()),
+        };
+        let global = AppConfig::default();
+
+        let mut live = store.resolve(&global).unwrap();
+        let voice = live.voice.as_mut().unwrap();
+        voice.enabled = !voice.enabled;
+        voice.rate = 1.6;
+        let new_global = store.split(&global, &live).unwrap();
+
+        let overlay = &store.profiles[0].overlay;
+        assert_eq!(overlay.len(), 1);
+        assert_eq!(overlay["voice"].as_object().unwrap().len(), 1);
+        assert_eq!(
+            overlay["voice"]["rate"].as_f64().map(|r| r as f32),
+            Some(1.6)
+        );
+        let global_voice = new_global.voice.unwrap();
+        assert_eq!(global_voice.enabled, live.voice.as_ref().unwrap().enabled);
+        assert_eq!(global_voice.rate, global.voice.unwrap().rate);
+    }
+
+    #[test]
+    fn test_split_ignores_missing_active_profile() {
+        let mut store = ProfileStore {
+            profiles: Vec::new(),
+            active_profile_id: Some("gone".to_string()),
+        };
+        let live = AppConfig {
+            overlay_opacity: 0.4,
+            ..AppConfig::default()
+        };
+        let new_global = store.split(&AppConfig::default(), &live).unwrap();
+        assert_eq!(new_global.overlay_opacity, 0.4);
+    }
+
+    #[test]
+    fn test_create_full_duplicate_and_unknown_field() {
+        let mut store = ProfileStore::default();
+        let full = store.create("Ranked", None, &AppConfig::default()).unwrap();
+        assert!(full.overlay.contains_key("hotkeys"));
+        assert!(!full.overlay.contains_key("schemaVersion"));
+
+        let copy = store.duplicate("ranked", "Ranked").unwrap();
+        assert_eq!(copy.id, "ranked-2");
+        assert_eq!(copy.overlay, full.overlay);
+
+        let bad = ["nope".to_string()];
+        assert!(store
+            .create("Bad", Some(&bad), &AppConfig::default())
+            .is_err());
+        assert!(store.rename("ranked", " ").is_err());
+    }
+
+    #[test]
+    fn test_cycle_passes_through_global() {
+        let mut store = ProfileStore::default();
+        let a = streaming(&mut store);
+        let b = store.duplicate(&a, "Practice").unwrap().id;
+
+        assert_eq!(store.cycle(), Some(a.clone()));
+        assert_eq!(store.cycle(), Some(b.clone()));
+        assert_eq!(store.cycle(), None);
+
+        store.set_active(Some(b.clone())).unwrap();
+        store.delete(&b).unwrap();
+        assert!(store.active_profile_id.is_none());
+    }
+}
@@ -0,0 +1,221 @@
+use super::app_config::AppConfig;
+use super::validation::ConfigFieldIssue;
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+/// How `config.json` was read at startup, for the settings window to report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum ConfigLoadStatus {
+    /// Read as is.
+    #[default]
+    Clean,
+    /// No file yet; defaults are in use.
+    Missing,
+    /// Some fields were invalid and replaced by their defaults.
+    Recovered,
+    /// The file was unreadable as a whole; every setting is a default.
+    Reset,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigLoadReport {
+    pub status: ConfigLoadStatus,
+    /// JSON paths ("voice.rate", "hotkeys") that were reset to defaults.
+    pub recovered_fields: Vec<String>,
+    /// Copy of the original file, kept whenever anything was discarded.
+    pub backup_path: Option<String>,
+    /// Parse error for a file that could not be read at all.
+    pub error: Option<String>,
+    /// Schema version the file was migrated from, if it was.
+    pub migrated_from: Option<u32>,
+    /// Values clamped or replaced because they were out of range.
+    pub adjusted_fields: Vec<ConfigFieldIssue>,
+}
+
+impl ConfigLoadReport {
+    pub fn needs_backup(&self) -> bool {
+        matches!(
+            self.status,
+            ConfigLoadStatus::Recovered | ConfigLoadStatus::Reset
+        )
+    }
+}
+
+/// Reads `content` as an `AppConfig`, keeping every field that is valid and
+/// defaulting only the broken ones. Sections are salvaged field by field, so a
+/// bad `voice.rate` keeps the rest of `voice`.
+pub fn recover_config(content: &str) -> (AppConfig, ConfigLoadReport) {
+    if let Ok(config) = serde_json::from_str::<AppConfig>(content) {
+        return (config, ConfigLoadReport::default());
+    }
+
+    let incoming = match serde_json::from_str::<Value>(content) {
+        Ok(Value::Object(fields)) => fields,
+        Ok(_) => return reset("Config is not a JSON object".to_string()),
+        Err(e) => return reset(e.to_string()),
+    };
+
+    let mut root = serde_json::to_value(AppConfig::default()).unwrap_or(Value::Null);
+    let mut recovered = Vec::new();
+    salvage(&mut root, &mut Vec::new(), &incoming, &mut recovered);
+
+    match serde_json::from_value::<AppConfig>(root) {
+        Ok(config) => (
+            config,
+            ConfigLoadReport {
+                status: ConfigLoadStatus::Recovered,
+                recovered_fields: recovered,
+                ..ConfigLoadReport::default()
+            },
+        ),
+        // Only reachable if the defaults themselves stop deserializing.
+        Err(e) => reset(e.to_string()),
+    }
+}
+
+fn reset(error: String) -> (AppConfig, ConfigLoadReport) {
+    (
+        AppConfig::default(),
+        ConfigLoadReport {
+            status: ConfigLoadStatus::Reset,
+            error: Some(error),
+            ..ConfigLoadReport::default()
+        },
+    )
+}
+
+fn parent_mut<'a>(root: &'a mut Value, path: &[String]) -> Option<&'a mut Map<String, Value>> {
+    path.iter()
+        .try_fold(root, |value, key| value.get_mut(key))?
+        .as_object_mut()
+}
+
+/// Copies each field of `incoming` into `root` at `path`, keeping it only if
+/// the whole config still deserializes. Objects that fail as a whole are
+/// retried field by field; anything else that fails keeps its default and is
+/// recorded in `recovered`, as are required fields `incoming` lacks.
+fn salvage(
+    root: &mut Value,
+    path: &mut Vec<String>,
+    incoming: &Map<String, Value>,
+    recovered: &mut Vec<String>,
+) {
+    for (key, value) in incoming {
+        let Some(parent) = parent_mut(root, path) else {
+            return;
+        };
+        let previous = parent.insert(key.clone(), value.clone());
+        if serde_json::from_value::<AppConfig>(root.clone()).is_ok() {
+            continue;
+        }
+
+        let parent = parent_mut(root, path).expect("parent exists");
+        match previous.clone() {
+            Some(previous) => parent.insert(key.clone(), previous),
+            None => parent.remove(key),
+        };
+        match (value, previous) {
+            (Value::Object(fields), Some(Value::Object(_))) => {
+                path.push(key.clone());
+                salvage(root, path, fields, recovered);
+                path.pop();
+            }
+            _ => {
+                let mut field = path.clone();
+                field.push(key.clone());
+                recovered.push(field.join("."));
+            }
+        }
+    }
+
+    let Some(parent) = parent_mut(root, path) else {
+        return;
+    };
+    let absent: Vec<String> = parent
+        .keys()
+        .filter(|key| !incoming.contains_key(*key))
+        .cloned()
+        .collect();
+    for key in absent {
+        let mut without = root.clone();
+        if let Some(parent) = parent_mut(&mut without, path) {
+            parent.remove(&key);
+        }
+        if serde_json::from_value::<AppConfig>(without).is_err() {
+            let mut field = path.clone();
+            field.push(key);
+            recovered.push(field.join("."));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::appearance::Theme;
+    use super::*;
+
+    fn config_json(edit: impl FnOnce(&mut Value)) -> String {
+        let mut value = serde_json::to_value(AppConfig::default()).unwrap();
+        value["overlay_opacity"] = serde_json::json!(0.55);
+        value["theme"] = serde_json::json!("light");
+        edit(&mut value);
+        value.to_string()
+    }
+
+    #[test]
+    fn test_valid_config_is_clean() {
+        let (config, report) = recover_config(&config_json(|_| {}));
+        assert_eq!(report.status, ConfigLoadStatus::Clean);
+        assert_eq!(config.overlay_opacity, 0.55);
+        assert!(!report.needs_backup());
+    }
+
+    #[test]
+    fn test_broken_field_keeps_rest_of_section() {
+        let json = config_json(|v| {
+            v["voice"]["rate"] = serde_json::json!("fast");
+            v["voice"]["enabled"] = serde_json::json!(false);
+            v["hotkeys"]["next_step"] = serde_json::json!(42);
+            v["hotkeys"]["toggle_overlay"] = serde_json::json!("Ctrl+F9");
+        });
+        let (config, report) = recover_config(&json);
+
+        assert_eq!(report.status, ConfigLoadStatus::Recovered);
+        assert_eq!(
+            report.recovered_fields,
+            vec!["hotkeys.next_step", "voice.rate"]
+        );
+        assert!(report.needs_backup());
+        assert_eq!(config.overlay_opacity, 0.55);
+        assert_eq!(config.theme, Theme::Light);
+        let voice = config.voice.unwrap();
+        assert!(!voice.enabled);
+        assert_eq!(voice.rate, 1.0);
+        assert_eq!(config.hotkeys.toggle_overlay, "Ctrl+F9");
+        assert_eq!(config.hotkeys.next_step, "Ctrl+Alt+F3");
+    }
+
+    #[test]
+    fn test_missing_required_field_uses_default() {
+        let json = config_json(|v| {
+            v.as_object_mut().unwrap().remove("auto_advance");
+        });
+        let (config, report) = recover_config(&json);
+        assert_eq!(report.status, ConfigLoadStatus::Recovered);
+        assert_eq!(report.recovered_fields, vec!["auto_advance"]);
+        assert_eq!(config.theme, Theme::Light);
+        assert!(!config.auto_advance.enabled);
+    }
+
+    #[test]
+    fn test_unparseable_file_resets() {
+        let (config, report) = recover_config("{ \"overlay_opacity\": 0.5,");
+        assert_eq!(report.status, ConfigLoadStatus::Reset);
+        assert!(report.error.is_some());
+        assert_eq!(config.overlay_opacity, 0.8);
+
+        assert_eq!(recover_config("[1, 2]").1.status, ConfigLoadStatus::Reset);
+    }
+}
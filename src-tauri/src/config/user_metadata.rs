@@ -0,0 +1,483 @@
+use super::app_config::{
+    AppConfig, AutoAdvanceConfig, ReminderConfig, UpgradeBadgesConfig, VoiceConfig,
+};
+use super::assets::all_steps_mut;
+use super::build_order::BuildOrder;
+use super::validation::validate_config;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const MAX_NOTES_LEN: usize = 4000;
+pub const MAX_STEP_ANNOTATION_LEN: usize = 500;
+
+/// Personal data about a build that must not travel with its content: flags,
+/// notes and play history. Keyed by build id so it survives re-imports and
+/// id-preserving updates, and never ends up in shared/exported files.
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct UserBuildMetadata {
+    pub pinned: bool,
+    pub favorite: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+    /// step id -> personal annotation
+    pub step_annotations: BTreeMap<String, String>,
+    /// Unix epoch milliseconds of the last session started with this build.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_played_at: Option<u64>,
+    pub play_count: u32,
+    /// Overall reported results with this build.
+    pub record: MatchRecord,
+    /// Reported results keyed by opponent civilization (as named by the UI).
+    pub matchup_records: BTreeMap<String, MatchRecord>,
+    /// Settings this build uses instead of the user's own.
+    #[serde(skip_serializing_if = "BuildSettingsOverrides::is_empty")]
+    pub settings_overrides: BuildSettingsOverrides,
+}
+
+/// Per-build replacements for whole config sections, e.g. faster voice
+/// callouts for one build only. A section left as None follows the config.
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BuildSettingsOverrides {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub voice: Option<VoiceConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reminders: Option<ReminderConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auto_advance: Option<AutoAdvanceConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub upgrade_badges: Option<UpgradeBadgesConfig>,
+}
+
+impl BuildSettingsOverrides {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// `config` (the global config with the active profile already layered
+    /// on) with this build's sections swapped in.
+    pub fn apply(&self, config: &AppConfig) -> AppConfig {
+        let mut effective = config.clone();
+        if let Some(voice) = &self.voice {
+            effective.voice = Some(voice.clone());
+        }
+        if let Some(reminders) = &self.reminders {
+            effective.reminders = Some(reminders.clone());
+        }
+        if let Some(auto_advance) = &self.auto_advance {
+            effective.auto_advance = auto_advance.clone();
+        }
+        if let Some(upgrade_badges) = &self.upgrade_badges {
+            effective.upgrade_badges = Some(upgrade_badges.clone());
+        }
+        effective
+    }
+
+    /// Checks the sections against the same ranges as the config, returning
+    /// them with out-of-range values clamped.
+    pub fn validated(&self) -> Result<Self, String> {
+        let mut config = self.apply(&AppConfig::default());
+        validate_config(&mut config)?;
+        Ok(Self {
+            voice: self.voice.as_ref().and(config.voice),
+            reminders: self.reminders.as_ref().and(config.reminders),
+            auto_advance: self.auto_advance.as_ref().map(|_| config.auto_advance),
+            upgrade_badges: self.upgrade_badges.as_ref().and(config.upgrade_badges),
+        })
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MatchRecord {
+    pub wins: u32,
+    pub losses: u32,
+}
+
+impl MatchRecord {
+    pub fn games(&self) -> u32 {
+        self.wins + self.losses
+    }
+
+    fn add(&mut self, won: bool) {
+        if won {
+            self.wins = self.wins.saturating_add(1);
+        } else {
+            self.losses = self.losses.saturating_add(1);
+        }
+    }
+}
+
+impl UserBuildMetadata {
+    /// Results against `opponent_civilization`, matched case-insensitively.
+    pub fn matchup_record(&self, opponent_civilization: &str) -> MatchRecord {
+        self.matchup_records
+            .iter()
+            .find(|(civ, _)| civ.eq_ignore_ascii_case(opponent_civilization.trim()))
+            .map(|(_, record)| *record)
+            .unwrap_or_default()
+    }
+
+    fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Persisted as `user-metadata.json` next to `config.json`.
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct UserMetadataStore {
+    pub builds: BTreeMap<String, UserBuildMetadata>,
+    /// Set once flags embedded in pre-existing build files have been copied in.
+    pub legacy_flags_migrated: bool,
+}
+
+impl UserMetadataStore {
+    pub fn get(&self, id: &str) -> UserBuildMetadata {
+        self.builds.get(id).cloned().unwrap_or_default()
+    }
+
+    /// The config the overlay runs `id` with: `config` plus the build's
+    /// settings overrides, or `config` itself for builds without any.
+    pub fn effective_config(&self, config: &AppConfig, id: &str) -> AppConfig {
+        match self.builds.get(id) {
+            Some(metadata) => metadata.settings_overrides.apply(config),
+            None => config.clone(),
+        }
+    }
+
+    /// Mutates the entry for `id`, dropping it again if it ends up all-default.
+    pub fn update<F>(&mut self, id: &str, f: F)
+    where
+        F: FnOnce(&mut UserBuildMetadata),
+    {
+        let mut entry = self.get(id);
+        f(&mut entry);
+        if entry.is_empty() {
+            self.builds.remove(id);
+        } else {
+            self.builds.insert(id.to_string(), entry);
+        }
+    }
+
+    /// Pins or unpins `id`. Pinning is exclusive: only one build auto-loads on
+    /// start, so pinning one unpins the rest.
+    pub fn set_pinned(&mut self, id: &str, pinned: bool) {
+        if pinned {
+            let others: Vec<String> = self
+                .builds
+                .iter()
+                .filter(|(other, m)| other.as_str() != id && m.pinned)
+                .map(|(other, _)| other.clone())
+                .collect();
+            for other in others {
+                self.update(&other, |m| m.pinned = false);
+            }
+        }
+        self.update(id, |m| m.pinned = pinned);
+    }
+
+    /// Takes the personal flags the UI sent along with a saved build. The UI
+    /// toggles pin/favorite by re-saving the whole build, so this keeps that
+    /// path working while the flags themselves stay out of the build file.
+    pub fn absorb_flags(&mut self, order: &BuildOrder) {
+        self.set_pinned(&order.id, order.pinned);
+        self.update(&order.id, |m| m.favorite = order.favorite);
+    }
+
+    /// Seeds entries from flags embedded in build files written before metadata
+    /// was stored separately. Runs once; afterwards flags found in build files
+    /// are treated as shared content and ignored. Existing entries always win.
+    /// Returns true if the store changed.
+    pub fn migrate_legacy_flags(&mut self, orders: &[BuildOrder]) -> bool {
+        if self.legacy_flags_migrated {
+            return false;
+        }
+        for order in orders {
+            if (order.pinned || order.favorite) && !self.builds.contains_key(&order.id) {
+                // The first pin found wins; an existing one always does.
+                let pinned = order.pinned && !self.builds.values().any(|m| m.pinned);
+                self.update(&order.id, |m| {
+                    m.pinned = pinned;
+                    m.favorite = order.favorite;
+                });
+            }
+        }
+        self.legacy_flags_migrated = true;
+        true
+    }
+
+    pub fn record_played(&mut self, id: &str) {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or_default();
+        self.update(id, |m| {
+            m.play_count = m.play_count.saturating_add(1);
+            m.last_played_at = Some(now);
+        });
+    }
+
+    /// Records a win or loss, optionally against a known opponent civilization.
+    pub fn record_result(&mut self, id: &str, opponent_civilization: Option<&str>, won: bool) {
+        self.update(id, |m| {
+            m.record.add(won);
+            if let Some(opponent) = opponent_civilization
+                .map(str::trim)
+                .filter(|o| !o.is_empty())
+            {
+                let key = m
+                    .matchup_records
+                    .keys()
+                    .find(|civ| civ.eq_ignore_ascii_case(opponent))
+                    .cloned()
+                    .unwrap_or_else(|| opponent.to_string());
+                m.matchup_records.entry(key).or_default().add(won);
+            }
+        });
+    }
+
+    /// Returns `order` with the user's metadata applied, as served to the UI.
+    pub fn merge_into(&self, order: &BuildOrder) -> BuildOrder {
+        let metadata = self.get(&order.id);
+        let mut merged = order.clone();
+        merged.pinned = metadata.pinned;
+        merged.favorite = metadata.favorite;
+        merged.user_metadata = Some(metadata);
+        merged
+    }
+
+    pub fn merge_all(&self, orders: &[BuildOrder]) -> Vec<BuildOrder> {
+        orders.iter().map(|o| self.merge_into(o)).collect()
+    }
+}
+
+/// Copy of `order` with every personal field and served-only step segment
+/// cleared, fit for writing to the library folder or sharing with someone else.
+pub fn strip_user_data(order: &BuildOrder) -> BuildOrder {
+    let mut stripped = order.clone();
+    stripped.pinned = false;
+    stripped.favorite = false;
+    stripped.user_metadata = None;
+    for step in all_steps_mut(&mut stripped) {
+        step.segments = None;
+    }
+    stripped
+}
+
+pub fn validate_notes(notes: &str) -> Result<(), String> {
+    if notes.chars().count() > MAX_NOTES_LEN {
+        return Err(format!(
+            "Notes exceed max length of {} characters",
+            MAX_NOTES_LEN
+        ));
+    }
+    Ok(())
+}
+
+pub fn validate_step_annotation(annotation: &str) -> Result<(), String> {
+    if annotation.chars().count() > MAX_STEP_ANNOTATION_LEN {
+        return Err(format!(
+            "Step annotation exceeds max length of {} characters",
+            MAX_STEP_ANNOTATION_LEN
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::BuildOrderStep;
+
+    fn build(id: &str) -> BuildOrder {
+        BuildOrder {
+            id: id.to_string(),
+            name: id.to_string(),
+            civilization: "English".to_string(),
+            difficulty: "Beginner".to_string(),
+            steps: vec![BuildOrderStep {
+                id: "s1".to_string(),
+                description: "Step".to_string(),
+                ..Default::default()
+            }],
+            enabled: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_merge_applies_flags_and_metadata() {
+        let mut store = UserMetadataStore::default();
+        store.update("a", |m| {
+            m.favorite = true;
+            m.notes = Some("Wall early".to_string());
+        });
+
+        let merged = store.merge_into(&build("a"));
+        assert!(merged.favorite);
+        assert!(!merged.pinned);
+        assert_eq!(
+            merged.user_metadata.unwrap().notes.as_deref(),
+            Some("Wall early")
+        );
+    }
+
+    #[test]
+    fn test_metadata_overrides_flags_in_content() {
+        let store = UserMetadataStore::default();
+        let mut shared = build("a");
+        shared.favorite = true;
+        assert!(!store.merge_into(&shared).favorite);
+    }
+
+    #[test]
+    fn test_absorb_flags_and_default_entries_are_dropped() {
+        let mut store = UserMetadataStore::default();
+        let mut order = build("a");
+        order.pinned = true;
+        store.absorb_flags(&order);
+        assert!(store.get("a").pinned);
+
+        order.pinned = false;
+        store.absorb_flags(&order);
+        assert!(store.builds.is_empty());
+    }
+
+    #[test]
+    fn test_absorbed_pin_unpins_other_builds() {
+        let mut store = UserMetadataStore::default();
+        store.update("a", |m| {
+            m.pinned = true;
+            m.favorite = true;
+        });
+        let mut b = build("b");
+        b.pinned = true;
+        store.absorb_flags(&b);
+        assert!(store.get("b").pinned);
+        assert!(!store.get("a").pinned);
+        assert!(store.get("a").favorite);
+    }
+
+    #[test]
+    fn test_migrate_legacy_flags_keeps_existing_entries() {
+        let mut store = UserMetadataStore::default();
+        store.update("b", |m| m.play_count = 3);
+
+        let mut a = build("a");
+        a.favorite = true;
+        let mut b = build("b");
+        b.pinned = true;
+
+        assert!(store.migrate_legacy_flags(&[a, b]));
+        assert!(store.get("a").favorite);
+        assert!(!store.get("b").pinned);
+
+        // Un-favoriting must not be undone by the flag still in the old file
+        store.update("a", |m| m.favorite = false);
+        let mut a = build("a");
+        a.favorite = true;
+        assert!(!store.migrate_legacy_flags(&[a]));
+        assert!(!store.get("a").favorite);
+    }
+
+    #[test]
+    fn test_record_played() {
+        let mut store = UserMetadataStore::default();
+        store.record_played("a");
+        store.record_played("a");
+        let entry = store.get("a");
+        assert_eq!(entry.play_count, 2);
+        assert!(entry.last_played_at.is_some());
+    }
+
+    #[test]
+    fn test_record_result_by_matchup() {
+        let mut store = UserMetadataStore::default();
+        store.record_result("a", Some("French"), true);
+        store.record_result("a", Some("french "), false);
+        store.record_result("a", None, true);
+
+        let entry = store.get("a");
+        assert_eq!(entry.record, MatchRecord { wins: 2, losses: 1 });
+        assert_eq!(entry.matchup_records.len(), 1);
+        assert_eq!(entry.matchup_record("FRENCH").games(), 2);
+        assert_eq!(entry.matchup_record("Mongols").games(), 0);
+    }
+
+    #[test]
+    fn test_strip_user_data() {
+        let mut order = build("a");
+        order.pinned = true;
+        order.favorite = true;
+        order.user_metadata = Some(UserBuildMetadata::default());
+        let stripped = strip_user_data(&order);
+        assert!(!stripped.pinned);
+        assert!(!stripped.favorite);
+        assert!(stripped.user_metadata.is_none());
+    }
+
+    #[test]
+    fn test_effective_config_swaps_in_build_sections() {
+        let mut store = UserMetadataStore::default();
+        let voice = VoiceConfig {
+            rate: 1.5,
+            ..VoiceConfig::default()
+        };
+        store.update("a", |m| {
+            m.settings_overrides.voice = Some(voice.clone());
+            m.settings_overrides.auto_advance = Some(AutoAdvanceConfig {
+                enabled: true,
+                delay_seconds: 2,
+            });
+        });
+
+        let config = AppConfig {
+            overlay_opacity: 0.5,
+            ..AppConfig::default()
+        };
+        let effective = store.effective_config(&config, "a");
+        assert_eq!(effective.voice, Some(voice));
+        assert!(effective.auto_advance.enabled);
+        assert_eq!(effective.reminders, config.reminders);
+        assert_eq!(effective.overlay_opacity, 0.5);
+        assert_eq!(store.effective_config(&config, "b").voice, config.voice);
+
+        store.update("a", |m| {
+            m.settings_overrides = BuildSettingsOverrides::default()
+        });
+        assert!(store.builds.is_empty());
+    }
+
+    #[test]
+    fn test_validated_overrides_are_clamped() {
+        let overrides = BuildSettingsOverrides {
+            voice: Some(VoiceConfig {
+                rate: 5.0,
+                ..VoiceConfig::default()
+            }),
+            ..BuildSettingsOverrides::default()
+        };
+        let validated = overrides.validated().unwrap();
+        assert_eq!(validated.voice.unwrap().rate, 2.0);
+        assert!(validated.reminders.is_none());
+
+        let nan = BuildSettingsOverrides {
+            voice: Some(VoiceConfig {
+                rate: f32::NAN,
+                ..VoiceConfig::default()
+            }),
+            ..BuildSettingsOverrides::default()
+        };
+        assert!(nan.validated().is_err());
+    }
+
+    #[test]
+    fn test_validate_lengths() {
+        assert!(validate_notes(&"n".repeat(MAX_NOTES_LEN)).is_ok());
+        assert!(validate_notes(&"n".repeat(MAX_NOTES_LEN + 1)).is_err());
+        assert!(validate_step_annotation(&"a".repeat(MAX_STEP_ANNOTATION_LEN + 1)).is_err());
+    }
+}
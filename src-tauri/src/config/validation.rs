@@ -0,0 +1,247 @@
+use super::app_config::{AppConfig, GameDetectionConfig};
+use serde::Serialize;
+use std::ops::RangeInclusive;
+
+// Ranges match the settings UI's sliders and inputs.
+pub const OVERLAY_OPACITY_RANGE: RangeInclusive<f64> = 0.1..=1.0;
+pub const UI_SCALE_RANGE: RangeInclusive<f64> = 0.85..=1.2;
+pub const VOICE_RATE_RANGE: RangeInclusive<f32> = 0.5..=2.0;
+pub const AUTO_ADVANCE_DELAY_RANGE: RangeInclusive<u32> = 0..=30;
+pub const OCR_POLL_INTERVAL_RANGE: RangeInclusive<u32> = 500..=10_000;
+pub const OCR_CONFIDENCE_RANGE: RangeInclusive<f64> = 0.5..=0.99;
+pub const DETECTION_POLL_INTERVAL_RANGE: RangeInclusive<u32> = 200..=5000;
+pub const TELEMETRY_MAX_EVENTS_RANGE: RangeInclusive<u32> = 10..=2000;
+pub const WINDOW_WIDTH_RANGE: RangeInclusive<u32> = 200..=7680;
+pub const WINDOW_HEIGHT_RANGE: RangeInclusive<u32> = 100..=4320;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConfigIssueKind {
+    /// Out of range; moved to the nearest bound.
+    Clamped,
+    /// Not usable at all (NaN, empty list); replaced by the default.
+    Rejected,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigFieldIssue {
+    /// JSON path of the field, e.g. "voice.rate".
+    pub field: String,
+    pub kind: ConfigIssueKind,
+    pub message: String,
+}
+
+#[derive(Default)]
+struct Checker {
+    issues: Vec<ConfigFieldIssue>,
+}
+
+impl Checker {
+    fn issue(&mut self, field: &str, kind: ConfigIssueKind, message: String) {
+        self.issues.push(ConfigFieldIssue {
+            field: field.to_string(),
+            kind,
+            message,
+        });
+    }
+
+    fn float<T>(&mut self, field: &str, value: &mut T, range: RangeInclusive<T>, default: T)
+    where
+        T: Copy + PartialOrd + std::fmt::Display + Into<f64>,
+    {
+        if !(*value).into().is_finite() {
+            self.issue(
+                field,
+                ConfigIssueKind::Rejected,
+                format!("{} is not a number; reset to {}", field, default),
+            );
+            *value = default;
+        } else {
+            self.clamp(field, value, range);
+        }
+    }
+
+    fn clamp<T>(&mut self, field: &str, value: &mut T, range: RangeInclusive<T>)
+    where
+        T: Copy + PartialOrd + std::fmt::Display,
+    {
+        let (min, max) = (*range.start(), *range.end());
+        let clamped = if *value < min {
+            min
+        } else if *value > max {
+            max
+        } else {
+            return;
+        };
+        self.issue(
+            field,
+            ConfigIssueKind::Clamped,
+            format!(
+                "{} must be between {} and {}; {} became {}",
+                field, min, max, value, clamped
+            ),
+        );
+        *value = clamped;
+    }
+}
+
+/// Clamps out-of-range values into range and replaces unusable ones with
+/// their defaults, returning one issue per field it touched. A config that
+/// comes back with no issues was already valid.
+pub fn normalize_config(config: &mut AppConfig) -> Vec<ConfigFieldIssue> {
+    let defaults = AppConfig::default();
+    let mut check = Checker::default();
+
+    check.float(
+        "overlay_opacity",
+        &mut config.overlay_opacity,
+        OVERLAY_OPACITY_RANGE,
+        defaults.overlay_opacity,
+    );
+    check.float(
+        "ui_scale",
+        &mut config.ui_scale,
+        UI_SCALE_RANGE,
+        defaults.ui_scale,
+    );
+    check.clamp(
+        "auto_advance.delay_seconds",
+        &mut config.auto_advance.delay_seconds,
+        AUTO_ADVANCE_DELAY_RANGE,
+    );
+    if let Some(size) = &mut config.window_size {
+        check.clamp("window_size.width", &mut size.width, WINDOW_WIDTH_RANGE);
+        check.clamp("window_size.height", &mut size.height, WINDOW_HEIGHT_RANGE);
+    }
+    if let Some(voice) = &mut config.voice {
+        check.float("voice.rate", &mut voice.rate, VOICE_RATE_RANGE, 1.0);
+    }
+    if let Some(ocr) = &mut config.ocr_assist {
+        check.clamp(
+            "ocrAssist.pollIntervalMs",
+            &mut ocr.poll_interval_ms,
+            OCR_POLL_INTERVAL_RANGE,
+        );
+        check.float(
+            "ocrAssist.confidenceThreshold",
+            &mut ocr.confidence_threshold,
+            OCR_CONFIDENCE_RANGE,
+            0.82,
+        );
+    }
+    if let Some(telemetry) = &mut config.telemetry {
+        check.clamp(
+            "telemetry.maxEvents",
+            &mut telemetry.max_events,
+            TELEMETRY_MAX_EVENTS_RANGE,
+        );
+    }
+    if let Some(detection) = &mut config.game_detection {
+        check.clamp(
+            "gameDetection.pollIntervalMs",
+            &mut detection.poll_interval_ms,
+            DETECTION_POLL_INTERVAL_RANGE,
+        );
+        detection
+            .process_names
+            .retain(|name| !name.trim().is_empty());
+        if detection.process_names.is_empty() {
+            // An empty list would never match, hiding the overlay for good.
+            detection.process_names = GameDetectionConfig::default().process_names;
+            check.issue(
+                "gameDetection.processNames",
+                ConfigIssueKind::Rejected,
+                "gameDetection.processNames is empty; reset to the default".to_string(),
+            );
+        }
+    }
+    check.issues
+}
+
+/// `normalize_config` for values coming from the UI: clamping is applied
+/// silently, but anything rejected fails with one message per field.
+pub fn validate_config(config: &mut AppConfig) -> Result<Vec<ConfigFieldIssue>, String> {
+    let issues = normalize_config(config);
+    let rejected: Vec<&str> = issues
+        .iter()
+        .filter(|i| i.kind == ConfigIssueKind::Rejected)
+        .map(|i| i.message.as_str())
+        .collect();
+    if rejected.is_empty() {
+        Ok(issues)
+    } else {
+        Err(format!("Invalid config: {}", rejected.join("; ")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::WindowSize;
+
+    #[test]
+    fn test_default_config_is_valid() {
+        let mut config = AppConfig::default();
+        assert!(normalize_config(&mut config).is_empty());
+    }
+
+    #[test]
+    fn test_out_of_range_values_are_clamped() {
+        let mut config = AppConfig {
+            overlay_opacity: 0.0,
+            ui_scale: 3.0,
+            window_size: Some(WindowSize {
+                width: 0,
+                height: 600,
+            }),
+            ..AppConfig::default()
+        };
+        config.voice.as_mut().unwrap().rate = 9.0;
+        config.game_detection.as_mut().unwrap().poll_interval_ms = 0;
+        config.telemetry.as_mut().unwrap().max_events = 1_000_000;
+
+        let issues = normalize_config(&mut config);
+        let fields: Vec<&str> = issues.iter().map(|i| i.field.as_str()).collect();
+        assert_eq!(
+            fields,
+            vec![
+                "overlay_opacity",
+                "ui_scale",
+                "window_size.width",
+                "voice.rate",
+                "telemetry.maxEvents",
+                "gameDetection.pollIntervalMs",
+            ]
+        );
+        assert!(issues.iter().all(|i| i.kind == ConfigIssueKind::Clamped));
+        assert_eq!(config.overlay_opacity, 0.1);
+        assert_eq!(config.ui_scale, 1.2);
+        assert_eq!(config.window_size.unwrap().width, 200);
+        assert_eq!(config.voice.unwrap().rate, 2.0);
+        assert_eq!(config.game_detection.unwrap().poll_interval_ms, 200);
+        assert_eq!(config.telemetry.unwrap().max_events, 2000);
+        assert!(validate_config(&mut AppConfig::default()).is_ok());
+    }
+
+    #[test]
+    fn test_unusable_values_are_rejected() {
+        let mut config = AppConfig {
+            overlay_opacity: f64::NAN,
+            ..AppConfig::default()
+        };
+        config.game_detection.as_mut().unwrap().process_names = vec![" ".to_string()];
+
+        let err = validate_config(&mut config.clone()).unwrap_err();
+        assert!(err.contains("overlay_opacity"), "{}", err);
+        assert!(err.contains("gameDetection.processNames"), "{}", err);
+
+        let issues = normalize_config(&mut config);
+        assert!(issues.iter().all(|i| i.kind == ConfigIssueKind::Rejected));
+        assert_eq!(config.overlay_opacity, 0.8);
+        assert_eq!(
+            config.game_detection.unwrap().process_names,
+            vec!["RelicCardinal.exe"]
+        );
+    }
+}
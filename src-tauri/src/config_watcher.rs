@@ -0,0 +1,63 @@
+//! Picks up edits made to `config.json` outside the app (by hand, or by a
+//! sync tool) while it runs. The file is polled rather than watched through
+//! OS notifications; a stat per second is cheap and behaves the same on
+//! every platform and on synced folders.
+
+use crate::commands::apply_external_config;
+use crate::config::{get_config_path, read_config_file};
+use std::fs;
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, SystemTime};
+use tauri::AppHandle;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Modification time and size; a change in either means the file was written.
+fn file_stamp(path: &Path) -> Option<(SystemTime, u64)> {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+fn reload(app: &AppHandle, path: &Path) {
+    let global = match read_config_file(path) {
+        Ok(global) => global,
+        Err(e) => {
+            eprintln!("Ignoring edit to {:?}: {}", path, e);
+            return;
+        }
+    };
+    match apply_external_config(app, global) {
+        Ok(true) => eprintln!("Reloaded config edited outside the app: {:?}", path),
+        Ok(false) => {} // The app's own write, or an edit that changed nothing.
+        Err(e) => eprintln!("Failed to apply edited config {:?}: {}", path, e),
+    }
+}
+
+/// Starts the watcher thread. Saves made by the app itself are seen too, but
+/// read back as the config already running and are ignored.
+pub fn start_config_watcher(app: AppHandle) {
+    thread::spawn(move || {
+        let mut path = get_config_path();
+        let mut last = file_stamp(&path);
+        loop {
+            thread::sleep(POLL_INTERVAL);
+            let current = get_config_path();
+            if current != path {
+                // The data folder moved; its copy holds the running config.
+                path = current;
+                last = file_stamp(&path);
+                continue;
+            }
+            let stamp = file_stamp(&path);
+            if stamp == last {
+                continue;
+            }
+            last = stamp;
+            // A deleted file keeps the running config; the next save recreates it.
+            if stamp.is_some() {
+                reload(&app, &path);
+            }
+        }
+    });
+}
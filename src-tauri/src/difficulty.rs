@@ -0,0 +1,265 @@
+//! Estimates how hard a build is to execute from its structure, independent of
+//! the author's label.
+//!
+//! Four factors are each scored 0-3 and averaged by weight: step density
+//! (steps per minute), production buildings added, branch count and timing
+//! tightness (share of short gaps between timed steps, plus an early first
+//! age-up). Factors without data (an untimed build has no density) are left out
+//! of the average rather than counted as easy.
+
+use crate::config::{BuildOrder, BuildOrderStep, DifficultyEstimate, DifficultyLevel};
+use crate::stats::{compute_build_order_stats, step_timing_seconds, BuildOrderStats};
+
+const DENSITY_WEIGHT: f32 = 0.35;
+const PRODUCTION_WEIGHT: f32 = 0.25;
+const BRANCH_WEIGHT: f32 = 0.15;
+const TIGHTNESS_WEIGHT: f32 = 0.25;
+
+/// Consecutive timed steps closer than this count as a tight sequence.
+const TIGHT_GAP_SECONDS: u32 = 20;
+/// First age-up at or before this is a fast (demanding) opener.
+const FAST_AGE_UP_SECONDS: u32 = 240;
+
+const PRODUCTION_BUILDINGS: &[&str] = &[
+    "town center",
+    "barracks",
+    "archery range",
+    "stable",
+    "siege workshop",
+    "dock",
+    "monastery",
+    "mosque",
+    "prayer tent",
+    "military school",
+];
+const BUILD_WORDS: &[&str] = &[
+    "build",
+    "construct",
+    "place",
+    "add",
+    "another",
+    "second",
+    "2nd",
+    "3rd",
+];
+
+/// Scores a value against ascending thresholds: below the first is 0, past
+/// the last is 3.
+fn banded(value: f32, thresholds: [f32; 3]) -> f32 {
+    thresholds.iter().filter(|t| value >= **t).count() as f32
+}
+
+/// Production buildings a step asks for: "2 stables" counts two, "build a
+/// barracks" one, and every mention adds up. Steps without a build verb only
+/// mention buildings.
+fn production_buildings_in(step: &BuildOrderStep) -> u32 {
+    let text = step.description.to_lowercase().replace('_', " ");
+    if !BUILD_WORDS.iter().any(|w| text.contains(w)) {
+        return 0;
+    }
+    PRODUCTION_BUILDINGS
+        .iter()
+        .flat_map(|building| text.match_indices(building))
+        .map(|(at, _)| {
+            text[..at]
+                .split_whitespace()
+                .next_back()
+                .and_then(|w| w.parse::<u32>().ok())
+                .filter(|n| (1..=10).contains(n))
+                .unwrap_or(1)
+        })
+        .sum()
+}
+
+fn tight_gap_share(steps: &[BuildOrderStep]) -> Option<f32> {
+    let timings: Vec<u32> = steps.iter().filter_map(step_timing_seconds).collect();
+    let gaps: Vec<u32> = timings
+        .windows(2)
+        .filter(|pair| pair[1] >= pair[0])
+        .map(|pair| pair[1] - pair[0])
+        .collect();
+    if gaps.is_empty() {
+        return None;
+    }
+    let tight = gaps.iter().filter(|g| **g < TIGHT_GAP_SECONDS).count();
+    Some(tight as f32 / gaps.len() as f32)
+}
+
+fn level_for_score(score: f32) -> DifficultyLevel {
+    match score {
+        s if s < 0.75 => DifficultyLevel::Beginner,
+        s if s < 1.5 => DifficultyLevel::Intermediate,
+        s if s < 2.25 => DifficultyLevel::Advanced,
+        _ => DifficultyLevel::Expert,
+    }
+}
+
+pub fn estimate_difficulty(order: &BuildOrder) -> DifficultyEstimate {
+    let stats: BuildOrderStats = compute_build_order_stats(order);
+    // (weight, score, reason)
+    let mut factors: Vec<(f32, f32, String)> = Vec::new();
+
+    if let Some(spm) = stats.steps_per_minute {
+        factors.push((
+            DENSITY_WEIGHT,
+            banded(spm, [1.5, 2.5, 3.5]),
+            format!("{:.1} steps per minute", spm),
+        ));
+    }
+
+    let production: u32 = order.steps.iter().map(production_buildings_in).sum();
+    factors.push((
+        PRODUCTION_WEIGHT,
+        banded(production as f32, [2.0, 4.0, 6.0]),
+        format!("{} production buildings added", production),
+    ));
+
+    factors.push((
+        BRANCH_WEIGHT,
+        banded(stats.branch_count as f32, [1.0, 3.0, 5.0]),
+        format!("{} branches", stats.branch_count),
+    ));
+
+    if let Some(share) = tight_gap_share(&order.steps) {
+        let fast_age_up = stats
+            .age_ups
+            .first()
+            .and_then(|a| a.timing_seconds)
+            .is_some_and(|t| t <= FAST_AGE_UP_SECONDS);
+        let score = (banded(share, [0.2, 0.4, 0.6]) + if fast_age_up { 1.0 } else { 0.0 }).min(3.0);
+        let mut reason = format!(
+            "{:.0}% of timed steps under {}s apart",
+            share * 100.0,
+            TIGHT_GAP_SECONDS
+        );
+        if fast_age_up {
+            reason.push_str(", fast first age-up");
+        }
+        factors.push((TIGHTNESS_WEIGHT, score, reason));
+    }
+
+    let total_weight: f32 = factors.iter().map(|(w, _, _)| w).sum();
+    let score = factors.iter().map(|(w, s, _)| w * s).sum::<f32>() / total_weight;
+
+    factors.sort_by(|a, b| (b.0 * b.1).total_cmp(&(a.0 * a.1)));
+    let reasons = factors
+        .into_iter()
+        .filter(|(_, s, _)| *s > 0.0)
+        .map(|(_, _, reason)| reason)
+        .collect();
+
+    let level = level_for_score(score);
+    let label_level = DifficultyLevel::from_label(&order.difficulty);
+    let disagrees_with_label = label_level.is_some_and(|l| l.rank().abs_diff(level.rank()) >= 2);
+
+    DifficultyEstimate {
+        level,
+        score,
+        reasons,
+        label_level,
+        disagrees_with_label,
+    }
+}
+
+/// Recomputes the stored estimate after the build's steps or label changed.
+pub fn refresh_difficulty_estimate(order: &mut BuildOrder) {
+    order.difficulty_estimate = Some(estimate_difficulty(order));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(description: &str, timing: &str) -> BuildOrderStep {
+        BuildOrderStep {
+            id: format!("s-{}", timing),
+            description: description.to_string(),
+            timing: Some(timing.to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn order(difficulty: &str, steps: Vec<BuildOrderStep>) -> BuildOrder {
+        BuildOrder {
+            id: "test".to_string(),
+            name: "Test".to_string(),
+            civilization: "English".to_string(),
+            difficulty: difficulty.to_string(),
+            steps,
+            enabled: true,
+            ..Default::default()
+        }
+    }
+
+    fn relaxed_steps() -> Vec<BuildOrderStep> {
+        vec![
+            step("6 to sheep", "0:00"),
+            step("Build a house", "1:30"),
+            step("Send 4 to wood", "3:00"),
+            step("Feudal Age", "6:00"),
+        ]
+    }
+
+    fn frantic_steps() -> Vec<BuildOrderStep> {
+        let mut steps = vec![step("Age up to Feudal Age", "3:50")];
+        for i in 0..16 {
+            let description = match i % 4 {
+                0 => "Build 2 stables",
+                1 => "Build archery range",
+                _ => "Queue units",
+            };
+            steps.push(step(description, &format!("4:{:02}", i * 3)));
+        }
+        steps
+    }
+
+    #[test]
+    fn test_relaxed_build_is_beginner() {
+        let estimate = estimate_difficulty(&order("Beginner", relaxed_steps()));
+        assert_eq!(estimate.level, DifficultyLevel::Beginner);
+        assert!(!estimate.disagrees_with_label);
+    }
+
+    #[test]
+    fn test_frantic_build_scores_high_and_flags_easy_label() {
+        let estimate = estimate_difficulty(&order("Easy", frantic_steps()));
+        assert!(
+            estimate.level >= DifficultyLevel::Advanced,
+            "{:?}",
+            estimate
+        );
+        assert_eq!(estimate.label_level, Some(DifficultyLevel::Beginner));
+        assert!(estimate.disagrees_with_label);
+        assert!(estimate.reasons[0].contains("steps per minute"));
+    }
+
+    #[test]
+    fn test_unknown_label_never_disagrees() {
+        let estimate = estimate_difficulty(&order("Spicy", frantic_steps()));
+        assert_eq!(estimate.label_level, None);
+        assert!(!estimate.disagrees_with_label);
+    }
+
+    #[test]
+    fn test_production_buildings_counts_numbers() {
+        assert_eq!(
+            production_buildings_in(&step("Build 2 stables and a barracks", "1:00")),
+            3
+        );
+        assert_eq!(
+            production_buildings_in(&step("Rally to the barracks", "1:00")),
+            0
+        );
+        assert_eq!(
+            production_buildings_in(&step("Build 2 barracks, then 1 more barracks", "1:00")),
+            3
+        );
+    }
+
+    #[test]
+    fn test_refresh_stores_estimate() {
+        let mut build = order("Beginner", relaxed_steps());
+        refresh_difficulty_estimate(&mut build);
+        assert!(build.difficulty_estimate.is_some());
+    }
+}
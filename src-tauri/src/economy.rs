@@ -0,0 +1,509 @@
+//! Projects a build's economy second by second from its villager allocations
+//! to sanity-check step timings before a build is published.
+//!
+//! Gather rates, age-up costs and civilization bonuses come from
+//! `data/economy.json`. The model is deliberately coarse: villagers gather at a
+//! flat rate per resource, each villager added is paid for at the step that
+//! lists it, and age-ups are the only costs checked. Two kinds of issue come
+//! out of it: a step asking for more than the economy can deliver by its timing
+//! (infeasible), and an age-up that could have been afforded much earlier
+//! (slack).
+
+use crate::config::{BuildOrder, BuildOrderStep};
+use crate::stats::{compute_build_order_stats, step_timing_seconds, step_villagers};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+const ECONOMY_DATA: &str = include_str!("../data/economy.json");
+
+/// An age-up affordable this long before its planned time is flagged as slack.
+const SLACK_SECONDS: u32 = 150;
+/// Villager counts may run this far ahead of continuous production before a
+/// step is flagged (authors round up).
+const VILLAGER_TOLERANCE: i32 = 1;
+/// Simulation stops here even if step timings go further.
+const MAX_SIMULATED_SECONDS: u32 = 60 * 60;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Amounts {
+    pub food: f32,
+    pub wood: f32,
+    pub gold: f32,
+    pub stone: f32,
+}
+
+impl Amounts {
+    fn values(&self) -> [f32; 4] {
+        [self.food, self.wood, self.gold, self.stone]
+    }
+
+    fn zip(&self, other: &Amounts, f: impl Fn(f32, f32) -> f32) -> Amounts {
+        Amounts {
+            food: f(self.food, other.food),
+            wood: f(self.wood, other.wood),
+            gold: f(self.gold, other.gold),
+            stone: f(self.stone, other.stone),
+        }
+    }
+
+    fn covers(&self, cost: &Amounts) -> bool {
+        self.values()
+            .iter()
+            .zip(cost.values())
+            .all(|(have, need)| *have >= need)
+    }
+}
+
+/// Multipliers on the base gather rates; resources left out stay at 1.0.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct GatherMultipliers {
+    pub food: f32,
+    pub wood: f32,
+    pub gold: f32,
+    pub stone: f32,
+}
+
+impl Default for GatherMultipliers {
+    fn default() -> Self {
+        GatherMultipliers {
+            food: 1.0,
+            wood: 1.0,
+            gold: 1.0,
+            stone: 1.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CivilizationEconomy {
+    pub gather_multipliers: GatherMultipliers,
+    pub starting_resources: Option<Amounts>,
+    pub villager_train_seconds: Option<u32>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EconomyData {
+    pub content_version: String,
+    pub starting_villagers: i32,
+    pub starting_resources: Amounts,
+    pub villager_cost: Amounts,
+    pub villager_train_seconds: u32,
+    pub gather_rates_per_minute: Amounts,
+    /// Keyed by age number ("2" = Feudal).
+    pub age_up_costs: HashMap<String, Amounts>,
+    #[serde(default)]
+    pub civilizations: HashMap<String, CivilizationEconomy>,
+}
+
+impl EconomyData {
+    pub fn parse(json: &str) -> Result<Self, String> {
+        let data: EconomyData =
+            serde_json::from_str(json).map_err(|e| format!("Invalid economy data: {}", e))?;
+        data.validate()?;
+        Ok(data)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.villager_train_seconds == 0 {
+            return Err("villagerTrainSeconds must be positive".to_string());
+        }
+        if self
+            .gather_rates_per_minute
+            .values()
+            .iter()
+            .any(|r| *r <= 0.0)
+        {
+            return Err("Gather rates must be positive".to_string());
+        }
+        for age in ["2", "3", "4"] {
+            if !self.age_up_costs.contains_key(age) {
+                return Err(format!("Missing age-up cost for age {}", age));
+            }
+        }
+        for (name, civ) in &self.civilizations {
+            let m = &civ.gather_multipliers;
+            if civ.villager_train_seconds == Some(0)
+                || [m.food, m.wood, m.gold, m.stone].iter().any(|v| *v < 0.0)
+            {
+                return Err(format!("Invalid economy bonuses for {}", name));
+            }
+        }
+        Ok(())
+    }
+
+    /// Bundled data, parsed and validated once.
+    pub fn bundled() -> Result<&'static EconomyData, String> {
+        static DATA: OnceLock<Result<EconomyData, String>> = OnceLock::new();
+        DATA.get_or_init(|| EconomyData::parse(ECONOMY_DATA))
+            .as_ref()
+            .map_err(|e| e.clone())
+    }
+
+    fn civilization(&self, name: &str) -> CivilizationEconomy {
+        self.civilizations
+            .iter()
+            .find(|(civ, _)| civ.eq_ignore_ascii_case(name.trim()))
+            .map(|(_, economy)| economy.clone())
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FeasibilityKind {
+    Infeasible,
+    Slack,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeasibilityIssue {
+    pub step_id: String,
+    pub step_index: usize,
+    pub kind: FeasibilityKind,
+    pub message: String,
+}
+
+/// Projected state at a timed step, before the step's own spending.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EconomySnapshot {
+    pub step_id: String,
+    pub step_index: usize,
+    pub timing_seconds: u32,
+    pub villagers: i32,
+    pub income_per_minute: Amounts,
+    pub bank: Amounts,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EconomyReport {
+    pub content_version: String,
+    pub timeline: Vec<EconomySnapshot>,
+    pub issues: Vec<FeasibilityIssue>,
+}
+
+/// Villagers per resource from a step's `resources`; categories it leaves out
+/// are idle. Steps without resources keep the previous allocation.
+fn allocation(step: &BuildOrderStep) -> Option<Amounts> {
+    let r = step.resources.as_ref()?;
+    if [r.food, r.wood, r.gold, r.stone]
+        .iter()
+        .all(Option::is_none)
+    {
+        return None;
+    }
+    let count = |v: Option<i32>| v.unwrap_or(0).max(0) as f32;
+    Some(Amounts {
+        food: count(r.food),
+        wood: count(r.wood),
+        gold: count(r.gold),
+        stone: count(r.stone),
+    })
+}
+
+/// True when the step adds a town center, which doubles villager production.
+fn adds_town_center(step: &BuildOrderStep) -> bool {
+    let text = step.description.to_lowercase().replace('_', " ");
+    (text.contains("town center") || text.contains(" tc"))
+        && ["build", "construct", "place", "2nd", "second"]
+            .iter()
+            .any(|w| text.contains(w))
+}
+
+fn format_amounts(amounts: &Amounts, mask: &Amounts) -> String {
+    let names = ["food", "wood", "gold", "stone"];
+    amounts
+        .values()
+        .iter()
+        .zip(mask.values())
+        .zip(names)
+        .filter(|((_, m), _)| *m > 0.0)
+        .map(|((v, _), name)| format!("{:.0} {}", v, name))
+        .collect::<Vec<_>>()
+        .join(" / ")
+}
+
+pub fn simulate_economy(order: &BuildOrder, data: &EconomyData) -> EconomyReport {
+    let civ = data.civilization(&order.civilization);
+    let m = civ.gather_multipliers;
+    let multipliers = Amounts {
+        food: m.food,
+        wood: m.wood,
+        gold: m.gold,
+        stone: m.stone,
+    };
+    let rates = data
+        .gather_rates_per_minute
+        .zip(&multipliers, |rate, m| rate * m / 60.0);
+    let train_seconds = civ
+        .villager_train_seconds
+        .unwrap_or(data.villager_train_seconds);
+    let age_ups: HashMap<String, (u8, String)> = compute_build_order_stats(order)
+        .age_ups
+        .into_iter()
+        .map(|a| (a.step_id, (a.age, a.name)))
+        .collect();
+
+    let mut bank = civ.starting_resources.unwrap_or(data.starting_resources);
+    let mut assigned = Amounts {
+        food: data.starting_villagers as f32,
+        ..Default::default()
+    };
+    let mut villagers = data.starting_villagers;
+    // Villagers one town center could have produced so far, per town center
+    let mut town_centers = 1;
+    let mut production_credit = 0.0_f32;
+
+    let mut pending_age_cost = data.age_up_costs.get("2").copied();
+    let mut affordable_since: Option<u32> = None;
+
+    let mut now = 0;
+    let mut timeline = Vec::new();
+    let mut issues = Vec::new();
+
+    for (index, step) in order.steps.iter().enumerate() {
+        let Some(timing) = step_timing_seconds(step) else {
+            // Untimed steps still change what villagers are doing
+            if let Some(a) = allocation(step) {
+                assigned = a;
+            }
+            continue;
+        };
+        let timing = timing.min(MAX_SIMULATED_SECONDS);
+
+        while now < timing {
+            now += 1;
+            bank = bank.zip(&assigned.zip(&rates, |n, r| n * r), |b, i| b + i);
+            production_credit += town_centers as f32 / train_seconds as f32;
+            if let Some(cost) = pending_age_cost {
+                if bank.covers(&cost) {
+                    affordable_since.get_or_insert(now);
+                } else {
+                    affordable_since = None;
+                }
+            }
+        }
+
+        let income = assigned.zip(&rates, |n, r| n * r * 60.0);
+        timeline.push(EconomySnapshot {
+            step_id: step.id.clone(),
+            step_index: index,
+            timing_seconds: timing,
+            villagers,
+            income_per_minute: income,
+            bank,
+        });
+
+        // Villagers added by this step are paid for now
+        if let Some(count) = step_villagers(step) {
+            let max_villagers = data.starting_villagers + production_credit.floor() as i32;
+            if count > max_villagers + VILLAGER_TOLERANCE {
+                issues.push(FeasibilityIssue {
+                    step_id: step.id.clone(),
+                    step_index: index,
+                    kind: FeasibilityKind::Infeasible,
+                    message: format!(
+                        "{} villagers at {}:{:02}, but continuous production only reaches {}",
+                        count,
+                        timing / 60,
+                        timing % 60,
+                        max_villagers
+                    ),
+                });
+            }
+            let added = (count - villagers).max(0) as f32;
+            bank = bank.zip(&data.villager_cost, |b, c| b - c * added);
+            villagers = villagers.max(count);
+        }
+
+        if let Some((age, name)) = age_ups.get(&step.id) {
+            let cost = data.age_up_costs.get(&age.to_string()).copied();
+            if let Some(cost) = cost {
+                if !bank.covers(&cost) {
+                    issues.push(FeasibilityIssue {
+                        step_id: step.id.clone(),
+                        step_index: index,
+                        kind: FeasibilityKind::Infeasible,
+                        message: format!(
+                            "{} Age at {}:{:02} needs {}, projected bank is {}",
+                            name,
+                            timing / 60,
+                            timing % 60,
+                            format_amounts(&cost, &cost),
+                            format_amounts(&bank, &cost)
+                        ),
+                    });
+                } else if let Some(since) = affordable_since {
+                    // A step timed before an earlier one can sit before `since`
+                    let slack = timing.saturating_sub(since);
+                    if slack > SLACK_SECONDS {
+                        issues.push(FeasibilityIssue {
+                            step_id: step.id.clone(),
+                            step_index: index,
+                            kind: FeasibilityKind::Slack,
+                            message: format!(
+                                "{} Age is affordable from {}:{:02}, {}s before its planned {}:{:02}",
+                                name,
+                                since / 60,
+                                since % 60,
+                                slack,
+                                timing / 60,
+                                timing % 60
+                            ),
+                        });
+                    }
+                }
+                // Keep projecting from an empty bank rather than a debt
+                bank = bank.zip(&cost, |b, c| (b - c).max(0.0));
+            }
+            pending_age_cost = data.age_up_costs.get(&(age + 1).to_string()).copied();
+            affordable_since = None;
+        }
+
+        if adds_town_center(step) {
+            town_centers += 1;
+        }
+        if let Some(a) = allocation(step) {
+            assigned = a;
+        }
+    }
+
+    EconomyReport {
+        content_version: data.content_version.clone(),
+        timeline,
+        issues,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Resources;
+
+    fn step(id: &str, description: &str, timing: &str, food: i32, gold: i32) -> BuildOrderStep {
+        BuildOrderStep {
+            id: id.to_string(),
+            description: description.to_string(),
+            timing: Some(timing.to_string()),
+            resources: Some(Resources {
+                food: Some(food),
+                gold: Some(gold),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn order(steps: Vec<BuildOrderStep>) -> BuildOrder {
+        BuildOrder {
+            id: "test".to_string(),
+            name: "Test".to_string(),
+            civilization: "Holy Roman Empire".to_string(),
+            difficulty: "Beginner".to_string(),
+            steps,
+            enabled: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_bundled_data_is_valid() {
+        let data = EconomyData::parse(ECONOMY_DATA).unwrap();
+        assert_eq!(data.starting_villagers, 6);
+        let english = data.civilization("english");
+        assert_eq!(english.gather_multipliers.food, 1.05);
+        assert_eq!(english.gather_multipliers.wood, 1.0);
+    }
+
+    #[test]
+    fn test_rejects_invalid_data() {
+        let json = ECONOMY_DATA.replace(
+            "\"villagerTrainSeconds\": 20",
+            "\"villagerTrainSeconds\": 0",
+        );
+        assert!(EconomyData::parse(&json).is_err());
+        assert!(EconomyData::parse("{}").is_err());
+    }
+
+    #[test]
+    fn test_early_feudal_with_few_villagers_is_infeasible() {
+        let report = simulate_economy(
+            &order(vec![
+                step("s1", "Sheep", "0:00", 6, 0),
+                step("s2", "Feudal Age", "1:30", 8, 0),
+            ]),
+            EconomyData::bundled().unwrap(),
+        );
+        let issue = report
+            .issues
+            .iter()
+            .find(|i| i.step_id == "s2")
+            .expect("feudal flagged");
+        assert_eq!(issue.kind, FeasibilityKind::Infeasible);
+        assert!(issue.message.contains("400 food"));
+    }
+
+    #[test]
+    fn test_late_age_up_is_slack() {
+        let report = simulate_economy(
+            &order(vec![
+                step("s1", "Split", "0:00", 4, 2),
+                step("s2", "Feudal Age", "9:00", 4, 2),
+            ]),
+            EconomyData::bundled().unwrap(),
+        );
+        assert!(report
+            .issues
+            .iter()
+            .any(|i| i.step_id == "s2" && i.kind == FeasibilityKind::Slack));
+    }
+
+    #[test]
+    fn test_out_of_order_timings_do_not_underflow() {
+        let report = simulate_economy(
+            &order(vec![
+                step("s1", "Split", "0:00", 4, 2),
+                step("s2", "Gold", "9:00", 4, 2),
+                step("s3", "Feudal Age", "1:00", 4, 2),
+            ]),
+            EconomyData::bundled().unwrap(),
+        );
+        assert!(!report
+            .issues
+            .iter()
+            .any(|i| i.kind == FeasibilityKind::Slack));
+        assert_eq!(report.timeline.len(), 3);
+    }
+
+    #[test]
+    fn test_villager_count_beyond_production_is_infeasible() {
+        let report = simulate_economy(
+            &order(vec![step("s1", "Food", "1:00", 14, 0)]),
+            EconomyData::bundled().unwrap(),
+        );
+        assert_eq!(report.issues.len(), 1);
+        assert!(report.issues[0].message.contains("14 villagers"));
+        assert_eq!(report.timeline[0].villagers, 6);
+    }
+
+    #[test]
+    fn test_reasonable_opening_has_no_issues() {
+        let report = simulate_economy(
+            &order(vec![
+                step("s1", "Sheep", "0:00", 6, 0),
+                step("s2", "Gold", "2:00", 8, 4),
+                step("s3", "Feudal Age", "4:30", 10, 5),
+            ]),
+            EconomyData::bundled().unwrap(),
+        );
+        assert!(report.issues.is_empty(), "{:?}", report.issues);
+        assert_eq!(report.timeline.len(), 3);
+    }
+}
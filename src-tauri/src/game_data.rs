@@ -0,0 +1,310 @@
+//! Units, buildings and technologies with their costs, build times and civ
+//! availability, versioned by `asset_content_version`.
+//!
+//! The data ships as `data/game_data.json`, one snapshot per content version.
+//! It is validated when first loaded; an invalid file makes every lookup fail
+//! with the validation error rather than serve half-checked numbers.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::sync::OnceLock;
+
+const GAME_DATA: &str = include_str!("../data/game_data.json");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum EntityCategory {
+    Unit,
+    Building,
+    Technology,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Cost {
+    pub food: u32,
+    pub wood: u32,
+    pub gold: u32,
+    pub stone: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GameEntity {
+    pub id: String,
+    pub name: String,
+    /// Age it becomes available in, 1 (Dark) to 4 (Imperial).
+    pub age: u8,
+    pub cost: Cost,
+    pub build_seconds: u32,
+    /// Civilizations that have it. None means every civilization.
+    pub civilizations: Option<Vec<String>>,
+    /// Other names players write in build orders ("maa", "tc").
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+    /// Names a civilization uses for its own variant ("Ger" for Mongol houses).
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub civilization_names: BTreeMap<String, String>,
+}
+
+impl GameEntity {
+    pub fn available_to(&self, civilization: &str) -> bool {
+        self.civilizations.as_ref().is_none_or(|civs| {
+            civs.iter()
+                .any(|c| c.eq_ignore_ascii_case(civilization.trim()))
+        })
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GameDataSnapshot {
+    pub units: Vec<GameEntity>,
+    pub buildings: Vec<GameEntity>,
+    pub technologies: Vec<GameEntity>,
+}
+
+impl GameDataSnapshot {
+    pub fn entities(&self) -> impl Iterator<Item = (EntityCategory, &GameEntity)> {
+        self.units
+            .iter()
+            .map(|e| (EntityCategory::Unit, e))
+            .chain(self.buildings.iter().map(|e| (EntityCategory::Building, e)))
+            .chain(
+                self.technologies
+                    .iter()
+                    .map(|e| (EntityCategory::Technology, e)),
+            )
+    }
+
+    pub fn find(&self, id: &str) -> Option<(EntityCategory, &GameEntity)> {
+        self.entities().find(|(_, e)| e.id == id)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GameDatabase {
+    /// Snapshots keyed by content version ("2026-05-07"), oldest first.
+    pub versions: BTreeMap<String, GameDataSnapshot>,
+}
+
+/// A lookup result, with the category and the content version it came from.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GameEntityEntry {
+    pub content_version: String,
+    pub category: EntityCategory,
+    pub entity: GameEntity,
+}
+
+/// An entity whose fields differ between two content versions.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EntityChange {
+    pub id: String,
+    pub category: EntityCategory,
+    /// Changed fields ("cost", "buildSeconds", ...).
+    pub fields: Vec<String>,
+    pub before: GameEntity,
+    pub after: GameEntity,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GameDataDiff {
+    pub from_version: String,
+    pub to_version: String,
+    pub added: Vec<GameEntity>,
+    pub removed: Vec<GameEntity>,
+    pub changed: Vec<EntityChange>,
+}
+
+impl GameDatabase {
+    pub fn parse(json: &str) -> Result<Self, String> {
+        let db: GameDatabase =
+            serde_json::from_str(json).map_err(|e| format!("Invalid game data: {}", e))?;
+        db.validate()?;
+        Ok(db)
+    }
+
+    /// Bundled database, parsed and validated once.
+    pub fn bundled() -> Result<&'static GameDatabase, String> {
+        static DB: OnceLock<Result<GameDatabase, String>> = OnceLock::new();
+        DB.get_or_init(|| GameDatabase::parse(GAME_DATA))
+            .as_ref()
+            .map_err(|e| e.clone())
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.versions.is_empty() {
+            return Err("Game data has no content versions".to_string());
+        }
+        for (version, snapshot) in &self.versions {
+            let mut seen = HashSet::new();
+            for (_, entity) in snapshot.entities() {
+                let problem = if entity.id.trim().is_empty() {
+                    Some("empty id")
+                } else if !seen.insert(entity.id.as_str()) {
+                    Some("duplicate id")
+                } else if entity.name.trim().is_empty() {
+                    Some("empty name")
+                } else if !(1..=4).contains(&entity.age) {
+                    Some("age must be 1-4")
+                } else if entity.build_seconds == 0 {
+                    Some("build time must be positive")
+                } else if entity.civilizations.as_ref().is_some_and(Vec::is_empty) {
+                    Some("empty civilization list (use null for all)")
+                } else if entity
+                    .aliases
+                    .iter()
+                    .chain(entity.civilization_names.values())
+                    .any(|name| name.trim().is_empty())
+                {
+                    Some("empty alias")
+                } else {
+                    None
+                };
+                if let Some(problem) = problem {
+                    return Err(format!(
+                        "Invalid game data entry \"{}\" in {}: {}",
+                        entity.id, version, problem
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The snapshot for `version`: an exact match, else the newest one not
+    /// newer than it, else the oldest. Versions are ISO dates, so they order
+    /// as strings.
+    pub fn resolve(&self, version: &str) -> (&str, &GameDataSnapshot) {
+        self.versions
+            .get_key_value(version)
+            .or_else(|| self.versions.range(..version.to_string()).next_back())
+            .or_else(|| self.versions.iter().next())
+            .map(|(v, s)| (v.as_str(), s))
+            .expect("validated game data has at least one version")
+    }
+
+    pub fn diff(&self, from: &str, to: &str) -> Result<GameDataDiff, String> {
+        let snapshot = |version: &str| {
+            self.versions
+                .get(version)
+                .ok_or_else(|| format!("Unknown content version \"{}\"", version))
+        };
+        let (old, new) = (snapshot(from)?, snapshot(to)?);
+
+        let mut diff = GameDataDiff {
+            from_version: from.to_string(),
+            to_version: to.to_string(),
+            added: Vec::new(),
+            removed: Vec::new(),
+            changed: Vec::new(),
+        };
+        for (category, after) in new.entities() {
+            match old.find(&after.id) {
+                None => diff.added.push(after.clone()),
+                Some((_, before)) => {
+                    let fields = changed_fields(before, after);
+                    if !fields.is_empty() {
+                        diff.changed.push(EntityChange {
+                            id: after.id.clone(),
+                            category,
+                            fields,
+                            before: before.clone(),
+                            after: after.clone(),
+                        });
+                    }
+                }
+            }
+        }
+        diff.removed = old
+            .entities()
+            .filter(|(_, e)| new.find(&e.id).is_none())
+            .map(|(_, e)| e.clone())
+            .collect();
+        Ok(diff)
+    }
+}
+
+fn changed_fields(before: &GameEntity, after: &GameEntity) -> Vec<String> {
+    [
+        ("name", before.name != after.name),
+        ("age", before.age != after.age),
+        ("cost", before.cost != after.cost),
+        ("buildSeconds", before.build_seconds != after.build_seconds),
+        ("civilizations", before.civilizations != after.civilizations),
+    ]
+    .iter()
+    .filter(|(_, changed)| *changed)
+    .map(|(field, _)| field.to_string())
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entity(id: &str) -> String {
+        format!(
+            r#"{{ "id": "{}", "name": "X", "age": 1, "cost": {{}}, "buildSeconds": 10, "civilizations": null }}"#,
+            id
+        )
+    }
+
+    #[test]
+    fn test_bundled_data_is_valid() {
+        let db = GameDatabase::parse(GAME_DATA).unwrap();
+        let (version, snapshot) = db.resolve("2026-05-07");
+        assert_eq!(version, "2026-05-07");
+        let (category, villager) = snapshot.find("villager").unwrap();
+        assert_eq!(category, EntityCategory::Unit);
+        assert_eq!(villager.cost.food, 50);
+    }
+
+    #[test]
+    fn test_rejects_duplicate_ids() {
+        let json = format!(
+            r#"{{ "versions": {{ "v1": {{ "units": [{}], "buildings": [{}] }} }} }}"#,
+            entity("house"),
+            entity("house")
+        );
+        let err = GameDatabase::parse(&json).unwrap_err();
+        assert!(err.contains("duplicate id"), "{}", err);
+    }
+
+    #[test]
+    fn test_rejects_empty_database() {
+        assert!(GameDatabase::parse(r#"{ "versions": {} }"#).is_err());
+    }
+
+    #[test]
+    fn test_resolve_falls_back_to_older_version() {
+        let db = GameDatabase::parse(GAME_DATA).unwrap();
+        assert_eq!(db.resolve("2026-03-01").0, "2026-02-10");
+        assert_eq!(db.resolve("2030-01-01").0, "2026-05-07");
+        assert_eq!(db.resolve("2020-01-01").0, "2026-02-10");
+    }
+
+    #[test]
+    fn test_diff_reports_added_and_changed() {
+        let db = GameDatabase::parse(GAME_DATA).unwrap();
+        let diff = db.diff("2026-02-10", "2026-05-07").unwrap();
+        assert!(diff.added.iter().any(|e| e.id == "camel_archer"));
+        assert!(diff.removed.is_empty());
+        let wheelbarrow = diff.changed.iter().find(|c| c.id == "wheelbarrow").unwrap();
+        assert_eq!(wheelbarrow.fields, vec!["cost"]);
+        assert!(db.diff("2026-02-10", "nope").is_err());
+    }
+
+    #[test]
+    fn test_available_to() {
+        let db = GameDatabase::parse(GAME_DATA).unwrap();
+        let (_, snapshot) = db.resolve("2026-05-07");
+        let (_, longbow) = snapshot.find("longbowman").unwrap();
+        assert!(longbow.available_to("english"));
+        assert!(!longbow.available_to("French"));
+        assert!(snapshot.find("house").unwrap().1.available_to("French"));
+    }
+}
@@ -150,7 +150,7 @@ pub fn register_hotkeys<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
     };
 
     // Register all hotkeys using helper
-    let hotkeys: [(&str, &'static str); 14] = [
+    let hotkeys: [(&str, &'static str); 15] = [
         (&hotkey_config.toggle_overlay, "hotkey-toggle-overlay"),
         (&hotkey_config.previous_step, "hotkey-previous-step"),
         (&hotkey_config.next_step, "hotkey-next-step"),
@@ -171,6 +171,7 @@ pub fn register_hotkeys<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
         (&hotkey_config.activate_branch_2, "hotkey-activate-branch-2"),
         (&hotkey_config.activate_branch_3, "hotkey-activate-branch-3"),
         (&hotkey_config.activate_branch_4, "hotkey-activate-branch-4"),
+        (&hotkey_config.cycle_profile, "hotkey-cycle-profile"),
     ];
 
     for (key_str, event_name) in hotkeys {
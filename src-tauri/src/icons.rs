@@ -0,0 +1,442 @@
+//! Icon tokens in step descriptions.
+//!
+//! Build sources write icons in several notations: `[icon:maa]`, a bare
+//! `[barracks]`, age4builder's `@unit_icons/knight-2.png@`, `<img src=...>`
+//! tags, plain image paths and emoji. All of them are resolved against the
+//! registry in `data/icons.json` to one canonical id, so the UI only has to
+//! render `[icon:<id>]`. Unknown icons are kept (as their normalized name) and
+//! reported as build warnings rather than dropped.
+
+use crate::config::{all_steps_mut, BuildOrder, DescriptionSegment};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+const ICONS: &str = include_str!("../data/icons.json");
+const UNKNOWN_ICON_WARNING: &str = "Unknown icon";
+const IMAGE_EXTENSIONS: &[&str] = &[".png", ".webp", ".jpg", ".jpeg", ".gif", ".svg"];
+/// Folder-style prefixes image sources put on file names ("unit_knight").
+const PATH_PREFIXES: &[&str] = &["unit_", "building_", "resource_", "technology_", "tech_"];
+/// Variation selector that may follow an emoji.
+const EMOJI_VARIATION: char = '\u{FE0F}';
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IconEntry {
+    pub id: String,
+    pub display_name: String,
+    pub category: String,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    #[serde(default)]
+    pub emoji: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct IconFile {
+    icons: Vec<IconEntry>,
+}
+
+#[derive(Debug)]
+pub struct IconRegistry {
+    icons: HashMap<String, IconEntry>,
+    /// Lowercase alias to canonical id.
+    aliases: HashMap<String, String>,
+    /// Emoji to canonical id, longest first so "🏹" and "🏹️" both match.
+    emoji: Vec<(String, String)>,
+}
+
+impl IconRegistry {
+    pub fn parse(json: &str) -> Result<Self, String> {
+        let file: IconFile =
+            serde_json::from_str(json).map_err(|e| format!("Invalid icon registry: {}", e))?;
+        let mut registry = IconRegistry {
+            icons: HashMap::new(),
+            aliases: HashMap::new(),
+            emoji: Vec::new(),
+        };
+        for icon in file.icons {
+            if icon.id.trim().is_empty() {
+                return Err("Icon registry has an entry with an empty id".to_string());
+            }
+            for alias in &icon.aliases {
+                registry
+                    .aliases
+                    .insert(alias.to_lowercase(), icon.id.clone());
+            }
+            if let Some(emoji) = &icon.emoji {
+                registry.emoji.push((emoji.clone(), icon.id.clone()));
+            }
+            if registry.icons.insert(icon.id.clone(), icon).is_some() {
+                return Err("Icon registry has duplicate ids".to_string());
+            }
+        }
+        registry
+            .emoji
+            .sort_by_key(|(emoji, _)| std::cmp::Reverse(emoji.len()));
+        Ok(registry)
+    }
+
+    /// Bundled registry, parsed and validated once.
+    pub fn bundled() -> Result<&'static IconRegistry, String> {
+        static REGISTRY: OnceLock<Result<IconRegistry, String>> = OnceLock::new();
+        REGISTRY
+            .get_or_init(|| IconRegistry::parse(ICONS))
+            .as_ref()
+            .map_err(|e| e.clone())
+    }
+
+    pub fn is_known(&self, id: &str) -> bool {
+        self.icons.contains_key(id)
+    }
+
+    /// Canonical id for a written icon name, mirroring the frontend's
+    /// `normalizeIconName`: "MAA", "man-at-arms", "Villager_English" and
+    /// "knight-2" become "man_at_arms", "man_at_arms", "villager" and
+    /// "knight". Names that resolve to nothing come back normalized.
+    pub fn normalize_name(&self, raw: &str) -> String {
+        let mut name = raw
+            .trim()
+            .to_lowercase()
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join("_");
+        if let Some(age) = age_alias(&name) {
+            return age.to_string();
+        }
+        name = name.replace('-', "_");
+        name = strip_version_suffix(&name).to_string();
+
+        if name.starts_with("villager_") {
+            return "villager".to_string();
+        }
+        if let Some(id) = self.aliases.get(&name) {
+            return id.clone();
+        }
+        if self.is_known(&name) {
+            return name;
+        }
+        let compact = name.replace('_', "");
+        if let Some(id) = self.aliases.get(&compact) {
+            return id.clone();
+        }
+        name
+    }
+
+    /// Canonical id for an image path: the file name without folders,
+    /// extension and category prefix.
+    fn normalize_path(&self, path: &str) -> String {
+        let file = path.rsplit(['/', '\\']).next().unwrap_or(path);
+        let lower = file.to_lowercase();
+        let stem = IMAGE_EXTENSIONS
+            .iter()
+            .find_map(|ext| lower.strip_suffix(ext))
+            .unwrap_or(&lower);
+        let stem = PATH_PREFIXES
+            .iter()
+            .find_map(|prefix| stem.strip_prefix(prefix))
+            .unwrap_or(stem);
+        self.normalize_name(stem)
+    }
+
+    /// Splits `description` into text and icon segments. Adjacent text is
+    /// merged; an icon's `known` says whether the registry has it.
+    pub fn tokenize(&self, description: &str) -> Vec<DescriptionSegment> {
+        let mut segments = Vec::new();
+        let mut text = String::new();
+        let mut rest = description;
+        while let Some(c) = rest.chars().next() {
+            let at_word_start = text.is_empty() && segments.is_empty()
+                || text.ends_with(char::is_whitespace)
+                || (text.is_empty()
+                    && matches!(segments.last(), Some(DescriptionSegment::Icon { .. })));
+            match self.match_icon(rest, at_word_start) {
+                Some((id, consumed)) => {
+                    if !text.is_empty() {
+                        segments.push(DescriptionSegment::Text {
+                            text: std::mem::take(&mut text),
+                        });
+                    }
+                    let known = self.is_known(&id);
+                    segments.push(DescriptionSegment::Icon { id, known });
+                    rest = &rest[consumed..];
+                }
+                None => {
+                    text.push(c);
+                    rest = &rest[c.len_utf8()..];
+                }
+            }
+        }
+        if !text.is_empty() {
+            segments.push(DescriptionSegment::Text { text });
+        }
+        segments
+    }
+
+    /// The icon starting at the front of `text`, with the bytes it spans.
+    fn match_icon(&self, text: &str, at_word_start: bool) -> Option<(String, usize)> {
+        if let Some(inner) = text.strip_prefix("[icon:") {
+            let end = inner.find(']')?;
+            let id = self.normalize_name(&inner[..end]);
+            return (!id.is_empty()).then_some((id, "[icon:".len() + end + 1));
+        }
+        if let Some(inner) = text.strip_prefix('[') {
+            // Bare brackets are common in prose ("[optional]"), so only
+            // registry names count.
+            let end = inner.find(']')?;
+            let id = self.normalize_name(&inner[..end]);
+            return self.is_known(&id).then_some((id, end + 2));
+        }
+        if let Some(inner) = text.strip_prefix('@') {
+            let end = inner.find('@')?;
+            let content = &inner[..end];
+            if content.is_empty() || content.contains(char::is_whitespace) {
+                return None;
+            }
+            return Some((self.normalize_path(content), end + 2));
+        }
+        if text
+            .get(..4)
+            .is_some_and(|tag| tag.eq_ignore_ascii_case("<img"))
+        {
+            let end = text.find('>')?;
+            let src = img_src(&text[..end])?;
+            return Some((self.normalize_path(src), end + 1));
+        }
+        if at_word_start {
+            let word_end = text.find(char::is_whitespace).unwrap_or(text.len());
+            let word = &text[..word_end];
+            let lower = word.to_lowercase();
+            if word.contains('/') && IMAGE_EXTENSIONS.iter().any(|ext| lower.ends_with(ext)) {
+                return Some((self.normalize_path(word), word_end));
+            }
+        }
+        self.emoji.iter().find_map(|(emoji, id)| {
+            let after = text.strip_prefix(emoji.as_str())?;
+            let variation = if after.starts_with(EMOJI_VARIATION) {
+                EMOJI_VARIATION.len_utf8()
+            } else {
+                0
+            };
+            Some((id.clone(), emoji.len() + variation))
+        })
+    }
+}
+
+fn age_alias(name: &str) -> Option<&'static str> {
+    match name {
+        "age_1" | "age1" => Some("dark_age"),
+        "age_2" | "age2" => Some("feudal_age"),
+        "age_3" | "age3" => Some("castle_age"),
+        "age_4" | "age4" => Some("imperial_age"),
+        _ => None,
+    }
+}
+
+/// Drops a trailing version number ("knight_2", "knight2") when it follows a
+/// non-digit, so "age4"-style names survive when they reach here.
+fn strip_version_suffix(name: &str) -> &str {
+    let without_digits = name.trim_end_matches(|c: char| c.is_ascii_digit());
+    if without_digits.len() == name.len() {
+        return name;
+    }
+    let stem = without_digits.strip_suffix('_').unwrap_or(without_digits);
+    if stem.is_empty() || stem.ends_with(|c: char| c.is_ascii_digit()) {
+        return name;
+    }
+    stem
+}
+
+/// The `src` attribute of an `<img ...` tag, quoted or not.
+fn img_src(tag: &str) -> Option<&str> {
+    // ASCII-only lowering keeps byte offsets valid for slicing `tag`.
+    let lower = tag.to_ascii_lowercase();
+    let start = lower.find("src=")? + "src=".len();
+    let value = &tag[start..];
+    match value.chars().next()? {
+        quote @ ('"' | '\'') => {
+            let value = &value[1..];
+            value.find(quote).map(|end| &value[..end])
+        }
+        _ => value.split(|c: char| c.is_whitespace() || c == '/').next(),
+    }
+}
+
+/// Segments for `description` with the bundled registry.
+pub fn tokenize_description(description: &str) -> Result<Vec<DescriptionSegment>, String> {
+    Ok(IconRegistry::bundled()?.tokenize(description))
+}
+
+fn canonical_description(segments: &[DescriptionSegment]) -> String {
+    segments
+        .iter()
+        .map(|segment| match segment {
+            DescriptionSegment::Text { text } => text.clone(),
+            DescriptionSegment::Icon { id, .. } => format!("[icon:{}]", id),
+        })
+        .collect()
+}
+
+/// Rewrites every step description (main line and branches) to `[icon:<id>]`
+/// notation and replaces the build's unknown-icon warnings with the current
+/// ones.
+pub fn normalize_build_order_icons(order: &mut BuildOrder) -> Result<(), String> {
+    let mut unknown = Vec::new();
+    for step in all_steps_mut(order) {
+        let segments = tokenize_description(&step.description)?;
+        for segment in &segments {
+            if let DescriptionSegment::Icon { id, known: false } = segment {
+                let warning = format!("{} \"{}\" in step {}", UNKNOWN_ICON_WARNING, id, step.id);
+                if !unknown.contains(&warning) {
+                    unknown.push(warning);
+                }
+            }
+        }
+        step.description = canonical_description(&segments);
+    }
+
+    let mut warnings: Vec<String> = order
+        .warnings
+        .take()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|w| !w.starts_with(UNKNOWN_ICON_WARNING))
+        .collect();
+    warnings.extend(unknown);
+    order.warnings = (!warnings.is_empty()).then_some(warnings);
+    Ok(())
+}
+
+/// Fills in `segments` on every step, for copies served to the UI.
+pub fn attach_description_segments(order: &mut BuildOrder) -> Result<(), String> {
+    for step in all_steps_mut(order) {
+        step.segments = Some(tokenize_description(&step.description)?);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::BuildOrderStep;
+
+    fn icon(id: &str, known: bool) -> DescriptionSegment {
+        DescriptionSegment::Icon {
+            id: id.to_string(),
+            known,
+        }
+    }
+
+    fn text(text: &str) -> DescriptionSegment {
+        DescriptionSegment::Text {
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_bundled_registry_is_valid() {
+        let registry = IconRegistry::parse(ICONS).unwrap();
+        assert!(registry.is_known("man_at_arms"));
+        assert_eq!(registry.icons["food"].display_name, "Food");
+    }
+
+    #[test]
+    fn test_normalize_name_matches_frontend_rules() {
+        let registry = IconRegistry::bundled().unwrap();
+        assert_eq!(registry.normalize_name("MAA"), "man_at_arms");
+        assert_eq!(registry.normalize_name("Man-at-Arms"), "man_at_arms");
+        assert_eq!(registry.normalize_name("knight-2"), "knight");
+        assert_eq!(registry.normalize_name("Villager_English"), "villager");
+        assert_eq!(registry.normalize_name("age_2"), "feudal_age");
+        assert_eq!(registry.normalize_name("Some Thing"), "some_thing");
+    }
+
+    #[test]
+    fn test_tokenizes_every_notation() {
+        let segments = tokenize_description(
+            "[icon:maa] and [barracks] @resource/resource_food.png@ <img src=\"icons/gold.webp\"> images/wood.png 🪨️ [optional]",
+        )
+        .unwrap();
+        assert_eq!(
+            segments,
+            vec![
+                icon("man_at_arms", true),
+                text(" and "),
+                icon("barracks", true),
+                text(" "),
+                icon("food", true),
+                text(" "),
+                icon("gold", true),
+                text(" "),
+                icon("wood", true),
+                text(" "),
+                icon("stone", true),
+                text(" [optional]"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_img_src_after_non_ascii_text() {
+        assert_eq!(
+            img_src("<img alt=\"İİİİİİİİ\" SRC=\"a.png\""),
+            Some("a.png")
+        );
+        assert_eq!(
+            tokenize_description("<img alt=\"İİİİİİİİ\" src=\"icons/gold.webp\"> now").unwrap(),
+            tokenize_description("<img src=\"icons/gold.webp\"> now").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_plain_text_is_one_segment() {
+        assert_eq!(
+            tokenize_description("Send 6 to sheep, mail me@home").unwrap(),
+            vec![text("Send 6 to sheep, mail me@home")]
+        );
+        assert!(tokenize_description("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_normalize_build_order_rewrites_and_warns() {
+        let step = |id: &str, description: &str| BuildOrderStep {
+            id: id.to_string(),
+            description: description.to_string(),
+            ..Default::default()
+        };
+        let mut order = BuildOrder {
+            id: "test".to_string(),
+            name: "Test".to_string(),
+            civilization: "English".to_string(),
+            difficulty: "Beginner".to_string(),
+            steps: vec![
+                step("s1", "6 @resource/resource_food.png@ then [icon:xyz]"),
+                step("s2", "🪵 [icon:XYZ]"),
+            ],
+            enabled: true,
+            warnings: Some(vec![
+                "Step 3 has no text".to_string(),
+                "Unknown icon \"old\" in step s9".to_string(),
+            ]),
+            ..Default::default()
+        };
+
+        normalize_build_order_icons(&mut order).unwrap();
+        assert_eq!(order.steps[0].description, "6 [icon:food] then [icon:xyz]");
+        assert_eq!(order.steps[1].description, "[icon:wood] [icon:xyz]");
+        assert_eq!(
+            order.warnings.clone().unwrap(),
+            vec![
+                "Step 3 has no text",
+                "Unknown icon \"xyz\" in step s1",
+                "Unknown icon \"xyz\" in step s2",
+            ]
+        );
+
+        attach_description_segments(&mut order).unwrap();
+        assert_eq!(
+            order.steps[1].segments.clone().unwrap(),
+            vec![icon("wood", true), text(" "), icon("xyz", false)]
+        );
+    }
+}
@@ -1,29 +1,94 @@
+use std::sync::atomic::AtomicU64;
 use std::sync::Mutex;
 #[cfg(target_os = "windows")]
 use tauri::Manager;
 
 mod audio;
+mod bundle;
 mod commands;
 mod config;
+mod config_watcher;
+mod difficulty;
+mod economy;
 mod error;
+mod game_data;
 mod hotkeys;
+mod icons;
 mod platform;
+mod pronunciation;
+mod recommendation;
+mod search;
 mod state;
+mod stats;
+mod step_inference;
+mod team;
 mod tray;
 mod tts;
 #[cfg(target_os = "windows")]
 mod windows;
 
 use commands::*;
-use config::{load_build_orders, load_config};
+use config::{
+    load_build_orders, load_collections, load_config, load_game_plans, load_profiles,
+    load_user_metadata, save_user_metadata, UserMetadataStore,
+};
+use config_watcher::start_config_watcher;
 use hotkeys::register_hotkeys;
+use search::SearchIndex;
 use state::{AppState, GameDetectionRuntime};
 use tray::setup_tray;
 
+/// For stores whose file could not be read: the error says where the old file
+/// was backed up, and the app starts with an empty store.
+fn report_and_start_empty<T: Default>(error: String) -> T {
+    eprintln!("{}", error);
+    T::default()
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    let config = load_config();
-    let build_orders = load_build_orders();
+    let (global_config, config_load_report) = load_config();
+    let profiles = load_profiles().unwrap_or_else(report_and_start_empty);
+    let config = profiles.resolve(&global_config).unwrap_or_else(|e| {
+        eprintln!("Ignoring active profile: {}", e);
+        global_config.clone()
+    });
+    let mut build_orders = load_build_orders();
+    if let Err(e) = icons::IconRegistry::bundled() {
+        eprintln!("Bundled icon registry failed validation: {}", e);
+    }
+    build_orders.iter_mut().for_each(|order| {
+        // Without a valid icon registry, builds keep their stored notation
+        let _ = icons::normalize_build_order_icons(order);
+        difficulty::refresh_difficulty_estimate(order);
+    });
+    let search_index = SearchIndex::build(&build_orders);
+    if let Err(e) = game_data::GameDatabase::bundled() {
+        eprintln!("Bundled game data failed validation: {}", e);
+    }
+    if let Err(e) = economy::EconomyData::bundled() {
+        eprintln!("Bundled economy data failed validation: {}", e);
+    }
+    let collections = load_collections().unwrap_or_else(report_and_start_empty);
+    let game_plans = load_game_plans().unwrap_or_else(report_and_start_empty);
+    let user_metadata = match load_user_metadata() {
+        Ok(mut store) => {
+            if store.migrate_legacy_flags(&build_orders) {
+                if let Err(e) = save_user_metadata(&store) {
+                    eprintln!("Failed to save migrated user metadata: {}", e);
+                }
+            }
+            store
+        }
+        Err(e) => {
+            // Flags still migrate for this session, but the unreadable file
+            // is not replaced until the user changes something.
+            eprintln!("{}", e);
+            let mut store = UserMetadataStore::default();
+            store.migrate_legacy_flags(&build_orders);
+            store
+        }
+    };
 
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
@@ -32,7 +97,15 @@ pub fn run() {
         .plugin(tauri_plugin_global_shortcut::Builder::new().build())
         .manage(AppState {
             config: Mutex::new(config),
+            config_revision: AtomicU64::new(0),
+            global_config: Mutex::new(global_config),
+            profiles: Mutex::new(profiles),
+            config_load_report: Mutex::new(config_load_report),
             build_orders: Mutex::new(build_orders),
+            search_index: Mutex::new(search_index),
+            collections: Mutex::new(collections),
+            game_plans: Mutex::new(game_plans),
+            user_metadata: Mutex::new(user_metadata),
             tts_process: Mutex::new(None),
             game_detection: Mutex::new(GameDetectionRuntime::default()),
         })
@@ -45,6 +118,9 @@ pub fn run() {
                 eprintln!("Failed to register hotkeys: {}", e);
             }
 
+            // Apply hand edits to config.json without a restart
+            start_config_watcher(app.handle().clone());
+
             // On Windows, apply visibility fixes for transparent overlay window
             #[cfg(target_os = "windows")]
             {
@@ -76,7 +152,17 @@ pub fn run() {
         })
         .invoke_handler(tauri::generate_handler![
             get_config,
+            export_config,
+            preview_config_import,
+            import_config,
+            get_data_dir,
+            relocate_data_dir,
+            get_effective_config,
+            get_config_diagnostics,
+            apply_overlay_position,
             save_config,
+            patch_config,
+            get_config_revision,
             reload_hotkeys,
             get_build_orders,
             get_build_orders_dir_path,
@@ -97,6 +183,52 @@ pub fn run() {
             set_overlay_visible,
             import_build_order,
             export_build_order,
+            search_build_orders,
+            get_collections,
+            create_collection,
+            rename_collection,
+            delete_collection,
+            reorder_collections,
+            set_collection_build_orders,
+            set_active_collection,
+            get_cycle_build_order_ids,
+            get_game_plans,
+            create_game_plan,
+            rename_game_plan,
+            delete_game_plan,
+            set_game_plan_stages,
+            validate_game_plan,
+            set_active_game_plan,
+            navigate_game_plan,
+            get_profiles,
+            create_profile,
+            duplicate_profile,
+            rename_profile,
+            delete_profile,
+            activate_profile,
+            cycle_profile,
+            set_build_order_flags,
+            set_build_order_notes,
+            set_step_annotation,
+            record_build_order_played,
+            record_build_order_result,
+            set_build_settings_overrides,
+            recommend_build_orders,
+            get_build_order_stats,
+            simulate_build_order_economy,
+            get_game_data_versions,
+            get_game_entity,
+            list_game_entities,
+            diff_game_data,
+            suggest_step_resources,
+            get_team_role_view,
+            check_team_checkpoints,
+            export_bundle,
+            preview_bundle,
+            import_bundle,
+            add_step_attachment,
+            remove_step_attachment,
+            get_build_assets_dir_path,
             tts::speak,
             tts::tts_stop,
             audio::play_sound,
@@ -0,0 +1,248 @@
+//! Expands build-order shorthand into text that sounds natural when spoken.
+//!
+//! Step descriptions are written for the eye ("5 vils -> gold, 2nd TC @ 1:30").
+//! Before handing text to the platform TTS engine we expand game clock timings
+//! and apply the user's pronunciation dictionary (`VoiceConfig.pronunciations`).
+
+use crate::config::PronunciationEntry;
+
+/// Prepares `text` for the speech engine: expands `m:ss` timings, then applies
+/// the dictionary as whole-word replacements.
+pub fn prepare_speech_text(text: &str, dictionary: &[PronunciationEntry]) -> String {
+    let expanded = expand_timings(text);
+    apply_dictionary(&expanded, dictionary)
+}
+
+/// Applies dictionary entries left to right. Longer terms win over shorter ones
+/// ("TCs" before "TC"), and a term only matches on word boundaries so "vil" does
+/// not rewrite the middle of "evil". Symbol terms match anywhere, so their
+/// replacements are padded with spaces ("wood->gold" reads "wood to gold") and
+/// runs of whitespace are collapsed afterwards.
+pub fn apply_dictionary(text: &str, dictionary: &[PronunciationEntry]) -> String {
+    let mut entries: Vec<&PronunciationEntry> = dictionary
+        .iter()
+        .filter(|e| !e.term.trim().is_empty())
+        .collect();
+    entries.sort_by_key(|e| std::cmp::Reverse(e.term.len()));
+
+    let mut out = String::with_capacity(text.len());
+    let mut pos = 0;
+    'outer: while pos < text.len() {
+        let rest = &text[pos..];
+        for entry in &entries {
+            if term_matches_at(text, pos, entry) {
+                let (starts_with_word, ends_with_word) = word_edges(&entry.term);
+                if !starts_with_word {
+                    out.push(' ');
+                }
+                out.push_str(&entry.spoken);
+                if !ends_with_word {
+                    out.push(' ');
+                }
+                pos += entry.term.len();
+                continue 'outer;
+            }
+        }
+        // No entry matched here: copy one char through unchanged.
+        let ch = rest.chars().next().unwrap_or_default();
+        out.push(ch);
+        pos += ch.len_utf8();
+    }
+    out.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Whether `term` starts and ends with a word character.
+fn word_edges(term: &str) -> (bool, bool) {
+    (
+        term.chars().next().is_some_and(is_word_char),
+        term.chars().last().is_some_and(is_word_char),
+    )
+}
+
+fn term_matches_at(text: &str, pos: usize, entry: &PronunciationEntry) -> bool {
+    let term = entry.term.as_str();
+    let Some(candidate) = text.get(pos..pos + term.len()) else {
+        return false;
+    };
+    let same = if entry.case_sensitive {
+        candidate == term
+    } else {
+        candidate.eq_ignore_ascii_case(term)
+    };
+    if !same {
+        return false;
+    }
+
+    // Word-like edges must sit on a word boundary; symbol terms ("->") match anywhere.
+    let (starts_with_word, ends_with_word) = word_edges(term);
+    let before_ok = !starts_with_word || !text[..pos].chars().last().is_some_and(is_word_char);
+    let after_ok = !ends_with_word
+        || !text[pos + term.len()..]
+            .chars()
+            .next()
+            .is_some_and(is_word_char);
+    before_ok && after_ok
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Rewrites game clock timings ("1:30", "10:05", "0:45") into words the speech
+/// engine reads correctly ("1 minute 30", "10 minutes 5", "45 seconds").
+pub fn expand_timings(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::with_capacity(text.len());
+    let mut i = 0;
+
+    while i < chars.len() {
+        if let Some((minutes, seconds, len)) = timing_at(&chars, i) {
+            out.push_str(&spoken_timing(minutes, seconds));
+            i += len;
+        } else {
+            out.push(chars[i]);
+            i += 1;
+        }
+    }
+    out
+}
+
+/// Recognises `m:ss` / `mm:ss` starting at `start` when it is not glued to other
+/// digits or letters. Returns (minutes, seconds, consumed chars).
+fn timing_at(chars: &[char], start: usize) -> Option<(u32, u32, usize)> {
+    if start > 0 && is_word_char(chars[start - 1]) {
+        return None;
+    }
+
+    let minute_len = chars[start..]
+        .iter()
+        .take_while(|c| c.is_ascii_digit())
+        .count();
+    if !(1..=2).contains(&minute_len) || chars.get(start + minute_len) != Some(&':') {
+        return None;
+    }
+
+    let sec_start = start + minute_len + 1;
+    let sec_digits: Vec<char> = chars.iter().skip(sec_start).take(2).copied().collect();
+    if sec_digits.len() != 2 || !sec_digits.iter().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    if chars.get(sec_start + 2).is_some_and(|c| is_word_char(*c)) {
+        return None;
+    }
+
+    let minutes: u32 = chars[start..start + minute_len]
+        .iter()
+        .collect::<String>()
+        .parse()
+        .ok()?;
+    let seconds: u32 = sec_digits.iter().collect::<String>().parse().ok()?;
+    if seconds >= 60 {
+        return None;
+    }
+    Some((minutes, seconds, minute_len + 3))
+}
+
+fn spoken_timing(minutes: u32, seconds: u32) -> String {
+    let minute_word = if minutes == 1 { "minute" } else { "minutes" };
+    match (minutes, seconds) {
+        (0, s) => format!("{} seconds", s),
+        (m, 0) => format!("{} {}", m, minute_word),
+        (m, s) => format!("{} {} {}", m, minute_word, s),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::VoiceConfig;
+
+    fn entry(term: &str, spoken: &str) -> PronunciationEntry {
+        PronunciationEntry {
+            term: term.to_string(),
+            spoken: spoken.to_string(),
+            case_sensitive: false,
+        }
+    }
+
+    #[test]
+    fn test_default_dictionary_expands_common_shorthand() {
+        let dict = VoiceConfig::default().pronunciations;
+        assert_eq!(
+            prepare_speech_text("5 vils -> gold, 2nd TC", &dict),
+            "5 villagers to gold, 2nd town center"
+        );
+        assert_eq!(prepare_speech_text("Go FC", &dict), "Go fast castle");
+    }
+
+    #[test]
+    fn test_longer_terms_win() {
+        let dict = vec![entry("TC", "town center"), entry("TCs", "town centers")];
+        assert_eq!(apply_dictionary("both TCs", &dict), "both town centers");
+    }
+
+    #[test]
+    fn test_whole_word_matching_only() {
+        let dict = vec![entry("vil", "villager")];
+        assert_eq!(apply_dictionary("evil vil", &dict), "evil villager");
+        assert_eq!(apply_dictionary("vilified", &dict), "vilified");
+    }
+
+    #[test]
+    fn test_case_insensitive_by_default() {
+        let dict = vec![entry("tc", "town center")];
+        assert_eq!(
+            apply_dictionary("TC and Tc", &dict),
+            "town center and town center"
+        );
+    }
+
+    #[test]
+    fn test_case_sensitive_entry() {
+        let mut rule = entry("FC", "fast castle");
+        rule.case_sensitive = true;
+        assert_eq!(apply_dictionary("FC fc", &[rule]), "fast castle fc");
+    }
+
+    #[test]
+    fn test_symbol_terms_match_without_boundaries() {
+        let dict = vec![entry("->", "to"), entry("+", "and")];
+        assert_eq!(apply_dictionary("wood->gold", &dict), "wood to gold");
+        assert_eq!(
+            apply_dictionary("sheep + berries->wood", &dict),
+            "sheep and berries to wood"
+        );
+    }
+
+    #[test]
+    fn test_empty_terms_are_ignored() {
+        let dict = vec![entry("", "nothing"), entry("  ", "spaces")];
+        assert_eq!(apply_dictionary("keep this", &dict), "keep this");
+    }
+
+    #[test]
+    fn test_non_ascii_text_passes_through() {
+        let dict = vec![entry("vils", "villagers")];
+        assert_eq!(
+            apply_dictionary("Zhu Xi → vils", &dict),
+            "Zhu Xi → villagers"
+        );
+    }
+
+    #[test]
+    fn test_expand_timings() {
+        assert_eq!(expand_timings("Feudal at 4:30"), "Feudal at 4 minutes 30");
+        assert_eq!(expand_timings("1:05 house"), "1 minute 5 house");
+        assert_eq!(expand_timings("0:45"), "45 seconds");
+        assert_eq!(expand_timings("by 10:00!"), "by 10 minutes!");
+    }
+
+    #[test]
+    fn test_expand_timings_ignores_non_timings() {
+        assert_eq!(expand_timings("ratio 3:1"), "ratio 3:1");
+        assert_eq!(expand_timings("1:75"), "1:75");
+        assert_eq!(expand_timings("123:45"), "123:45");
+        assert_eq!(expand_timings("a1:30"), "a1:30");
+        assert_eq!(expand_timings("1:300"), "1:300");
+    }
+}
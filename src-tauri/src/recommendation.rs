@@ -0,0 +1,288 @@
+//! Ranks openers for a specific matchup, e.g. during the loading screen.
+//!
+//! Candidates are the enabled builds for the player's civilization. Each one is
+//! scored from its matchup targeting (`opponentCivilizations` /
+//! `opponentStrategies`), the user's favorite/pin flags and the results the user
+//! reported with it, and every contributing factor is returned as a reason so
+//! the UI can explain the pick.
+
+use crate::config::{BuildOrder, MatchRecord};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+pub const DEFAULT_RECOMMENDATION_LIMIT: usize = 5;
+pub const MAX_RECOMMENDATION_LIMIT: usize = 50;
+
+const TARGETS_OPPONENT_SCORE: f32 = 3.0;
+const TARGETS_OTHER_CIVS_SCORE: f32 = -1.0;
+const TARGETS_STRATEGY_SCORE: f32 = 2.0;
+const FAVORITE_SCORE: f32 = 1.5;
+const PINNED_SCORE: f32 = 0.5;
+/// Scales the smoothed win rate (-0.5..0.5) against the matchup.
+const MATCHUP_RESULTS_WEIGHT: f32 = 4.0;
+/// Overall results say less about this particular matchup.
+const OVERALL_RESULTS_WEIGHT: f32 = 1.5;
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RecommendationQuery {
+    pub civilization: String,
+    pub opponent_civilization: Option<String>,
+    /// What the opponent is expected to do ("tower rush"), if known.
+    pub opponent_strategy: Option<String>,
+    pub difficulty: Option<String>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Recommendation {
+    pub order: BuildOrder,
+    pub score: f32,
+    /// Human-readable factors behind the score, most significant first.
+    pub reasons: Vec<String>,
+}
+
+/// Ranks `orders` (with user metadata merged in) for `query`, best first.
+pub fn recommend_build_orders(
+    orders: &[BuildOrder],
+    query: &RecommendationQuery,
+) -> Vec<Recommendation> {
+    let difficulty = query
+        .difficulty
+        .as_deref()
+        .map(str::trim)
+        .filter(|d| !d.is_empty());
+
+    let mut ranked: Vec<Recommendation> = orders
+        .iter()
+        .filter(|o| {
+            o.enabled
+                && o.civilization
+                    .eq_ignore_ascii_case(query.civilization.trim())
+        })
+        .filter(|o| difficulty.is_none_or(|d| o.difficulty.eq_ignore_ascii_case(d)))
+        .map(|o| score_build_order(o, query))
+        .collect();
+
+    ranked.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| {
+                a.order
+                    .name
+                    .to_lowercase()
+                    .cmp(&b.order.name.to_lowercase())
+            })
+    });
+    ranked.truncate(
+        query
+            .limit
+            .unwrap_or(DEFAULT_RECOMMENDATION_LIMIT)
+            .clamp(1, MAX_RECOMMENDATION_LIMIT),
+    );
+    ranked
+}
+
+fn score_build_order(order: &BuildOrder, query: &RecommendationQuery) -> Recommendation {
+    let mut factors: Vec<(f32, String)> = Vec::new();
+    let opponent = query
+        .opponent_civilization
+        .as_deref()
+        .map(str::trim)
+        .filter(|o| !o.is_empty());
+    let strategy = query
+        .opponent_strategy
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty());
+
+    if let (Some(opponent), Some(targets)) = (opponent, order.opponent_civilizations.as_ref()) {
+        if contains_label(targets, opponent) {
+            factors.push((TARGETS_OPPONENT_SCORE, format!("Made for {}", opponent)));
+        } else if !targets.is_empty() {
+            factors.push((
+                TARGETS_OTHER_CIVS_SCORE,
+                format!("Aimed at other matchups than {}", opponent),
+            ));
+        }
+    }
+    if let (Some(strategy), Some(targets)) = (strategy, order.opponent_strategies.as_ref()) {
+        if contains_label(targets, strategy) {
+            factors.push((TARGETS_STRATEGY_SCORE, format!("Answers {}", strategy)));
+        }
+    }
+
+    if order.favorite {
+        factors.push((FAVORITE_SCORE, "Favorite".to_string()));
+    }
+    if order.pinned {
+        factors.push((PINNED_SCORE, "Pinned".to_string()));
+    }
+
+    if let Some(metadata) = &order.user_metadata {
+        if let Some(opponent) = opponent {
+            let record = metadata.matchup_record(opponent);
+            if record.games() > 0 {
+                factors.push((
+                    results_score(record, MATCHUP_RESULTS_WEIGHT),
+                    format!(
+                        "{}-{} vs {} with this build",
+                        record.wins, record.losses, opponent
+                    ),
+                ));
+            }
+        }
+        if metadata.record.games() > 0 {
+            factors.push((
+                results_score(metadata.record, OVERALL_RESULTS_WEIGHT),
+                format!(
+                    "{}-{} overall with this build",
+                    metadata.record.wins, metadata.record.losses
+                ),
+            ));
+        }
+    }
+
+    let score = factors.iter().map(|(s, _)| s).sum();
+    factors.sort_by(|a, b| b.0.abs().partial_cmp(&a.0.abs()).unwrap_or(Ordering::Equal));
+    Recommendation {
+        order: order.clone(),
+        score,
+        reasons: factors.into_iter().map(|(_, reason)| reason).collect(),
+    }
+}
+
+/// Laplace-smoothed win rate centered on zero, so one lucky game doesn't
+/// outweigh matchup targeting.
+fn results_score(record: MatchRecord, weight: f32) -> f32 {
+    let rate = (record.wins as f32 + 1.0) / (record.games() as f32 + 2.0);
+    (rate - 0.5) * weight
+}
+
+fn contains_label(labels: &[String], wanted: &str) -> bool {
+    labels.iter().any(|l| l.trim().eq_ignore_ascii_case(wanted))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{BuildOrderStep, UserMetadataStore};
+
+    fn build(id: &str, civilization: &str) -> BuildOrder {
+        BuildOrder {
+            id: id.to_string(),
+            name: id.to_string(),
+            civilization: civilization.to_string(),
+            difficulty: "Beginner".to_string(),
+            steps: vec![BuildOrderStep {
+                id: "s1".to_string(),
+                description: "Step".to_string(),
+                ..Default::default()
+            }],
+            enabled: true,
+            ..Default::default()
+        }
+    }
+
+    fn query(opponent: Option<&str>) -> RecommendationQuery {
+        RecommendationQuery {
+            civilization: "English".to_string(),
+            opponent_civilization: opponent.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    fn ids(recommendations: &[Recommendation]) -> Vec<&str> {
+        recommendations
+            .iter()
+            .map(|r| r.order.id.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_filters_by_civilization_enabled_and_difficulty() {
+        let mut disabled = build("disabled", "English");
+        disabled.enabled = false;
+        let mut expert = build("expert", "English");
+        expert.difficulty = "Expert".to_string();
+        let orders = vec![
+            build("a", "English"),
+            build("b", "French"),
+            disabled,
+            expert,
+        ];
+
+        assert_eq!(
+            ids(&recommend_build_orders(&orders, &query(None))),
+            vec!["a", "expert"]
+        );
+
+        let mut beginner = query(None);
+        beginner.difficulty = Some("beginner".to_string());
+        assert_eq!(ids(&recommend_build_orders(&orders, &beginner)), vec!["a"]);
+    }
+
+    #[test]
+    fn test_matchup_targeting_ranks_first() {
+        let mut anti_french = build("anti-french", "English");
+        anti_french.opponent_civilizations = Some(vec!["French".to_string()]);
+        let mut anti_mongols = build("anti-mongols", "English");
+        anti_mongols.opponent_civilizations = Some(vec!["Mongols".to_string()]);
+        let orders = vec![anti_mongols, build("generic", "English"), anti_french];
+
+        let ranked = recommend_build_orders(&orders, &query(Some("french")));
+        assert_eq!(ids(&ranked), vec!["anti-french", "generic", "anti-mongols"]);
+        assert_eq!(ranked[0].reasons, vec!["Made for french"]);
+    }
+
+    #[test]
+    fn test_strategy_and_favorite_contribute() {
+        let mut anti_rush = build("anti-rush", "English");
+        anti_rush.opponent_strategies = Some(vec!["Tower Rush".to_string()]);
+        let mut favorite = build("favorite", "English");
+        favorite.favorite = true;
+        let orders = vec![favorite, anti_rush];
+
+        let mut q = query(None);
+        assert_eq!(
+            ids(&recommend_build_orders(&orders, &q)),
+            vec!["favorite", "anti-rush"]
+        );
+        q.opponent_strategy = Some("tower rush".to_string());
+        assert_eq!(
+            ids(&recommend_build_orders(&orders, &q)),
+            vec!["anti-rush", "favorite"]
+        );
+    }
+
+    #[test]
+    fn test_past_results_in_matchup() {
+        let mut store = UserMetadataStore::default();
+        for _ in 0..3 {
+            store.record_result("winner", Some("French"), true);
+            store.record_result("loser", Some("French"), false);
+        }
+        let orders = store.merge_all(&[build("loser", "English"), build("winner", "English")]);
+
+        let ranked = recommend_build_orders(&orders, &query(Some("French")));
+        assert_eq!(ids(&ranked), vec!["winner", "loser"]);
+        assert!(ranked[0].reasons[0].contains("3-0 vs French"));
+        assert!(ranked[1].score < 0.0);
+    }
+
+    #[test]
+    fn test_limit_is_clamped() {
+        let orders: Vec<BuildOrder> = (0..10)
+            .map(|i| build(&format!("b{}", i), "English"))
+            .collect();
+        assert_eq!(
+            recommend_build_orders(&orders, &query(None)).len(),
+            DEFAULT_RECOMMENDATION_LIMIT
+        );
+        let mut q = query(None);
+        q.limit = Some(0);
+        assert_eq!(recommend_build_orders(&orders, &q).len(), 1);
+    }
+}
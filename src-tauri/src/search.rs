@@ -0,0 +1,545 @@
+//! In-memory full-text index over the build order library.
+//!
+//! The index maps normalized terms to the builds (and weighted fields) they occur
+//! in, so `search_build_orders` can answer queries without the webview holding
+//! and scanning a full copy of `AppState.build_orders`. It is rebuilt at startup
+//! and updated incrementally whenever a build is saved, imported or deleted.
+
+use crate::config::BuildOrder;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
+
+pub const DEFAULT_SEARCH_LIMIT: usize = 50;
+pub const MAX_SEARCH_LIMIT: usize = 200;
+
+const NAME_WEIGHT: f32 = 5.0;
+const TAG_WEIGHT: f32 = 4.0;
+const CIVILIZATION_WEIGHT: f32 = 3.0;
+const DESCRIPTION_WEIGHT: f32 = 2.0;
+const STEP_WEIGHT: f32 = 1.0;
+
+const PREFIX_FACTOR: f32 = 0.8;
+const FUZZY_FACTOR: f32 = 0.5;
+
+// Filler words and the `[icon:...]` marker prefix carry no search signal.
+const STOP_WORDS: &[&str] = &["a", "an", "and", "at", "icon", "of", "on", "the", "to"];
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SearchSort {
+    #[default]
+    Relevance,
+    Name,
+    Civilization,
+    RecentlyUpdated,
+    StepCount,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SearchQuery {
+    /// Free text; every term must match (exactly, by prefix, or fuzzily).
+    pub text: String,
+    pub civilization: Option<String>,
+    pub difficulty: Option<String>,
+    pub favorite: Option<bool>,
+    pub pinned: Option<bool>,
+    /// Exact (case-insensitive) tag the build must carry.
+    pub tag: Option<String>,
+    /// Matches `source.type`; builds without source metadata count as "manual".
+    pub source_type: Option<String>,
+    pub sort: SearchSort,
+    pub offset: usize,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchHit {
+    pub order: BuildOrder,
+    pub score: f32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchResults {
+    /// Number of matches before pagination.
+    pub total: usize,
+    pub offset: usize,
+    pub limit: usize,
+    pub hits: Vec<SearchHit>,
+}
+
+#[derive(Debug, Default)]
+pub struct SearchIndex {
+    /// term -> (build id -> accumulated field weight)
+    postings: BTreeMap<String, HashMap<String, f32>>,
+    /// build id -> terms it contributed, so removal doesn't scan every posting.
+    doc_terms: HashMap<String, Vec<String>>,
+}
+
+impl SearchIndex {
+    pub fn build(orders: &[BuildOrder]) -> Self {
+        let mut index = Self::default();
+        for order in orders {
+            index.upsert(order);
+        }
+        index
+    }
+
+    /// Indexes `order`, replacing any previous entry with the same id.
+    pub fn upsert(&mut self, order: &BuildOrder) {
+        self.remove(&order.id);
+
+        let mut weights: HashMap<String, f32> = HashMap::new();
+        let mut add = |text: &str, weight: f32| {
+            for term in tokenize(text) {
+                *weights.entry(term).or_insert(0.0) += weight;
+            }
+        };
+
+        add(&order.name, NAME_WEIGHT);
+        for tag in order.tags.iter().flatten() {
+            add(tag, TAG_WEIGHT);
+        }
+        add(&order.civilization, CIVILIZATION_WEIGHT);
+        add(&order.description, DESCRIPTION_WEIGHT);
+        for step in &order.steps {
+            add(&step.description, STEP_WEIGHT);
+        }
+        for branch in order.branches.iter().flatten() {
+            add(&branch.name, STEP_WEIGHT);
+            for step in &branch.steps {
+                add(&step.description, STEP_WEIGHT);
+            }
+        }
+
+        let mut terms = Vec::with_capacity(weights.len());
+        for (term, weight) in weights {
+            self.postings
+                .entry(term.clone())
+                .or_default()
+                .insert(order.id.clone(), weight);
+            terms.push(term);
+        }
+        self.doc_terms.insert(order.id.clone(), terms);
+    }
+
+    pub fn remove(&mut self, id: &str) {
+        let Some(terms) = self.doc_terms.remove(id) else {
+            return;
+        };
+        for term in terms {
+            if let Some(docs) = self.postings.get_mut(&term) {
+                docs.remove(id);
+                if docs.is_empty() {
+                    self.postings.remove(&term);
+                }
+            }
+        }
+    }
+
+    /// Scores every indexed build against the query text. An empty query
+    /// matches everything with a score of zero.
+    fn score_text(&self, text: &str) -> HashMap<String, f32> {
+        let query_terms = tokenize(text);
+        if query_terms.is_empty() {
+            return self.doc_terms.keys().map(|id| (id.clone(), 0.0)).collect();
+        }
+
+        let mut totals: Option<HashMap<String, f32>> = None;
+        for query_term in &query_terms {
+            let matches = self.score_term(query_term);
+            // AND semantics: a build must match every query term.
+            totals = Some(match totals {
+                None => matches,
+                Some(prev) => prev
+                    .into_iter()
+                    .filter_map(|(id, score)| matches.get(&id).map(|s| (id, score + s)))
+                    .collect(),
+            });
+        }
+        totals.unwrap_or_default()
+    }
+
+    fn score_term(&self, query_term: &str) -> HashMap<String, f32> {
+        let mut scores: HashMap<String, f32> = HashMap::new();
+        let max_distance = fuzzy_distance_budget(query_term);
+
+        for (term, docs) in &self.postings {
+            let factor = if term == query_term {
+                1.0
+            } else if term.starts_with(query_term) {
+                PREFIX_FACTOR
+            } else if max_distance > 0 && within_edit_distance(term, query_term, max_distance) {
+                FUZZY_FACTOR
+            } else {
+                continue;
+            };
+
+            for (id, weight) in docs {
+                let score = scores.entry(id.clone()).or_insert(0.0);
+                *score = score.max(weight * factor);
+            }
+        }
+        scores
+    }
+
+    pub fn search(&self, orders: &[BuildOrder], query: &SearchQuery) -> SearchResults {
+        let scores = self.score_text(&query.text);
+
+        let mut hits: Vec<SearchHit> = orders
+            .iter()
+            .filter(|order| matches_filters(order, query))
+            .filter_map(|order| {
+                scores.get(&order.id).map(|score| SearchHit {
+                    order: order.clone(),
+                    score: *score,
+                })
+            })
+            .collect();
+
+        hits.sort_by(|a, b| compare_hits(a, b, query.sort));
+
+        let limit = query
+            .limit
+            .unwrap_or(DEFAULT_SEARCH_LIMIT)
+            .clamp(1, MAX_SEARCH_LIMIT);
+        let total = hits.len();
+        let hits = hits.into_iter().skip(query.offset).take(limit).collect();
+
+        SearchResults {
+            total,
+            offset: query.offset,
+            limit,
+            hits,
+        }
+    }
+}
+
+fn matches_filters(order: &BuildOrder, query: &SearchQuery) -> bool {
+    let eq = |value: &str, filter: &Option<String>| {
+        filter
+            .as_deref()
+            .is_none_or(|f| f.trim().is_empty() || value.eq_ignore_ascii_case(f.trim()))
+    };
+    let source_type = order
+        .source
+        .as_ref()
+        .map(|s| s.source_type.as_str())
+        .unwrap_or("manual");
+
+    eq(&order.civilization, &query.civilization)
+        && eq(&order.difficulty, &query.difficulty)
+        && eq(source_type, &query.source_type)
+        && query.favorite.is_none_or(|f| order.favorite == f)
+        && query.pinned.is_none_or(|p| order.pinned == p)
+        && query.tag.as_deref().is_none_or(|wanted| {
+            wanted.trim().is_empty()
+                || order
+                    .tags
+                    .iter()
+                    .flatten()
+                    .any(|t| t.trim().eq_ignore_ascii_case(wanted.trim()))
+        })
+}
+
+fn compare_hits(a: &SearchHit, b: &SearchHit, sort: SearchSort) -> Ordering {
+    let by_name = || {
+        a.order
+            .name
+            .to_lowercase()
+            .cmp(&b.order.name.to_lowercase())
+    };
+    let updated = |hit: &SearchHit| {
+        hit.order
+            .source
+            .as_ref()
+            .and_then(|s| s.updated_at.clone().or_else(|| s.imported_at.clone()))
+    };
+
+    match sort {
+        SearchSort::Relevance => b
+            .score
+            .partial_cmp(&a.score)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| b.order.pinned.cmp(&a.order.pinned))
+            .then_with(by_name),
+        SearchSort::Name => by_name(),
+        SearchSort::Civilization => a
+            .order
+            .civilization
+            .to_lowercase()
+            .cmp(&b.order.civilization.to_lowercase())
+            .then_with(by_name),
+        // ISO-8601 timestamps sort lexicographically; undated builds go last.
+        SearchSort::RecentlyUpdated => updated(b).cmp(&updated(a)).then_with(by_name),
+        SearchSort::StepCount => a
+            .order
+            .steps
+            .len()
+            .cmp(&b.order.steps.len())
+            .then_with(by_name),
+    }
+}
+
+/// Lowercases and splits on anything that isn't a letter or digit.
+pub fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .map(|t| t.to_lowercase())
+        .filter(|t| !STOP_WORDS.contains(&t.as_str()))
+        .collect()
+}
+
+/// Short terms must match exactly or by prefix; longer ones tolerate typos.
+fn fuzzy_distance_budget(term: &str) -> usize {
+    match term.chars().count() {
+        0..=3 => 0,
+        4..=7 => 1,
+        _ => 2,
+    }
+}
+
+/// Levenshtein distance check that bails out once `max` is exceeded.
+fn within_edit_distance(a: &str, b: &str, max: usize) -> bool {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    if a.len().abs_diff(b.len()) > max {
+        return false;
+    }
+
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut curr = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != cb);
+            curr[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+        if curr.iter().min().is_some_and(|m| *m > max) {
+            return false;
+        }
+        prev = curr;
+    }
+    prev[b.len()] <= max
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{BuildOrderSourceMetadata, BuildOrderStep};
+
+    fn order(id: &str, name: &str, civ: &str, steps: &[&str]) -> BuildOrder {
+        BuildOrder {
+            id: id.to_string(),
+            name: name.to_string(),
+            civilization: civ.to_string(),
+            difficulty: "Beginner".to_string(),
+            steps: steps
+                .iter()
+                .enumerate()
+                .map(|(i, d)| BuildOrderStep {
+                    id: format!("s{}", i),
+                    description: d.to_string(),
+                    ..Default::default()
+                })
+                .collect(),
+            enabled: true,
+            ..Default::default()
+        }
+    }
+
+    fn library() -> Vec<BuildOrder> {
+        vec![
+            order(
+                "eng-ff",
+                "English Fast Feudal",
+                "English",
+                &["Build a mill"],
+            ),
+            order(
+                "fr-knights",
+                "French Knight Rush",
+                "French",
+                &["Make knights"],
+            ),
+            order(
+                "hre-fc",
+                "HRE Fast Castle",
+                "Holy Roman Empire",
+                &["Build a house"],
+            ),
+        ]
+    }
+
+    fn ids(results: &SearchResults) -> Vec<&str> {
+        results.hits.iter().map(|h| h.order.id.as_str()).collect()
+    }
+
+    fn text(q: &str) -> SearchQuery {
+        SearchQuery {
+            text: q.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_tokenize() {
+        assert_eq!(
+            tokenize("Build [icon:town_center] TC, then 2 Spearmen!"),
+            vec!["build", "town", "center", "tc", "then", "2", "spearmen"]
+        );
+    }
+
+    #[test]
+    fn test_exact_match_ranks_name_above_steps() {
+        let orders = library();
+        let mut orders = orders;
+        orders.push(order("eng-mill", "Mill Opening", "English", &["Boom"]));
+        let index = SearchIndex::build(&orders);
+        let results = index.search(&orders, &text("mill"));
+        assert_eq!(ids(&results), vec!["eng-mill", "eng-ff"]);
+    }
+
+    #[test]
+    fn test_prefix_and_fuzzy_matching() {
+        let orders = library();
+        let index = SearchIndex::build(&orders);
+        assert_eq!(
+            ids(&index.search(&orders, &text("knig"))),
+            vec!["fr-knights"]
+        );
+        assert_eq!(ids(&index.search(&orders, &text("casle"))), vec!["hre-fc"]);
+    }
+
+    #[test]
+    fn test_all_terms_must_match() {
+        let orders = library();
+        let index = SearchIndex::build(&orders);
+        assert_eq!(
+            ids(&index.search(&orders, &text("fast feudal"))),
+            vec!["eng-ff"]
+        );
+        assert!(index.search(&orders, &text("fast knight")).hits.is_empty());
+    }
+
+    #[test]
+    fn test_empty_query_returns_everything_sorted() {
+        let orders = library();
+        let index = SearchIndex::build(&orders);
+        let query = SearchQuery {
+            sort: SearchSort::Name,
+            ..Default::default()
+        };
+        assert_eq!(
+            ids(&index.search(&orders, &query)),
+            vec!["eng-ff", "fr-knights", "hre-fc"]
+        );
+    }
+
+    #[test]
+    fn test_filters() {
+        let mut orders = library();
+        orders[1].favorite = true;
+        orders[2].source = Some(BuildOrderSourceMetadata {
+            source_type: "aoe4guides".to_string(),
+            url: None,
+            imported_at: None,
+            updated_at: None,
+            raw_civilization: None,
+        });
+        let index = SearchIndex::build(&orders);
+
+        let by_civ = SearchQuery {
+            civilization: Some("english".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(ids(&index.search(&orders, &by_civ)), vec!["eng-ff"]);
+
+        let favorites = SearchQuery {
+            favorite: Some(true),
+            ..Default::default()
+        };
+        assert_eq!(ids(&index.search(&orders, &favorites)), vec!["fr-knights"]);
+
+        let manual = SearchQuery {
+            source_type: Some("manual".to_string()),
+            sort: SearchSort::Name,
+            ..Default::default()
+        };
+        assert_eq!(
+            ids(&index.search(&orders, &manual)),
+            vec!["eng-ff", "fr-knights"]
+        );
+    }
+
+    #[test]
+    fn test_tags_are_indexed_and_filterable() {
+        let mut orders = library();
+        orders[1].tags = Some(vec!["Cheese".to_string()]);
+        let index = SearchIndex::build(&orders);
+        assert_eq!(
+            ids(&index.search(&orders, &text("cheese"))),
+            vec!["fr-knights"]
+        );
+
+        let by_tag = SearchQuery {
+            tag: Some("cheese".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(ids(&index.search(&orders, &by_tag)), vec!["fr-knights"]);
+    }
+
+    #[test]
+    fn test_pagination() {
+        let orders = library();
+        let index = SearchIndex::build(&orders);
+        let query = SearchQuery {
+            sort: SearchSort::Name,
+            offset: 1,
+            limit: Some(1),
+            ..Default::default()
+        };
+        let results = index.search(&orders, &query);
+        assert_eq!(results.total, 3);
+        assert_eq!(ids(&results), vec!["fr-knights"]);
+    }
+
+    #[test]
+    fn test_limit_is_clamped() {
+        let orders = library();
+        let index = SearchIndex::build(&orders);
+        let query = SearchQuery {
+            limit: Some(10_000),
+            ..Default::default()
+        };
+        assert_eq!(index.search(&orders, &query).limit, MAX_SEARCH_LIMIT);
+    }
+
+    #[test]
+    fn test_upsert_replaces_and_remove_drops_terms() {
+        let mut orders = library();
+        let mut index = SearchIndex::build(&orders);
+
+        orders[0].name = "English Longbow Rush".to_string();
+        index.upsert(&orders[0]);
+        assert!(index.search(&orders, &text("feudal")).hits.is_empty());
+        assert_eq!(
+            ids(&index.search(&orders, &text("longbow"))),
+            vec!["eng-ff"]
+        );
+
+        index.remove("eng-ff");
+        assert!(!index.postings.contains_key("longbow"));
+        assert!(index.search(&orders, &text("longbow")).hits.is_empty());
+    }
+
+    #[test]
+    fn test_within_edit_distance() {
+        assert!(within_edit_distance("castle", "castel", 2));
+        assert!(within_edit_distance("knight", "knigt", 1));
+        assert!(!within_edit_distance("feudal", "castle", 2));
+        assert!(!within_edit_distance("ab", "abcd", 1));
+    }
+}
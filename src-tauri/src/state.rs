@@ -1,5 +1,10 @@
-use crate::config::{AppConfig, BuildOrder};
+use crate::config::{
+    AppConfig, BuildOrder, CollectionStore, ConfigLoadReport, GamePlanStore, ProfileStore,
+    UserMetadataStore,
+};
+use crate::search::SearchIndex;
 use std::process::Child;
+use std::sync::atomic::AtomicU64;
 use std::sync::Mutex;
 
 /// Live game-detection state, updated by the foreground-window watcher thread.
@@ -13,8 +18,24 @@ pub struct GameDetectionRuntime {
 }
 
 pub struct AppState {
+    /// The live config: `global_config` with the active profile layered on.
     pub config: Mutex<AppConfig>,
+    /// Bumped each time `config` is replaced; only changed while it is locked.
+    pub config_revision: AtomicU64,
+    /// `config.json` as stored; lock after `config`.
+    pub global_config: Mutex<AppConfig>,
+    /// Named config profiles; lock after `global_config`.
+    pub profiles: Mutex<ProfileStore>,
+    /// How `config` was read at startup; see `get_config_diagnostics`.
+    pub config_load_report: Mutex<ConfigLoadReport>,
     pub build_orders: Mutex<Vec<BuildOrder>>,
+    /// Full-text index over `build_orders`; lock after `build_orders` when both are needed.
+    pub search_index: Mutex<SearchIndex>,
+    pub collections: Mutex<CollectionStore>,
+    /// Chained builds; lock after `collections` and before `user_metadata`.
+    pub game_plans: Mutex<GamePlanStore>,
+    /// Personal flags, notes and play history keyed by build id; lock after `collections`.
+    pub user_metadata: Mutex<UserMetadataStore>,
     pub tts_process: Mutex<Option<Child>>,
     pub game_detection: Mutex<GameDetectionRuntime>,
 }
@@ -27,7 +48,15 @@ mod tests {
     fn test_app_state_creation() {
         let state = AppState {
             config: Mutex::new(AppConfig::default()),
+            config_revision: AtomicU64::new(0),
+            global_config: Mutex::new(AppConfig::default()),
+            profiles: Mutex::new(ProfileStore::default()),
+            config_load_report: Mutex::new(ConfigLoadReport::default()),
             build_orders: Mutex::new(Vec::new()),
+            search_index: Mutex::new(SearchIndex::default()),
+            collections: Mutex::new(CollectionStore::default()),
+            game_plans: Mutex::new(GamePlanStore::default()),
+            user_metadata: Mutex::new(UserMetadataStore::default()),
             tts_process: Mutex::new(None),
             game_detection: Mutex::new(GameDetectionRuntime::default()),
         };
@@ -41,7 +70,15 @@ mod tests {
     fn test_app_state_config_mutex() {
         let state = AppState {
             config: Mutex::new(AppConfig::default()),
+            config_revision: AtomicU64::new(0),
+            global_config: Mutex::new(AppConfig::default()),
+            profiles: Mutex::new(ProfileStore::default()),
+            config_load_report: Mutex::new(ConfigLoadReport::default()),
             build_orders: Mutex::new(Vec::new()),
+            search_index: Mutex::new(SearchIndex::default()),
+            collections: Mutex::new(CollectionStore::default()),
+            game_plans: Mutex::new(GamePlanStore::default()),
+            user_metadata: Mutex::new(UserMetadataStore::default()),
             tts_process: Mutex::new(None),
             game_detection: Mutex::new(GameDetectionRuntime::default()),
         };
@@ -61,7 +98,15 @@ mod tests {
     fn test_app_state_build_orders_mutex() {
         let state = AppState {
             config: Mutex::new(AppConfig::default()),
+            config_revision: AtomicU64::new(0),
+            global_config: Mutex::new(AppConfig::default()),
+            profiles: Mutex::new(ProfileStore::default()),
+            config_load_report: Mutex::new(ConfigLoadReport::default()),
             build_orders: Mutex::new(Vec::new()),
+            search_index: Mutex::new(SearchIndex::default()),
+            collections: Mutex::new(CollectionStore::default()),
+            game_plans: Mutex::new(GamePlanStore::default()),
+            user_metadata: Mutex::new(UserMetadataStore::default()),
             tts_process: Mutex::new(None),
             game_detection: Mutex::new(GameDetectionRuntime::default()),
         };
@@ -89,6 +134,7 @@ mod tests {
                 source: None,
                 content_version: None,
                 warnings: None,
+                ..Default::default()
             });
         }
 
@@ -102,7 +148,15 @@ mod tests {
     fn test_app_state_tts_process_mutex() {
         let state = AppState {
             config: Mutex::new(AppConfig::default()),
+            config_revision: AtomicU64::new(0),
+            global_config: Mutex::new(AppConfig::default()),
+            profiles: Mutex::new(ProfileStore::default()),
+            config_load_report: Mutex::new(ConfigLoadReport::default()),
             build_orders: Mutex::new(Vec::new()),
+            search_index: Mutex::new(SearchIndex::default()),
+            collections: Mutex::new(CollectionStore::default()),
+            game_plans: Mutex::new(GamePlanStore::default()),
+            user_metadata: Mutex::new(UserMetadataStore::default()),
             tts_process: Mutex::new(None),
             game_detection: Mutex::new(GameDetectionRuntime::default()),
         };
@@ -119,7 +173,15 @@ mod tests {
 
         let state = Arc::new(AppState {
             config: Mutex::new(AppConfig::default()),
+            config_revision: AtomicU64::new(0),
+            global_config: Mutex::new(AppConfig::default()),
+            profiles: Mutex::new(ProfileStore::default()),
+            config_load_report: Mutex::new(ConfigLoadReport::default()),
             build_orders: Mutex::new(Vec::new()),
+            search_index: Mutex::new(SearchIndex::default()),
+            collections: Mutex::new(CollectionStore::default()),
+            game_plans: Mutex::new(GamePlanStore::default()),
+            user_metadata: Mutex::new(UserMetadataStore::default()),
             tts_process: Mutex::new(None),
             game_detection: Mutex::new(GameDetectionRuntime::default()),
         });
@@ -0,0 +1,433 @@
+//! Metrics derived from a build order's steps, computed once in the backend so
+//! the library view, difficulty estimates and coach reviews agree on them.
+//!
+//! Step `resources` are villager allocations, so resource figures here count
+//! villagers per category rather than amounts spent. Age-ups are detected from
+//! step text ("Feudal Age", `[icon:castle_age]`, "age up").
+
+use crate::config::{BuildOrder, BuildOrderStep};
+use serde::Serialize;
+
+#[derive(Debug, Default, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildOrderStats {
+    pub step_count: usize,
+    pub timed_step_count: usize,
+    /// Timing of the last timed step, in seconds.
+    pub duration_seconds: Option<u32>,
+    pub steps_per_minute: Option<f32>,
+    pub age_ups: Vec<AgeUp>,
+    /// Most villagers on each category at any main step. These are villager
+    /// counts, not resources spent.
+    pub peak_villagers: VillagerPeaks,
+    pub max_villagers: Option<i32>,
+    pub branch_count: usize,
+    pub branches: Vec<BranchStats>,
+    pub longest_gap: Option<TimingGap>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgeUp {
+    /// 2 = Feudal, 3 = Castle, 4 = Imperial.
+    pub age: u8,
+    pub name: String,
+    pub step_id: String,
+    pub step_index: usize,
+    pub timing_seconds: Option<u32>,
+    /// Villager count at the age-up step, carried forward from earlier steps
+    /// when the step itself has none.
+    pub villagers: Option<i32>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VillagerPeaks {
+    pub food: i32,
+    pub wood: i32,
+    pub gold: i32,
+    pub stone: i32,
+    pub builders: i32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BranchStats {
+    pub id: String,
+    pub name: String,
+    /// Main step the branch splits off at.
+    pub start_step_index: u32,
+    /// Number of steps in the branch.
+    pub depth: usize,
+    pub age_ups: Vec<AgeUp>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimingGap {
+    pub from_step_id: String,
+    pub to_step_id: String,
+    pub seconds: u32,
+}
+
+const AGE_NAMES: [(u8, &str); 3] = [(2, "Feudal"), (3, "Castle"), (4, "Imperial")];
+
+/// Parses step timings like "3:30", "10:45" or "1:30:00" into seconds. HTML
+/// left behind by importers ("4:30<br>") is ignored.
+pub fn parse_timing_seconds(timing: &str) -> Option<u32> {
+    let mut clean = String::with_capacity(timing.len());
+    let mut in_tag = false;
+    for c in timing.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag && !c.is_whitespace() => clean.push(c),
+            _ => {}
+        }
+    }
+
+    let parts: Vec<u32> = clean
+        .split(':')
+        .map(|p| {
+            if p.is_empty() || !p.chars().all(|c| c.is_ascii_digit()) {
+                None
+            } else {
+                p.parse().ok()
+            }
+        })
+        .collect::<Option<_>>()?;
+    match parts[..] {
+        [m, s] if s < 60 => Some(m * 60 + s),
+        [h, m, s] if m < 60 && s < 60 => Some(h * 3600 + m * 60 + s),
+        _ => None,
+    }
+}
+
+/// Step timing in seconds, if the step has a parseable one.
+pub fn step_timing_seconds(step: &BuildOrderStep) -> Option<u32> {
+    step.timing.as_deref().and_then(parse_timing_seconds)
+}
+
+/// Villager count for a step: the explicit total, else the sum of the
+/// per-resource assignments.
+pub fn step_villagers(step: &BuildOrderStep) -> Option<i32> {
+    let resources = step.resources.as_ref()?;
+    if resources.villagers.is_some() {
+        return resources.villagers;
+    }
+    let assigned = [
+        resources.food,
+        resources.wood,
+        resources.gold,
+        resources.stone,
+        resources.builders,
+    ];
+    if assigned.iter().all(Option::is_none) {
+        return None;
+    }
+    Some(assigned.iter().flatten().sum())
+}
+
+/// The age a step advances to, if its text mentions one above `current`.
+/// A bare "age up" means the next age.
+fn age_up_target(step: &BuildOrderStep, current: u8) -> Option<u8> {
+    let text = step.description.to_lowercase().replace('_', " ");
+    let named = [
+        (4, ["imperial age", "age iv", "age 4"]),
+        (3, ["castle age", "age iii", "age 3"]),
+        (2, ["feudal age", "age ii", "age 2"]),
+    ]
+    .iter()
+    .find(|(_, phrases)| phrases.iter().any(|p| contains_phrase(&text, p)))
+    .map(|(age, _)| *age);
+
+    match named {
+        Some(age) if age > current => Some(age),
+        Some(_) => None,
+        None if current < 4
+            && ["age up", "aging up", "age-up"]
+                .iter()
+                .any(|p| text.contains(p)) =>
+        {
+            Some(current + 1)
+        }
+        None => None,
+    }
+}
+
+/// `phrase` occurring in `text` as whole words, so "age 2" matches neither
+/// "age 20" nor "village 2".
+fn contains_phrase(text: &str, phrase: &str) -> bool {
+    text.match_indices(phrase).any(|(i, _)| {
+        let before = text[..i].chars().next_back();
+        let after = text[i + phrase.len()..].chars().next();
+        !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+    })
+}
+
+fn age_ups(steps: &[BuildOrderStep], mut current: u8, mut villagers: Option<i32>) -> Vec<AgeUp> {
+    let mut result = Vec::new();
+    for (index, step) in steps.iter().enumerate() {
+        villagers = step_villagers(step).or(villagers);
+        if let Some(age) = age_up_target(step, current) {
+            current = age;
+            result.push(AgeUp {
+                age,
+                name: AGE_NAMES
+                    .iter()
+                    .find(|(a, _)| *a == age)
+                    .map(|(_, name)| name.to_string())
+                    .unwrap_or_default(),
+                step_id: step.id.clone(),
+                step_index: index,
+                timing_seconds: step_timing_seconds(step),
+                villagers,
+            });
+        }
+    }
+    result
+}
+
+fn longest_gap(steps: &[BuildOrderStep]) -> Option<TimingGap> {
+    let timed: Vec<(&BuildOrderStep, u32)> = steps
+        .iter()
+        .filter_map(|s| step_timing_seconds(s).map(|t| (s, t)))
+        .collect();
+    timed
+        .windows(2)
+        .filter(|pair| pair[1].1 > pair[0].1)
+        .map(|pair| TimingGap {
+            from_step_id: pair[0].0.id.clone(),
+            to_step_id: pair[1].0.id.clone(),
+            seconds: pair[1].1 - pair[0].1,
+        })
+        // Earliest gap wins ties
+        .fold(None, |best: Option<TimingGap>, gap| match best {
+            Some(b) if b.seconds >= gap.seconds => Some(b),
+            _ => Some(gap),
+        })
+}
+
+pub fn compute_build_order_stats(order: &BuildOrder) -> BuildOrderStats {
+    let steps = &order.steps;
+    let timings: Vec<u32> = steps.iter().filter_map(step_timing_seconds).collect();
+    let duration_seconds = timings.iter().copied().max();
+    let steps_per_minute = duration_seconds
+        .filter(|d| *d > 0)
+        .map(|d| steps.len() as f32 * 60.0 / d as f32);
+
+    let mut peaks = VillagerPeaks::default();
+    for resources in steps.iter().filter_map(|s| s.resources.as_ref()) {
+        let max = |peak: &mut i32, value: Option<i32>| *peak = (*peak).max(value.unwrap_or(0));
+        max(&mut peaks.food, resources.food);
+        max(&mut peaks.wood, resources.wood);
+        max(&mut peaks.gold, resources.gold);
+        max(&mut peaks.stone, resources.stone);
+        max(&mut peaks.builders, resources.builders);
+    }
+
+    let main_age_ups = age_ups(steps, 1, None);
+    let branches: Vec<BranchStats> = order
+        .branches
+        .iter()
+        .flatten()
+        .map(|branch| {
+            // A branch starts in whatever age the main line reached by then
+            let start = branch.start_step_index as usize;
+            let age = main_age_ups
+                .iter()
+                .filter(|a| a.step_index < start)
+                .map(|a| a.age)
+                .max()
+                .unwrap_or(1);
+            let villagers = steps[..start.min(steps.len())]
+                .iter()
+                .rev()
+                .find_map(step_villagers);
+            BranchStats {
+                id: branch.id.clone(),
+                name: branch.name.clone(),
+                start_step_index: branch.start_step_index,
+                depth: branch.steps.len(),
+                age_ups: age_ups(&branch.steps, age, villagers),
+            }
+        })
+        .collect();
+
+    BuildOrderStats {
+        step_count: steps.len(),
+        timed_step_count: timings.len(),
+        duration_seconds,
+        steps_per_minute,
+        age_ups: main_age_ups,
+        peak_villagers: peaks,
+        max_villagers: steps.iter().filter_map(step_villagers).max(),
+        branch_count: branches.len(),
+        branches,
+        longest_gap: longest_gap(steps),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{BuildOrderBranch, Resources};
+
+    fn step(id: &str, description: &str, timing: Option<&str>) -> BuildOrderStep {
+        BuildOrderStep {
+            id: id.to_string(),
+            description: description.to_string(),
+            timing: timing.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    fn with_resources(mut step: BuildOrderStep, resources: Resources) -> BuildOrderStep {
+        step.resources = Some(resources);
+        step
+    }
+
+    fn order(steps: Vec<BuildOrderStep>) -> BuildOrder {
+        BuildOrder {
+            id: "test".to_string(),
+            name: "Test".to_string(),
+            civilization: "English".to_string(),
+            difficulty: "Beginner".to_string(),
+            steps,
+            enabled: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_timing_seconds() {
+        assert_eq!(parse_timing_seconds("3:30"), Some(210));
+        assert_eq!(parse_timing_seconds("10:05"), Some(605));
+        assert_eq!(parse_timing_seconds("1:30:00"), Some(5400));
+        assert_eq!(parse_timing_seconds(" 4:30<br/>"), Some(270));
+        assert_eq!(parse_timing_seconds("4:75"), None);
+        assert_eq!(parse_timing_seconds("3a:30"), None);
+        assert_eq!(parse_timing_seconds("90"), None);
+        assert_eq!(parse_timing_seconds(""), None);
+    }
+
+    #[test]
+    fn test_age_ups_with_villager_counts() {
+        let stats = compute_build_order_stats(&order(vec![
+            with_resources(
+                step("s1", "6 to sheep", Some("0:00")),
+                Resources {
+                    food: Some(6),
+                    ..Default::default()
+                },
+            ),
+            with_resources(
+                step("s2", "Build [icon:feudal_age] Council Hall", Some("4:30")),
+                Resources {
+                    villagers: Some(17),
+                    ..Default::default()
+                },
+            ),
+            step("s3", "Feudal Age reached, build barracks", Some("6:00")),
+            step("s4", "Age up to Castle Age", Some("11:00")),
+        ]));
+
+        assert_eq!(stats.age_ups.len(), 2);
+        assert_eq!(stats.age_ups[0].name, "Feudal");
+        assert_eq!(stats.age_ups[0].timing_seconds, Some(270));
+        assert_eq!(stats.age_ups[0].villagers, Some(17));
+        assert_eq!(stats.age_ups[1].age, 3);
+        assert_eq!(stats.age_ups[1].step_id, "s4");
+        assert_eq!(stats.age_ups[1].villagers, Some(17));
+    }
+
+    #[test]
+    fn test_bare_age_up_advances_one_age() {
+        let stats = compute_build_order_stats(&order(vec![
+            step("s1", "Age up with the Abbey", None),
+            step("s2", "age up again", None),
+            step("s3", "Send village 2 to gold", None),
+        ]));
+        let ages: Vec<u8> = stats.age_ups.iter().map(|a| a.age).collect();
+        assert_eq!(ages, vec![2, 3]);
+    }
+
+    #[test]
+    fn test_timing_metrics() {
+        let stats = compute_build_order_stats(&order(vec![
+            step("s1", "a", Some("0:00")),
+            step("s2", "b", None),
+            step("s3", "c", Some("1:00")),
+            step("s4", "d", Some("4:00")),
+            step("s5", "e", Some("5:00")),
+        ]));
+        assert_eq!(stats.timed_step_count, 4);
+        assert_eq!(stats.duration_seconds, Some(300));
+        assert_eq!(stats.steps_per_minute, Some(1.0));
+        assert_eq!(
+            stats.longest_gap,
+            Some(TimingGap {
+                from_step_id: "s3".to_string(),
+                to_step_id: "s4".to_string(),
+                seconds: 180,
+            })
+        );
+    }
+
+    #[test]
+    fn test_untimed_build_has_no_timing_metrics() {
+        let stats = compute_build_order_stats(&order(vec![step("s1", "a", None)]));
+        assert_eq!(stats.duration_seconds, None);
+        assert_eq!(stats.steps_per_minute, None);
+        assert_eq!(stats.longest_gap, None);
+    }
+
+    #[test]
+    fn test_peak_villagers_per_category() {
+        let stats = compute_build_order_stats(&order(vec![
+            with_resources(
+                step("s1", "a", None),
+                Resources {
+                    food: Some(6),
+                    wood: Some(2),
+                    ..Default::default()
+                },
+            ),
+            with_resources(
+                step("s2", "b", None),
+                Resources {
+                    food: Some(4),
+                    wood: Some(8),
+                    builders: Some(3),
+                    ..Default::default()
+                },
+            ),
+        ]));
+        assert_eq!(stats.peak_villagers.food, 6);
+        assert_eq!(stats.peak_villagers.wood, 8);
+        assert_eq!(stats.peak_villagers.builders, 3);
+        assert_eq!(stats.max_villagers, Some(15));
+    }
+
+    #[test]
+    fn test_branch_depth_and_inherited_age() {
+        let mut build = order(vec![
+            step("s1", "a", None),
+            step("s2", "Feudal Age", None),
+            step("s3", "c", None),
+        ]);
+        build.branches = Some(vec![BuildOrderBranch {
+            id: "b1".to_string(),
+            name: "Fast Castle".to_string(),
+            trigger: None,
+            start_step_index: 2,
+            steps: vec![step("b1-1", "x", None), step("b1-2", "Feudal age up", None)],
+        }]);
+
+        let stats = compute_build_order_stats(&build);
+        assert_eq!(stats.branch_count, 1);
+        assert_eq!(stats.branches[0].depth, 2);
+        // Already in Feudal, so "Feudal age up" is not an age-up; no bare "age up" either
+        assert!(stats.branches[0].age_ups.is_empty());
+    }
+}
@@ -0,0 +1,407 @@
+//! Suggests icons for steps, and works out what they cost, from the units,
+//! buildings and technologies their text mentions ("Build Barracks, then 2
+//! Spearmen"). Villagers the text sends somewhere ("6 vils to sheep") are
+//! proposed as the step's `resources`.
+//!
+//! Names are matched against the game data catalogue (names, plurals, aliases
+//! and the build's civilization-specific names) as whole words, longest first,
+//! with an optional count in front. Suggestions are returned to the author to
+//! accept; nothing is written to the build here.
+
+use crate::config::{BuildOrder, BuildOrderStep, Resources};
+use crate::game_data::{Cost, EntityCategory, GameDataSnapshot, GameEntity};
+use serde::Serialize;
+
+/// Largest count taken from text; bigger numbers are timings or resource amounts.
+const MAX_QUANTITY: u32 = 50;
+/// Moving villagers ("6 vils to sheep") costs nothing; only these verbs mean
+/// new ones are trained.
+const TRAIN_WORDS: &[&str] = &["train", "queue", "produce", "make", "create"];
+const NUMBER_WORDS: &[(&str, u32)] = &[
+    ("a", 1),
+    ("an", 1),
+    ("one", 1),
+    ("two", 2),
+    ("three", 3),
+    ("four", 4),
+    ("five", 5),
+    ("six", 6),
+    ("seven", 7),
+    ("eight", 8),
+    ("nine", 9),
+    ("ten", 10),
+];
+
+/// Villager words an allocation count can stand in front of.
+const VILLAGER_WORDS: &[&str] = &["villager", "villagers", "vil", "vils", "vill", "vills"];
+const FOOD_WORDS: &[&str] = &[
+    "food", "sheep", "berry", "berries", "deer", "boar", "farm", "farms", "fish",
+];
+const WOOD_WORDS: &[&str] = &["wood", "tree", "trees", "lumber"];
+const BUILD_WORDS: &[&str] = &["build", "builds", "building", "builder", "builders"];
+/// Words after "6 vils" searched for where they go.
+const ALLOCATION_LOOKAHEAD: usize = 4;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StepMention {
+    pub id: String,
+    pub category: EntityCategory,
+    pub name: String,
+    pub quantity: u32,
+    /// False for mentions that cost nothing, like villagers being moved.
+    pub counted: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StepResourceSuggestion {
+    pub step_id: String,
+    pub mentions: Vec<StepMention>,
+    /// Villagers the text assigns ("6 vils to sheep", "2 vils build a
+    /// house"), proposed as the step's `resources`. None when it assigns none.
+    pub resources: Option<Resources>,
+    /// What the counted mentions cost. This is spending, not villager counts,
+    /// so it is shown but never copied into `resources`.
+    pub cost: Cost,
+    /// Icon keys for the mentions, usable as `[icon:<key>]`.
+    pub icons: Vec<String>,
+}
+
+/// Lowercases and turns everything but letters and digits into single spaces,
+/// so "Man-at-Arms," and "man at arms" compare equal.
+fn normalize(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if c.is_alphanumeric() {
+            out.extend(c.to_lowercase());
+        } else if !out.ends_with(' ') {
+            out.push(' ');
+        }
+    }
+    out.trim().to_string()
+}
+
+/// Every form a name is written in: as is, plural, and "-man" as "-men".
+fn name_forms(name: &str) -> Vec<String> {
+    let name = normalize(name);
+    let mut forms = vec![name.clone()];
+    if let Some(stem) = name.strip_suffix("man") {
+        forms.push(format!("{}men", stem));
+    } else if !name.ends_with('s') {
+        forms.push(format!("{}s", name));
+    }
+    forms
+}
+
+/// Names of `entity` for `civilization`, with civ-specific names first.
+fn names_for(entity: &GameEntity, civilization: &str) -> Vec<String> {
+    let mut names: Vec<String> = entity
+        .civilization_names
+        .iter()
+        .filter(|(civ, _)| civ.eq_ignore_ascii_case(civilization.trim()))
+        .flat_map(|(_, name)| name_forms(name))
+        .collect();
+    names.extend(name_forms(&entity.name));
+    names.extend(entity.aliases.iter().flat_map(|a| name_forms(a)));
+    names.extend(name_forms(&entity.id.replace('_', " ")));
+    names.dedup();
+    names
+}
+
+fn quantity_before(words: &[&str]) -> Option<u32> {
+    let word = words.last()?;
+    word.parse::<u32>()
+        .ok()
+        .filter(|n| (1..=MAX_QUANTITY).contains(n))
+        .or_else(|| {
+            NUMBER_WORDS
+                .iter()
+                .find(|(w, _)| w == word)
+                .map(|(_, n)| *n)
+        })
+}
+
+/// Units, buildings and technologies `text` mentions, in order of appearance.
+pub fn find_mentions(
+    text: &str,
+    snapshot: &GameDataSnapshot,
+    civilization: &str,
+) -> Vec<StepMention> {
+    // `[icon:barracks]` tokens become words so they match like plain names
+    let text = normalize(&text.replace("[icon:", " ").replace('_', " "));
+    let words: Vec<&str> = text.split(' ').filter(|w| !w.is_empty()).collect();
+
+    let mut candidates: Vec<(Vec<String>, EntityCategory, &GameEntity)> = snapshot
+        .entities()
+        .filter(|(_, e)| e.available_to(civilization))
+        .flat_map(|(category, entity)| {
+            names_for(entity, civilization)
+                .into_iter()
+                .map(move |name| {
+                    let phrase = name.split(' ').map(str::to_string).collect();
+                    (phrase, category, entity)
+                })
+        })
+        .collect();
+    // Longest names first so "archery range" wins over "range"
+    candidates.sort_by_key(|(phrase, _, _)| std::cmp::Reverse(phrase.len()));
+
+    let mut taken = vec![false; words.len()];
+    let mut found: Vec<(usize, StepMention)> = Vec::new();
+    for (phrase, category, entity) in &candidates {
+        let len = phrase.len();
+        let mut start = 0;
+        while start + len <= words.len() {
+            let end = start + len;
+            if words[start..end].iter().eq(phrase.iter()) && !taken[start..end].iter().any(|t| *t) {
+                taken[start..end].iter_mut().for_each(|t| *t = true);
+                found.push((
+                    start,
+                    StepMention {
+                        id: entity.id.clone(),
+                        category: *category,
+                        name: entity.name.clone(),
+                        quantity: quantity_before(&words[..start]).unwrap_or(1),
+                        counted: true,
+                    },
+                ));
+                start = end;
+            } else {
+                start += 1;
+            }
+        }
+    }
+    found.sort_by_key(|(position, _)| *position);
+
+    let trains = TRAIN_WORDS.iter().any(|w| words.contains(w));
+    found
+        .into_iter()
+        .map(|(_, mut mention)| {
+            if mention.id == "villager" && !trains {
+                mention.counted = false;
+            }
+            mention
+        })
+        .collect()
+}
+
+type AllocationSlot = fn(&mut Resources) -> &mut Option<i32>;
+
+fn allocation_slot(word: &str) -> Option<AllocationSlot> {
+    if FOOD_WORDS.contains(&word) {
+        Some(|r| &mut r.food)
+    } else if WOOD_WORDS.contains(&word) {
+        Some(|r| &mut r.wood)
+    } else if word == "gold" {
+        Some(|r| &mut r.gold)
+    } else if word == "stone" {
+        Some(|r| &mut r.stone)
+    } else if BUILD_WORDS.contains(&word) {
+        Some(|r| &mut r.builders)
+    } else {
+        None
+    }
+}
+
+/// Villager allocation `text` describes: each "<count> vils" followed closely
+/// by a resource ("to sheep", "on gold") or by building adds to that slot.
+fn propose_allocation(text: &str) -> Option<Resources> {
+    let text = normalize(text);
+    let words: Vec<&str> = text.split(' ').filter(|w| !w.is_empty()).collect();
+    let mut resources = Resources::default();
+    let mut found = false;
+    for (index, word) in words.iter().enumerate() {
+        if !VILLAGER_WORDS.contains(word) {
+            continue;
+        }
+        let Some(count) = quantity_before(&words[..index]) else {
+            continue;
+        };
+        let slot = words[index + 1..]
+            .iter()
+            .take(ALLOCATION_LOOKAHEAD)
+            // The next count starts another allocation
+            .take_while(|w| w.parse::<u32>().is_err())
+            .find_map(|w| allocation_slot(w));
+        if let Some(slot) = slot {
+            let value = slot(&mut resources);
+            *value = Some(value.unwrap_or(0) + count as i32);
+            found = true;
+        }
+    }
+    found.then_some(resources)
+}
+
+fn total_cost(mentions: &[StepMention], snapshot: &GameDataSnapshot) -> Cost {
+    let mut total = Cost::default();
+    for mention in mentions.iter().filter(|m| m.counted) {
+        if let Some((_, entity)) = snapshot.find(&mention.id) {
+            total.food += entity.cost.food * mention.quantity;
+            total.wood += entity.cost.wood * mention.quantity;
+            total.gold += entity.cost.gold * mention.quantity;
+            total.stone += entity.cost.stone * mention.quantity;
+        }
+    }
+    total
+}
+
+fn suggest_for_step(
+    step: &BuildOrderStep,
+    snapshot: &GameDataSnapshot,
+    civilization: &str,
+) -> Option<StepResourceSuggestion> {
+    let mentions = find_mentions(&step.description, snapshot, civilization);
+    let resources = propose_allocation(&step.description);
+    if mentions.is_empty() && resources.is_none() {
+        return None;
+    }
+    let mut icons: Vec<String> = Vec::new();
+    for mention in &mentions {
+        if !icons.contains(&mention.id) {
+            icons.push(mention.id.clone());
+        }
+    }
+    Some(StepResourceSuggestion {
+        step_id: step.id.clone(),
+        resources,
+        cost: total_cost(&mentions, snapshot),
+        icons,
+        mentions,
+    })
+}
+
+/// Suggestions for the steps (main line and branches) that mention anything.
+/// Steps that already have `resources` are skipped unless `include_existing`.
+pub fn suggest_step_resources(
+    order: &BuildOrder,
+    snapshot: &GameDataSnapshot,
+    include_existing: bool,
+) -> Vec<StepResourceSuggestion> {
+    let branch_steps = order.branches.iter().flatten().flat_map(|b| &b.steps);
+    order
+        .steps
+        .iter()
+        .chain(branch_steps)
+        .filter(|s| include_existing || s.resources.is_none())
+        .filter_map(|s| suggest_for_step(s, snapshot, &order.civilization))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_data::GameDatabase;
+
+    fn snapshot() -> &'static GameDataSnapshot {
+        GameDatabase::bundled().unwrap().resolve("2026-05-07").1
+    }
+
+    fn mentions(text: &str, civilization: &str) -> Vec<(String, u32, bool)> {
+        find_mentions(text, snapshot(), civilization)
+            .into_iter()
+            .map(|m| (m.id, m.quantity, m.counted))
+            .collect()
+    }
+
+    #[test]
+    fn test_finds_buildings_and_units_with_quantities() {
+        assert_eq!(
+            mentions("Build Barracks, then 2 Spearmen", "English"),
+            vec![
+                ("barracks".to_string(), 1, true),
+                ("spearman".to_string(), 2, true)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_longest_name_wins_and_aliases_match() {
+        assert_eq!(
+            mentions("Archery range then three MAA", "French"),
+            vec![
+                ("archery_range".to_string(), 1, true),
+                ("man_at_arms".to_string(), 3, true)
+            ]
+        );
+        assert_eq!(
+            mentions("[icon:town_center] 2nd TC", "English")[0].0,
+            "town_center"
+        );
+    }
+
+    #[test]
+    fn test_civilization_names_and_availability() {
+        assert_eq!(mentions("Build a Ger", "Mongols")[0].0, "house");
+        assert!(mentions("Build a Ger", "English").is_empty());
+        assert!(mentions("Queue longbowmen", "French").is_empty());
+        assert_eq!(mentions("Queue longbowmen", "English")[0].0, "longbowman");
+    }
+
+    #[test]
+    fn test_moved_villagers_cost_nothing() {
+        assert_eq!(
+            mentions("Send 6 villagers to sheep", "English"),
+            vec![("villager".to_string(), 6, false)]
+        );
+        assert_eq!(
+            mentions("Queue 2 vils", "English"),
+            vec![("villager".to_string(), 2, true)]
+        );
+    }
+
+    #[test]
+    fn test_suggestion_totals_cost_and_skips_existing_resources() {
+        let step = |id: &str, description: &str, resources: Option<Resources>| BuildOrderStep {
+            id: id.to_string(),
+            description: description.to_string(),
+            resources,
+            ..Default::default()
+        };
+        let order = BuildOrder {
+            id: "test".to_string(),
+            name: "Test".to_string(),
+            civilization: "English".to_string(),
+            difficulty: "Beginner".to_string(),
+            steps: vec![
+                step("s1", "Build Barracks, then 2 Spearmen", None),
+                step("s2", "Build a house", Some(Resources::default())),
+                step("s3", "Scout the map", None),
+            ],
+            enabled: true,
+            ..Default::default()
+        };
+
+        let suggestions = suggest_step_resources(&order, snapshot(), false);
+        assert_eq!(suggestions.len(), 2);
+        assert_eq!(
+            suggestions[0].cost,
+            Cost {
+                food: 120,
+                wood: 190,
+                gold: 0,
+                stone: 0
+            }
+        );
+        assert_eq!(suggestions[0].icons, vec!["barracks", "spearman"]);
+        assert!(suggestions[0].resources.is_none());
+        assert_eq!(suggestions[1].step_id, "s3");
+
+        assert_eq!(suggest_step_resources(&order, snapshot(), true).len(), 3);
+    }
+
+    #[test]
+    fn test_proposes_villager_allocation() {
+        let proposed = propose_allocation("Send 6 villagers to sheep, 3 vils on wood").unwrap();
+        assert_eq!(proposed.food, Some(6));
+        assert_eq!(proposed.wood, Some(3));
+        assert_eq!(proposed.gold, None);
+
+        let proposed = propose_allocation("2 vils build a house, 1 vil to gold").unwrap();
+        assert_eq!(proposed.builders, Some(2));
+        assert_eq!(proposed.gold, Some(1));
+
+        assert!(propose_allocation("Build Barracks, then 2 Spearmen").is_none());
+        assert!(propose_allocation("Queue 2 vils").is_none());
+    }
+}
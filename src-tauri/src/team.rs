@@ -0,0 +1,310 @@
+//! Team builds: steps tagged with a player role, per-role views and checks
+//! that coordination checkpoints line up.
+//!
+//! A step without a role is played by everyone. A checkpoint is a name shared
+//! by one step per role ("feudal-hit"); the roles are expected to reach it
+//! together, so the timings of those steps should agree.
+
+use crate::config::{BuildOrder, BuildOrderStep};
+use crate::difficulty::refresh_difficulty_estimate;
+use crate::stats::step_timing_seconds;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Checkpoint timings further apart than this do not line up.
+pub const CHECKPOINT_TOLERANCE_SECONDS: u32 = 15;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CheckpointIssueKind {
+    /// A role has no step for the checkpoint.
+    MissingRole,
+    /// A role reaches the checkpoint more than once.
+    Duplicate,
+    /// A checkpoint step has no timing to compare.
+    Untimed,
+    /// Timings across roles differ by more than the tolerance.
+    TimingMismatch,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckpointIssue {
+    pub checkpoint: String,
+    pub kind: CheckpointIssueKind,
+    /// Roles the issue is about.
+    pub roles: Vec<String>,
+    pub message: String,
+}
+
+fn plays(step: &BuildOrderStep, role_id: &str) -> bool {
+    step.role.as_deref().is_none_or(|r| r == role_id)
+}
+
+/// Id of a role view, distinct from the team build's so that saving the view
+/// creates a new build instead of overwriting the team one.
+pub fn role_view_id(build_id: &str, role_id: &str) -> String {
+    format!("{}--{}", build_id, role_id)
+}
+
+/// The build as one player sees it: shared steps plus the role's own, as a
+/// plain solo build under its own id. Branch start indices are remapped onto
+/// the filtered main line, and the difficulty estimate is recomputed for the
+/// steps that remain.
+pub fn extract_role_view(order: &BuildOrder, role_id: &str) -> Result<BuildOrder, String> {
+    let role = order
+        .roles
+        .as_ref()
+        .and_then(|roles| roles.iter().find(|r| r.id == role_id))
+        .ok_or_else(|| format!("Build \"{}\" has no role \"{}\"", order.name, role_id))?;
+
+    let solo = |step: &BuildOrderStep| BuildOrderStep {
+        role: None,
+        ..step.clone()
+    };
+    let mut view = order.clone();
+    view.id = role_view_id(&order.id, role_id);
+    view.name = format!("{} ({})", order.name, role.name);
+    view.roles = None;
+    view.steps = order
+        .steps
+        .iter()
+        .filter(|s| plays(s, role_id))
+        .map(solo)
+        .collect();
+    if let Some(branches) = &mut view.branches {
+        for branch in branches.iter_mut() {
+            let start = (branch.start_step_index as usize).min(order.steps.len());
+            branch.start_step_index = order.steps[..start]
+                .iter()
+                .filter(|s| plays(s, role_id))
+                .count() as u32;
+            branch.steps = branch
+                .steps
+                .iter()
+                .filter(|s| plays(s, role_id))
+                .map(solo)
+                .collect();
+        }
+        branches.retain(|b| !b.steps.is_empty());
+    }
+    refresh_difficulty_estimate(&mut view);
+    Ok(view)
+}
+
+/// Problems with the main line's checkpoints across roles. Solo builds and
+/// checkpoints on shared steps have none.
+pub fn checkpoint_issues(order: &BuildOrder) -> Vec<CheckpointIssue> {
+    let Some(roles) = order.roles.as_ref().filter(|r| !r.is_empty()) else {
+        return Vec::new();
+    };
+
+    // checkpoint -> role -> step timings (None when untimed)
+    let mut checkpoints: BTreeMap<&str, BTreeMap<&str, Vec<Option<u32>>>> = BTreeMap::new();
+    for step in &order.steps {
+        if let (Some(checkpoint), Some(role)) = (&step.checkpoint, &step.role) {
+            checkpoints
+                .entry(checkpoint.trim())
+                .or_default()
+                .entry(role.as_str())
+                .or_default()
+                .push(step_timing_seconds(step));
+        }
+    }
+
+    let mut issues = Vec::new();
+    for (checkpoint, by_role) in checkpoints {
+        let issue = |kind, roles: Vec<String>, message: String| CheckpointIssue {
+            checkpoint: checkpoint.to_string(),
+            kind,
+            roles,
+            message,
+        };
+        let missing: Vec<String> = roles
+            .iter()
+            .filter(|r| !by_role.contains_key(r.id.as_str()))
+            .map(|r| r.id.clone())
+            .collect();
+        if !missing.is_empty() {
+            issues.push(issue(
+                CheckpointIssueKind::MissingRole,
+                missing.clone(),
+                format!(
+                    "Checkpoint \"{}\" has no step for {}",
+                    checkpoint,
+                    missing.join(", ")
+                ),
+            ));
+        }
+
+        let mut timed: Vec<(&str, u32)> = Vec::new();
+        for (role, timings) in &by_role {
+            if timings.len() > 1 {
+                issues.push(issue(
+                    CheckpointIssueKind::Duplicate,
+                    vec![role.to_string()],
+                    format!(
+                        "{} reaches checkpoint \"{}\" more than once",
+                        role, checkpoint
+                    ),
+                ));
+            }
+            match timings[0] {
+                Some(seconds) => timed.push((role, seconds)),
+                None => issues.push(issue(
+                    CheckpointIssueKind::Untimed,
+                    vec![role.to_string()],
+                    format!(
+                        "{}'s step for checkpoint \"{}\" has no timing",
+                        role, checkpoint
+                    ),
+                )),
+            }
+        }
+
+        let earliest = timed.iter().min_by_key(|(_, t)| *t);
+        let latest = timed.iter().max_by_key(|(_, t)| *t);
+        if let (Some(&(first, early)), Some(&(last, late))) = (earliest, latest) {
+            if late - early > CHECKPOINT_TOLERANCE_SECONDS {
+                issues.push(issue(
+                    CheckpointIssueKind::TimingMismatch,
+                    vec![first.to_string(), last.to_string()],
+                    format!(
+                        "Checkpoint \"{}\" is {}s apart: {} at {}:{:02}, {} at {}:{:02}",
+                        checkpoint,
+                        late - early,
+                        first,
+                        early / 60,
+                        early % 60,
+                        last,
+                        late / 60,
+                        late % 60
+                    ),
+                ));
+            }
+        }
+    }
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{BuildOrderBranch, TeamRole};
+
+    fn step(
+        id: &str,
+        role: Option<&str>,
+        timing: Option<&str>,
+        checkpoint: Option<&str>,
+    ) -> BuildOrderStep {
+        BuildOrderStep {
+            id: id.to_string(),
+            description: format!("Step {}", id),
+            timing: timing.map(str::to_string),
+            role: role.map(str::to_string),
+            checkpoint: checkpoint.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    fn role(id: &str, name: &str) -> TeamRole {
+        TeamRole {
+            id: id.to_string(),
+            name: name.to_string(),
+            description: None,
+        }
+    }
+
+    fn team_build(steps: Vec<BuildOrderStep>) -> BuildOrder {
+        BuildOrder {
+            id: "team".to_string(),
+            name: "2v2 Rush and boom".to_string(),
+            civilization: "English".to_string(),
+            difficulty: "Intermediate".to_string(),
+            steps,
+            enabled: true,
+            roles: Some(vec![role("p1", "Feudal rush"), role("p2", "Fast castle")]),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_role_view_keeps_shared_and_own_steps() {
+        let mut order = team_build(vec![
+            step("s1", None, Some("0:00"), None),
+            step("s2", Some("p1"), Some("1:00"), None),
+            step("s3", Some("p2"), Some("1:00"), None),
+            step("s4", Some("p2"), Some("2:00"), None),
+        ]);
+        order.branches = Some(vec![BuildOrderBranch {
+            id: "b1".to_string(),
+            name: "Pressure".to_string(),
+            trigger: None,
+            start_step_index: 3,
+            steps: vec![step("b1s1", Some("p1"), None, None)],
+        }]);
+
+        let p1 = extract_role_view(&order, "p1").unwrap();
+        assert_eq!(p1.id, format!("{}--p1", order.id));
+        assert!(p1.difficulty_estimate.is_some());
+        assert_eq!(p1.name, "2v2 Rush and boom (Feudal rush)");
+        assert!(p1.roles.is_none());
+        let ids: Vec<&str> = p1.steps.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, vec!["s1", "s2"]);
+        assert!(p1.steps.iter().all(|s| s.role.is_none()));
+        let branch = &p1.branches.as_ref().unwrap()[0];
+        assert_eq!(branch.start_step_index, 2);
+
+        let p2 = extract_role_view(&order, "p2").unwrap();
+        assert_eq!(p2.steps.len(), 3);
+        assert!(p2.branches.unwrap().is_empty());
+
+        assert!(extract_role_view(&order, "p3").is_err());
+    }
+
+    #[test]
+    fn test_aligned_checkpoints_have_no_issues() {
+        let order = team_build(vec![
+            step("s1", Some("p1"), Some("4:30"), Some("feudal-hit")),
+            step("s2", Some("p2"), Some("4:40"), Some("feudal-hit")),
+            step("s3", None, Some("5:00"), Some("shared")),
+        ]);
+        assert!(checkpoint_issues(&order).is_empty());
+    }
+
+    #[test]
+    fn test_reports_mismatch_missing_and_untimed() {
+        let order = team_build(vec![
+            step("s1", Some("p1"), Some("4:00"), Some("feudal-hit")),
+            step("s2", Some("p2"), Some("5:00"), Some("feudal-hit")),
+            step("s3", Some("p1"), None, Some("castle")),
+        ]);
+        let issues = checkpoint_issues(&order);
+        let kinds: Vec<(&str, CheckpointIssueKind)> = issues
+            .iter()
+            .map(|i| (i.checkpoint.as_str(), i.kind))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ("castle", CheckpointIssueKind::MissingRole),
+                ("castle", CheckpointIssueKind::Untimed),
+                ("feudal-hit", CheckpointIssueKind::TimingMismatch),
+            ]
+        );
+        assert_eq!(issues[0].roles, vec!["p2"]);
+        assert!(
+            issues[2].message.contains("60s apart"),
+            "{}",
+            issues[2].message
+        );
+    }
+
+    #[test]
+    fn test_solo_build_has_no_checkpoint_issues() {
+        let mut order = team_build(vec![step("s1", None, None, Some("x"))]);
+        order.roles = None;
+        assert!(checkpoint_issues(&order).is_empty());
+    }
+}
@@ -1,9 +1,15 @@
+use crate::config::ProfileStore;
+use crate::state::AppState;
 use tauri::{
-    menu::{Menu, MenuItem},
+    menu::{CheckMenuItem, IsMenuItem, Menu, MenuItem, Submenu},
     tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
-    App, Emitter, Manager, Runtime,
+    App, AppHandle, Emitter, Manager, Runtime,
 };
 
+const TRAY_ID: &str = "main";
+/// Menu ids of the profile submenu; "profile:" alone is the global config.
+const PROFILE_MENU_PREFIX: &str = "profile:";
+
 #[cfg(target_os = "windows")]
 use windows::Win32::Foundation::HWND;
 #[cfg(target_os = "windows")]
@@ -25,20 +31,88 @@ fn force_layered_alpha_opaque<R: Runtime>(window: &tauri::WebviewWindow<R>) {
     }
 }
 
-pub fn setup_tray<R: Runtime>(app: &App<R>) -> tauri::Result<()> {
-    let show_i = MenuItem::with_id(app, "show", "Show Overlay", true, None::<&str>)?;
-    let hide_i = MenuItem::with_id(app, "hide", "Hide Overlay", true, None::<&str>)?;
-    let settings_i = MenuItem::with_id(app, "settings", "Settings", true, None::<&str>)?;
-    let quit_i = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
+fn build_tray_menu<R: Runtime, M: Manager<R>>(
+    manager: &M,
+    profiles: &ProfileStore,
+) -> tauri::Result<Menu<R>> {
+    let show_i = MenuItem::with_id(manager, "show", "Show Overlay", true, None::<&str>)?;
+    let hide_i = MenuItem::with_id(manager, "hide", "Hide Overlay", true, None::<&str>)?;
+    let settings_i = MenuItem::with_id(manager, "settings", "Settings", true, None::<&str>)?;
+    let quit_i = MenuItem::with_id(manager, "quit", "Quit", true, None::<&str>)?;
+
+    let active = profiles.active_profile_id.as_deref();
+    let global_i = CheckMenuItem::with_id(
+        manager,
+        PROFILE_MENU_PREFIX,
+        "Global settings",
+        true,
+        active.is_none(),
+        None::<&str>,
+    )?;
+    let profile_items = profiles
+        .profiles
+        .iter()
+        .map(|profile| {
+            CheckMenuItem::with_id(
+                manager,
+                format!("{}{}", PROFILE_MENU_PREFIX, profile.id),
+                &profile.name,
+                true,
+                active == Some(profile.id.as_str()),
+                None::<&str>,
+            )
+        })
+        .collect::<tauri::Result<Vec<_>>>()?;
+    let mut items: Vec<&dyn IsMenuItem<R>> = vec![&global_i];
+    items.extend(profile_items.iter().map(|item| item as &dyn IsMenuItem<R>));
+    let profiles_i = Submenu::with_id_and_items(manager, "profiles", "Profiles", true, &items)?;
+
+    Menu::with_items(
+        manager,
+        &[&show_i, &hide_i, &profiles_i, &settings_i, &quit_i],
+    )
+}
+
+/// Rebuilds the tray menu so its profile submenu matches `profiles`.
+pub fn refresh_tray_profiles<R: Runtime>(app: &AppHandle<R>, profiles: &ProfileStore) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    match build_tray_menu(app, profiles) {
+        Ok(menu) => {
+            if let Err(e) = tray.set_menu(Some(menu)) {
+                eprintln!("[Tray] Failed to update profile menu: {}", e);
+            }
+        }
+        Err(e) => eprintln!("[Tray] Failed to build profile menu: {}", e),
+    }
+}
 
-    let menu = Menu::with_items(app, &[&show_i, &hide_i, &settings_i, &quit_i])?;
+pub fn setup_tray<R: Runtime>(app: &App<R>) -> tauri::Result<()> {
+    let profiles = app
+        .state::<AppState>()
+        .profiles
+        .lock()
+        .map(|p| p.clone())
+        .unwrap_or_default();
+    let menu = build_tray_menu(app, &profiles)?;
 
-    let _tray = TrayIconBuilder::new()
+    let _tray = TrayIconBuilder::with_id(TRAY_ID)
         .icon(app.default_window_icon().unwrap().clone())
         .menu(&menu)
         .tooltip("AoE4 Overlay")
         .on_menu_event(|app, event| {
             match event.id.as_ref() {
+                id if id.starts_with(PROFILE_MENU_PREFIX) => {
+                    // The frontend applies it through `activate_profile`, like
+                    // the cycle-profile hotkey.
+                    let profile_id = Some(&id[PROFILE_MENU_PREFIX.len()..])
+                        .filter(|id| !id.is_empty())
+                        .map(str::to_string);
+                    if let Err(e) = app.emit("tray-activate-profile", profile_id) {
+                        eprintln!("Failed to emit activate profile event: {}", e);
+                    }
+                }
                 "show" => {
                     // Ensure native overlay window is visible (Windows can start hidden/off-screen).
                     let window = app
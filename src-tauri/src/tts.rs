@@ -1,3 +1,5 @@
+use crate::config::VoiceConfig;
+use crate::pronunciation::prepare_speech_text;
 use crate::state::AppState;
 use std::process::{Child, Command, Stdio};
 use tauri::State;
@@ -85,10 +87,21 @@ fn spawn_tts_process(_text: &str, _rate: f32) -> std::io::Result<Child> {
 
 #[tauri::command]
 pub fn speak(text: String, rate: f32, state: State<'_, AppState>) -> Result<(), String> {
-    // 1. Stop existing speech
+    // 1. Expand abbreviations and timings with the user's pronunciation dictionary
+    let dictionary = state
+        .config
+        .lock()
+        .map_err(|e| e.to_string())?
+        .voice
+        .as_ref()
+        .map(|v| v.pronunciations.clone())
+        .unwrap_or_else(|| VoiceConfig::default().pronunciations);
+    let text = prepare_speech_text(&text, &dictionary);
+
+    // 2. Stop existing speech
     kill_active_tts(&state)?;
 
-    // 2. Spawn new speech process
+    // 3. Spawn new speech process
     match spawn_tts_process(&text, rate) {
         Ok(child) => {
             let mut guard = state
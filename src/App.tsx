@@ -2,7 +2,7 @@ import { useEffect, useState, useRef } from "react";
 import { getCurrentWindow } from "@/lib/tauri";
 import { Overlay } from "@/components/overlay";
 import { SettingsWindow } from "@/components/settings";
-import { useGlobalHotkeys, useBuildOrders, useConfig, useWindowSize, useReminders, useMetronome, useGameDetection } from "@/hooks";
+import { useGlobalHotkeys, useBuildOrders, useConfig, useEffectiveConfigSync, useWindowSize, useReminders, useMetronome, useGameDetection } from "@/hooks";
 import { useConfigStore, useOverlayStore } from "@/stores";
 
 // Extended window interface for Tauri window methods
@@ -89,6 +89,7 @@ function App() {
   useGlobalHotkeys();
   useBuildOrders();
   useConfig();
+  useEffectiveConfigSync();
   useWindowSize();
   useReminders(); // Run reminders even when overlay UI is hidden
   useMetronome();
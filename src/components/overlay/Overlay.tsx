@@ -16,7 +16,7 @@ import { PerformanceReport } from "./dialogs/PerformanceReport";
 import { MatchupPanel } from "./features/MatchupPanel";
 import { CounterGrid } from "./features/CounterGrid";
 import { MacroCycleHUD } from "./indicators/MacroCycleHUD";
-import { showSettings, saveConfig } from "@/lib/tauri";
+import { showSettings, patchConfig } from "@/lib/tauri";
 import { cn } from "@/lib/utils";
 
 
@@ -60,10 +60,9 @@ export function Overlay() {
   // Coach-only: keep audio/timers, hide build UI
   if (coachOnly) {
     const exitCoachMode = async () => {
-      const next = { ...config, coach_only_mode: false };
       useConfigStore.getState().updateConfig({ coach_only_mode: false });
       try {
-        await saveConfig(next);
+        await patchConfig({ coach_only_mode: false });
       } catch (error) {
         console.error("Failed to persist coach-only toggle:", error);
       }
@@ -2,9 +2,9 @@ import { describe, it, expect, vi, beforeEach, afterEach } from "vitest";
 import { render, screen, fireEvent, act } from "@testing-library/react";
 import { TimerBar } from "./TimerBar";
 
-// Mock saveConfig
+// Mock patchConfig
 vi.mock("@/lib/tauri", () => ({
-  saveConfig: vi.fn().mockResolvedValue(undefined),
+  patchConfig: vi.fn().mockResolvedValue(undefined),
 }));
 
 // Mock stores
@@ -13,7 +13,7 @@ const mockIsBadgeDismissed = vi.fn().mockReturnValue(false);
 
 vi.mock("@/stores", () => ({
   useConfigStore: vi.fn(() => ({
-    config: {
+    effectiveConfig: {
       upgradeBadges: {
         enabled: true,
         badges: [
@@ -137,7 +137,7 @@ describe("UpgradeBadges edge cases", () => {
   it("returns null when badges disabled", async () => {
     const { useConfigStore } = await import("@/stores");
     vi.mocked(useConfigStore).mockReturnValue({
-      config: {
+      effectiveConfig: {
         upgradeBadges: {
           enabled: false,
           badges: [],
@@ -153,7 +153,7 @@ describe("UpgradeBadges edge cases", () => {
     const { useElapsedSeconds, useConfigStore } = await import("@/stores");
     vi.mocked(useElapsedSeconds).mockReturnValue(0);
     vi.mocked(useConfigStore).mockReturnValue({
-      config: {
+      effectiveConfig: {
         upgradeBadges: {
           enabled: true,
           badges: [
@@ -177,7 +177,7 @@ describe("UpgradeBadges edge cases", () => {
     const { useElapsedSeconds, useConfigStore } = await import("@/stores");
     vi.mocked(useElapsedSeconds).mockReturnValue(30);
     vi.mocked(useConfigStore).mockReturnValue({
-      config: {
+      effectiveConfig: {
         upgradeBadges: {
           enabled: true,
           badges: [
@@ -201,7 +201,7 @@ describe("UpgradeBadges edge cases", () => {
     const { useElapsedSeconds, useConfigStore, useBadgeStore } = await import("@/stores");
     vi.mocked(useElapsedSeconds).mockReturnValue(150);
     vi.mocked(useConfigStore).mockReturnValue({
-      config: {
+      effectiveConfig: {
         upgradeBadges: {
           enabled: true,
           badges: [
@@ -229,7 +229,7 @@ describe("UpgradeBadges edge cases", () => {
     const { useElapsedSeconds, useConfigStore, useBadgeStore } = await import("@/stores");
     vi.mocked(useElapsedSeconds).mockReturnValue(200); // 80s past 120s trigger
     vi.mocked(useConfigStore).mockReturnValue({
-      config: {
+      effectiveConfig: {
         upgradeBadges: {
           enabled: true,
           badges: [
@@ -258,7 +258,7 @@ describe("UpgradeBadges edge cases", () => {
     const { useElapsedSeconds, useConfigStore, useBadgeStore } = await import("@/stores");
     vi.mocked(useElapsedSeconds).mockReturnValue(300); // Far past all triggers
     vi.mocked(useConfigStore).mockReturnValue({
-      config: {
+      effectiveConfig: {
         upgradeBadges: {
           enabled: true,
           badges: [
@@ -294,7 +294,7 @@ describe("UpgradeBadges edge cases", () => {
     const { useConfigStore, useElapsedSeconds } = await import("@/stores");
     vi.mocked(useElapsedSeconds).mockReturnValue(0);
     vi.mocked(useConfigStore).mockReturnValue({
-      config: {
+      effectiveConfig: {
         upgradeBadges: null,
       },
     });
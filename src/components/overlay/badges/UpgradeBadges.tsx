@@ -38,7 +38,7 @@ function Badge({ name, shortName, isUrgent, onDismiss }: BadgeProps) {
 }
 
 export function UpgradeBadges() {
-  const { config } = useConfigStore();
+  const { effectiveConfig: config } = useConfigStore();
   const { dismissBadge, isBadgeDismissed } = useBadgeStore();
   const elapsedSeconds = useElapsedSeconds();
 
@@ -4,7 +4,7 @@ import { StatusIndicators } from "./StatusIndicators";
 
 // Mock tauri lib
 vi.mock("@/lib/tauri", () => ({
-  saveConfig: vi.fn().mockResolvedValue(undefined),
+  patchConfig: vi.fn().mockResolvedValue(undefined),
   toggleClickThrough: vi.fn().mockResolvedValue(true),
   toggleCompactMode: vi.fn().mockResolvedValue(true),
 }));
@@ -3,7 +3,7 @@ import { Volume2, VolumeX, MousePointer2Off, MousePointer2, Minimize2, Maximize2
 import { useTTS } from "@/hooks";
 import { useConfigStore, useMatchupStore, useOcrAssistStore } from "@/stores";
 import { cn, logTelemetryEvent } from "@/lib/utils";
-import { toggleClickThrough as tauriToggleClickThrough, toggleCompactMode as tauriToggleCompactMode, saveConfig } from "@/lib/tauri";
+import { toggleClickThrough as tauriToggleClickThrough, toggleCompactMode as tauriToggleCompactMode, patchConfig } from "@/lib/tauri";
 
 interface StatusIndicatorsProps {
   onToggleClickThrough?: () => void;
@@ -90,10 +90,7 @@ export function StatusIndicators({
     });
 
     try {
-      await saveConfig({
-        ...config,
-        voice: nextVoice,
-      });
+      await patchConfig({ voice: nextVoice });
     } catch (error) {
       console.error("Failed to save voice config:", error);
     }
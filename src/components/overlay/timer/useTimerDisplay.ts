@@ -7,7 +7,7 @@ import { DEFAULT_TIMER_DRIFT_CONFIG, DEFAULT_VOICE_CONFIG } from "@/types";
 import type { VoiceConfig } from "@/types";
 import type { ReactNode } from "react";
 import { parseTimingToSeconds, formatTime } from "@/stores/timerStore";
-import { saveConfig } from "@/lib/tauri";
+import { patchConfig } from "@/lib/tauri";
 import React from "react";
 
 export interface DeltaStyles {
@@ -46,10 +46,9 @@ export function useTimerDisplay(targetTiming?: string): UseTimerDisplayResult {
       ...voiceConfig,
       enabled: !voiceEnabled,
     };
-    const nextConfig = { ...config, voice: newVoiceConfig };
     updateConfig({ voice: newVoiceConfig });
     try {
-      await saveConfig(nextConfig);
+      await patchConfig({ voice: newVoiceConfig });
     } catch (error) {
       console.error("Failed to persist voice toggle:", error);
     }
@@ -61,7 +60,7 @@ export function useTimerDisplay(targetTiming?: string): UseTimerDisplayResult {
     if (voiceEnabled) {
       await stopSpeaking();
     }
-  }, [config, voiceConfig, voiceEnabled, updateConfig, stopSpeaking]);
+  }, [voiceConfig, voiceEnabled, updateConfig, stopSpeaking]);
 
   const getDeltaStyles = useCallback((): DeltaStyles => {
     if (!deltaStatus) return { icon: null, colorClass: "text-white/40" };
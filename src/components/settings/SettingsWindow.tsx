@@ -31,6 +31,7 @@ import {
   recreateOverlayWindow,
   dialog,
 } from "@/lib/tauri";
+import { saveSettings } from "@/hooks/useConfig";
 import {
   Gamepad2,
   List,
@@ -157,7 +158,7 @@ export function SettingsWindow() {
     const filterValue = value === "all" ? undefined : value;
     updateConfig({ filter_civilization: filterValue });
     try {
-      await saveConfig({ ...config, filter_civilization: filterValue });
+      await saveSettings({ filter_civilization: filterValue ?? null });
     } catch (error) {
       console.error("Failed to save config:", error);
     }
@@ -168,7 +169,7 @@ export function SettingsWindow() {
     const filterValue = value === "all" ? undefined : value;
     updateConfig({ filter_difficulty: filterValue });
     try {
-      await saveConfig({ ...config, filter_difficulty: filterValue });
+      await saveSettings({ filter_difficulty: filterValue ?? null });
     } catch (error) {
       console.error("Failed to save config:", error);
     }
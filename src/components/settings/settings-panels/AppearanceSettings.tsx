@@ -10,7 +10,7 @@ import {
 } from "@/components/ui/select";
 import { Palette, Eye } from "lucide-react";
 import { useConfigStore } from "@/stores";
-import { saveConfig } from "@/lib/tauri";
+import { saveSettings } from "@/hooks/useConfig";
 import type { Theme, FontSize, OverlayPreset } from "@/types";
 
 export function AppearanceSettings() {
@@ -19,7 +19,7 @@ export function AppearanceSettings() {
   const handleThemeChange = async (value: Theme) => {
     updateConfig({ theme: value });
     try {
-      await saveConfig({ ...config, theme: value });
+      await saveSettings({ theme: value });
     } catch (error) {
       console.error("Failed to save config:", error);
     }
@@ -28,7 +28,7 @@ export function AppearanceSettings() {
   const handleFontSizeChange = async (value: FontSize) => {
     updateConfig({ font_size: value });
     try {
-      await saveConfig({ ...config, font_size: value });
+      await saveSettings({ font_size: value });
     } catch (error) {
       console.error("Failed to save config:", error);
     }
@@ -38,7 +38,7 @@ export function AppearanceSettings() {
     const opacity = value[0];
     updateConfig({ overlay_opacity: opacity });
     try {
-      await saveConfig({ ...config, overlay_opacity: opacity });
+      await saveSettings({ overlay_opacity: opacity });
     } catch (error) {
       console.error("Failed to save config:", error);
     }
@@ -48,7 +48,7 @@ export function AppearanceSettings() {
     const ui_scale = value[0];
     updateConfig({ ui_scale });
     try {
-      await saveConfig({ ...config, ui_scale });
+      await saveSettings({ ui_scale });
     } catch (error) {
       console.error("Failed to save config:", error);
     }
@@ -57,7 +57,7 @@ export function AppearanceSettings() {
   const handlePresetChange = async (value: OverlayPreset) => {
     updateConfig({ overlay_preset: value });
     try {
-      await saveConfig({ ...config, overlay_preset: value });
+      await saveSettings({ overlay_preset: value });
     } catch (error) {
       console.error("Failed to save preset:", error);
     }
@@ -66,7 +66,7 @@ export function AppearanceSettings() {
   const handleCoachOnlyToggle = async (enabled: boolean) => {
     updateConfig({ coach_only_mode: enabled });
     try {
-      await saveConfig({ ...config, coach_only_mode: enabled });
+      await saveSettings({ coach_only_mode: enabled });
     } catch (error) {
       console.error("Failed to save coach-only mode:", error);
     }
@@ -190,7 +190,7 @@ export function AppearanceSettings() {
               onCheckedChange={async (enabled) => {
                 updateConfig({ show_clock: enabled });
                 try {
-                  await saveConfig({ ...config, show_clock: enabled });
+                  await saveSettings({ show_clock: enabled });
                 } catch (error) {
                   console.error("Failed to save clock config:", error);
                 }
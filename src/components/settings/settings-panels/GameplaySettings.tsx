@@ -4,7 +4,8 @@ import { Switch } from "@/components/ui/switch";
 import { Input } from "@/components/ui/input";
 import { Gamepad2, Timer } from "lucide-react";
 import { useConfigStore } from "@/stores";
-import { saveConfig, toggleClickThrough, toggleCompactMode, setContentProtection } from "@/lib/tauri";
+import { toggleClickThrough, toggleCompactMode, setContentProtection } from "@/lib/tauri";
+import { saveSettings } from "@/hooks/useConfig";
 import { DEFAULT_TIMER_DRIFT_CONFIG, DEFAULT_GAME_DETECTION_CONFIG } from "@/types";
 import type { TimerDriftConfig } from "@/types";
 
@@ -47,7 +48,7 @@ export function GameplaySettings() {
     };
     updateConfig({ auto_advance: newAutoAdvance });
     try {
-      await saveConfig({ ...config, auto_advance: newAutoAdvance });
+      await saveSettings({ auto_advance: newAutoAdvance });
     } catch (error) {
       console.error("Failed to save config:", error);
     }
@@ -61,7 +62,7 @@ export function GameplaySettings() {
     };
     updateConfig({ auto_advance: newAutoAdvance });
     try {
-      await saveConfig({ ...config, auto_advance: newAutoAdvance });
+      await saveSettings({ auto_advance: newAutoAdvance });
     } catch (error) {
       console.error("Failed to save config:", error);
     }
@@ -78,7 +79,7 @@ export function GameplaySettings() {
     };
     updateConfig({ gameDetection: newConfig });
     try {
-      await saveConfig({ ...config, gameDetection: newConfig });
+      await saveSettings({ gameDetection: newConfig });
     } catch (error) {
       console.error("Failed to save game detection config:", error);
     }
@@ -106,7 +107,7 @@ export function GameplaySettings() {
     setProcessNamesInput(processNames.join(", "));
     updateConfig({ gameDetection: newConfig });
     try {
-      await saveConfig({ ...config, gameDetection: newConfig });
+      await saveSettings({ gameDetection: newConfig });
     } catch (error) {
       console.error("Failed to save game detection process names:", error);
     }
@@ -121,7 +122,7 @@ export function GameplaySettings() {
     const newConfig = { ...gameDetectionConfig, pollIntervalMs };
     updateConfig({ gameDetection: newConfig });
     try {
-      await saveConfig({ ...config, gameDetection: newConfig });
+      await saveSettings({ gameDetection: newConfig });
     } catch (error) {
       console.error("Failed to save game detection poll interval:", error);
     }
@@ -136,7 +137,7 @@ export function GameplaySettings() {
     };
     updateConfig({ timerDrift: newConfig });
     try {
-      await saveConfig({ ...config, timerDrift: newConfig });
+      await saveSettings({ timerDrift: newConfig });
     } catch (error) {
       console.error("Failed to save timer drift config:", error);
     }
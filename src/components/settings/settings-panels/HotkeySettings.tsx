@@ -1,7 +1,8 @@
 import { Button } from "@/components/ui/button";
 import { Keyboard } from "lucide-react";
 import { useConfigStore } from "@/stores";
-import { saveConfig, reloadHotkeys } from "@/lib/tauri";
+import { reloadHotkeys } from "@/lib/tauri";
+import { saveSettings } from "@/hooks/useConfig";
 import type { HotkeyConfig } from "@/types";
 import { HotkeyCapture } from "./HotkeyCapture";
 
@@ -20,6 +21,7 @@ const PRESET_QWERTY: HotkeyConfig = {
   activate_branch_3: "Ctrl+Alt+3",
   activate_branch_4: "Ctrl+Alt+4",
   toggle_counters: "Ctrl+Alt+TAB",
+  cycle_profile: "Ctrl+Alt+F9",
 };
 
 const PRESET_AZERTY: HotkeyConfig = {
@@ -37,6 +39,7 @@ const PRESET_AZERTY: HotkeyConfig = {
   activate_branch_3: "Ctrl+Alt+3",
   activate_branch_4: "Ctrl+Alt+4",
   toggle_counters: "Ctrl+Alt+TAB",
+  cycle_profile: "Ctrl+Alt+F9",
 };
 
 interface HotkeyRowProps {
@@ -61,7 +64,7 @@ export function HotkeySettings() {
     const newHotkeys = { ...config.hotkeys, [key]: value };
     updateConfig({ hotkeys: newHotkeys });
     try {
-      await saveConfig({ ...config, hotkeys: newHotkeys });
+      await saveSettings({ hotkeys: newHotkeys });
       await reloadHotkeys();
     } catch (error) {
       console.error("Failed to save hotkey config:", error);
@@ -71,7 +74,7 @@ export function HotkeySettings() {
   const applyPreset = async (preset: HotkeyConfig) => {
     updateConfig({ hotkeys: preset });
     try {
-      await saveConfig({ ...config, hotkeys: preset });
+      await saveSettings({ hotkeys: preset });
       await reloadHotkeys();
     } catch (error) {
       console.error("Failed to apply hotkey preset:", error);
@@ -143,6 +146,11 @@ export function HotkeySettings() {
           value={config.hotkeys.toggle_counters}
           onChange={(key) => handleHotkeyChange("toggle_counters", key)}
         />
+        <HotkeyRow
+          label="Cycle Settings Profile"
+          value={config.hotkeys.cycle_profile}
+          onChange={(key) => handleHotkeyChange("cycle_profile", key)}
+        />
         
         <div className="pt-2">
           <h3 className="text-xs font-semibold text-muted-foreground uppercase tracking-wider mb-2">Branch Activation</h3>
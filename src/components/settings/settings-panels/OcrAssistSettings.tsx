@@ -3,7 +3,8 @@ import { Input } from "@/components/ui/input";
 import { Label } from "@/components/ui/label";
 import { Switch } from "@/components/ui/switch";
 import { useConfigStore, useOcrAssistStore } from "@/stores";
-import { saveConfig, setContentProtection } from "@/lib/tauri";
+import { setContentProtection } from "@/lib/tauri";
+import { saveSettings } from "@/hooks/useConfig";
 import { DEFAULT_OCR_ASSIST_CONFIG, type OcrAssistConfig } from "@/types";
 
 export function OcrAssistSettings() {
@@ -14,7 +15,7 @@ export function OcrAssistSettings() {
   const persist = async (next: OcrAssistConfig) => {
     updateConfig({ ocrAssist: next });
     try {
-      await saveConfig({ ...config, ocrAssist: next });
+      await saveSettings({ ocrAssist: next });
     } catch (error) {
       console.error("Failed to save OCR assist config:", error);
     }
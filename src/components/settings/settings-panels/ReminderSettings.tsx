@@ -3,7 +3,7 @@ import { Switch } from "@/components/ui/switch";
 import { Input } from "@/components/ui/input";
 import { Bell } from "lucide-react";
 import { useConfigStore } from "@/stores";
-import { saveConfig } from "@/lib/tauri";
+import { saveSettings } from "@/hooks/useConfig";
 import { DEFAULT_REMINDER_CONFIG } from "@/types";
 import type { ReminderConfig } from "@/types";
 
@@ -51,7 +51,7 @@ export function ReminderSettings() {
     const newReminders: ReminderConfig = { ...reminderConfig, enabled: !reminderConfig.enabled };
     updateConfig({ reminders: newReminders });
     try {
-      await saveConfig({ ...config, reminders: newReminders });
+      await saveSettings({ reminders: newReminders });
     } catch (error) {
       console.error("Failed to save reminders config:", error);
     }
@@ -64,7 +64,7 @@ export function ReminderSettings() {
     };
     updateConfig({ reminders: newReminders });
     try {
-      await saveConfig({ ...config, reminders: newReminders });
+      await saveSettings({ reminders: newReminders });
     } catch (error) {
       console.error("Failed to save reminders config:", error);
     }
@@ -77,7 +77,7 @@ export function ReminderSettings() {
     };
     updateConfig({ reminders: newReminders });
     try {
-      await saveConfig({ ...config, reminders: newReminders });
+      await saveSettings({ reminders: newReminders });
     } catch (error) {
       console.error("Failed to save reminders config:", error);
     }
@@ -90,7 +90,7 @@ export function ReminderSettings() {
     };
     updateConfig({ reminders: newReminders });
     try {
-      await saveConfig({ ...config, reminders: newReminders });
+      await saveSettings({ reminders: newReminders });
     } catch (error) {
       console.error("Failed to save reminders config:", error);
     }
@@ -104,7 +104,7 @@ export function ReminderSettings() {
     };
     updateConfig({ reminders: newReminders });
     try {
-      await saveConfig({ ...config, reminders: newReminders });
+      await saveSettings({ reminders: newReminders });
     } catch (error) {
       console.error("Failed to save reminders config:", error);
     }
@@ -115,7 +115,7 @@ export function ReminderSettings() {
     const newReminders: ReminderConfig = { ...reminderConfig, calmMode: nextCalm };
     updateConfig({ reminders: newReminders });
     try {
-      await saveConfig({ ...config, reminders: newReminders });
+      await saveSettings({ reminders: newReminders });
     } catch (error) {
       console.error("Failed to save calm mode:", error);
     }
@@ -127,7 +127,7 @@ export function ReminderSettings() {
     const newReminders: ReminderConfig = { ...reminderConfig, calmMode: nextCalm };
     updateConfig({ reminders: newReminders });
     try {
-      await saveConfig({ ...config, reminders: newReminders });
+      await saveSettings({ reminders: newReminders });
     } catch (error) {
       console.error("Failed to save calm window:", error);
     }
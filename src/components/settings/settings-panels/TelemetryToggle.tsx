@@ -8,7 +8,7 @@ import { ScrollArea } from "@/components/ui/scroll-area";
 import { useConfigStore, useEventLogEvents, useEventLogStore } from "@/stores";
 import { DEFAULT_TELEMETRY_CONFIG, type TelemetryConfig } from "@/types";
 import { formatTimestamp, logTelemetryEvent } from "@/lib/utils";
-import { saveConfig } from "@/lib/tauri";
+import { saveSettings } from "@/hooks/useConfig";
 
 export function TelemetryToggle() {
   const { config, updateConfig } = useConfigStore();
@@ -24,7 +24,7 @@ export function TelemetryToggle() {
     const nextConfig: TelemetryConfig = { ...telemetryConfig, enabled };
     updateConfig({ telemetry: nextConfig });
     try {
-      await saveConfig({ ...config, telemetry: nextConfig });
+      await saveSettings({ telemetry: nextConfig });
     } catch (error) {
       console.error("Failed to save telemetry config:", error);
     }
@@ -3,7 +3,7 @@ import { Switch } from "@/components/ui/switch";
 import { Input } from "@/components/ui/input";
 import { Award } from "lucide-react";
 import { useConfigStore } from "@/stores";
-import { saveConfig } from "@/lib/tauri";
+import { saveSettings } from "@/hooks/useConfig";
 import { DEFAULT_UPGRADE_BADGES_CONFIG } from "@/types";
 import type { UpgradeBadgesConfig, UpgradeBadgeConfig } from "@/types";
 
@@ -76,7 +76,7 @@ export function UpgradeBadgesSettings() {
     };
     updateConfig({ upgradeBadges: newConfig });
     try {
-      await saveConfig({ ...config, upgradeBadges: newConfig });
+      await saveSettings({ upgradeBadges: newConfig });
     } catch (error) {
       console.error("Failed to save badges config:", error);
     }
@@ -92,7 +92,7 @@ export function UpgradeBadgesSettings() {
     };
     updateConfig({ upgradeBadges: newConfig });
     try {
-      await saveConfig({ ...config, upgradeBadges: newConfig });
+      await saveSettings({ upgradeBadges: newConfig });
     } catch (error) {
       console.error("Failed to save badges config:", error);
     }
@@ -108,7 +108,7 @@ export function UpgradeBadgesSettings() {
     };
     updateConfig({ upgradeBadges: newConfig });
     try {
-      await saveConfig({ ...config, upgradeBadges: newConfig });
+      await saveSettings({ upgradeBadges: newConfig });
     } catch (error) {
       console.error("Failed to save badges config:", error);
     }
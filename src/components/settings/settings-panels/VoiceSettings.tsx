@@ -4,7 +4,7 @@ import { Switch } from "@/components/ui/switch";
 import { Button } from "@/components/ui/button";
 import { Volume2 } from "lucide-react";
 import { useConfigStore } from "@/stores";
-import { saveConfig } from "@/lib/tauri";
+import { saveSettings } from "@/hooks/useConfig";
 import { DEFAULT_VOICE_CONFIG } from "@/types";
 import type { VoiceConfig } from "@/types";
 
@@ -16,7 +16,7 @@ export function VoiceSettings() {
     const newVoice: VoiceConfig = { ...voiceConfig, enabled: !voiceConfig.enabled };
     updateConfig({ voice: newVoice });
     try {
-      await saveConfig({ ...config, voice: newVoice });
+      await saveSettings({ voice: newVoice });
     } catch (error) {
       console.error("Failed to save voice config:", error);
     }
@@ -27,7 +27,7 @@ export function VoiceSettings() {
     const newVoice: VoiceConfig = { ...voiceConfig, rate };
     updateConfig({ voice: newVoice });
     try {
-      await saveConfig({ ...config, voice: newVoice });
+      await saveSettings({ voice: newVoice });
     } catch (error) {
       console.error("Failed to save voice config:", error);
     }
@@ -37,7 +37,7 @@ export function VoiceSettings() {
     const newVoice: VoiceConfig = { ...voiceConfig, [option]: !voiceConfig[option] };
     updateConfig({ voice: newVoice });
     try {
-      await saveConfig({ ...config, voice: newVoice });
+      await saveSettings({ voice: newVoice });
     } catch (error) {
       console.error("Failed to save voice config:", error);
     }
@@ -50,7 +50,7 @@ export function VoiceSettings() {
         : { ...voiceConfig, speakSteps: true, speakReminders: true, speakDelta: true };
     updateConfig({ voice: profileConfig });
     try {
-      await saveConfig({ ...config, voice: profileConfig });
+      await saveSettings({ voice: profileConfig });
     } catch (error) {
       console.error("Failed to save voice profile:", error);
     }
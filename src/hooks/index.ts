@@ -1,5 +1,6 @@
 export * from "./useGlobalHotkeys";
 export * from "./useConfig";
+export * from "./useEffectiveConfigSync";
 export * from "./useBuildOrders";
 export * from "./useInterval";
 export * from "./useBoundedQueue";
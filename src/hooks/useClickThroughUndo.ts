@@ -1,6 +1,6 @@
 import { useEffect, useRef, useState, useCallback } from "react";
 import { useConfigStore } from "@/stores";
-import { saveConfig, setClickThrough } from "@/lib/tauri";
+import { patchConfig, setClickThrough } from "@/lib/tauri";
 
 // Duration of the undo window in milliseconds
 const CLICK_THROUGH_UNDO_TIMEOUT = 5000;
@@ -79,7 +79,7 @@ export function useClickThroughUndo() {
 
     // Apply the new state
     updateConfig({ click_through: nextState });
-    saveConfig({ ...config, click_through: nextState }).catch((error) =>
+    patchConfig({ click_through: nextState }).catch((error) =>
       console.error("Failed to persist click-through toggle:", error)
     );
 
@@ -96,7 +96,7 @@ export function useClickThroughUndo() {
         setClickUndoState({ active: false, revertTo: false });
       }
     }, CLICK_THROUGH_UNDO_TIMEOUT);
-  }, [config, updateConfig]);
+  }, [config.click_through, updateConfig]);
 
   const undoClickThrough = useCallback(() => {
     if (!clickUndoState.active) return;
@@ -110,13 +110,13 @@ export function useClickThroughUndo() {
     // Revert to the stored state
     const revertTo = clickUndoState.revertTo;
     updateConfig({ click_through: revertTo });
-    saveConfig({ ...config, click_through: revertTo }).catch((error) =>
+    patchConfig({ click_through: revertTo }).catch((error) =>
       console.error("Failed to persist click-through undo:", error)
     );
 
     // Clear undo state
     setClickUndoState({ active: false, revertTo: false });
-  }, [clickUndoState, config, updateConfig]);
+  }, [clickUndoState, updateConfig]);
 
   return {
     toggleWithUndo,
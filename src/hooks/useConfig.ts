@@ -1,33 +1,78 @@
 import { useEffect, useRef } from "react";
 import { useConfigStore, useSaveStatus } from "@/stores";
-import { CONFIG_CHANGED_EVENT, getConfig, saveConfig, listen } from "@/lib/tauri";
-import type { AppConfig } from "@/types";
+import {
+  CONFIG_CHANGED_EVENT,
+  getConfig,
+  getConfigRevision,
+  patchConfig,
+  listen,
+} from "@/lib/tauri";
+import type { AppConfig, ConfigChange, ConfigPatch } from "@/types";
 
 // Auto-clear saved status after this duration
 const SAVE_STATUS_CLEAR_DELAY = 2000;
 
-export function useConfig() {
-  const { config, setConfig, isLoading, setSaveStatus } = useConfigStore();
-  const saveStatus = useSaveStatus();
-  const clearTimeoutRef = useRef<number | null>(null);
+/** Loads the backend config and the revision it was read at into the store. */
+async function loadConfigIntoStore(): Promise<void> {
+  // Revision first: if the config changes in between, the next save is
+  // rejected and reloads rather than overwriting the newer change
+  const revision = await getConfigRevision();
+  const config = await getConfig();
+  const { setConfig, setRevision } = useConfigStore.getState();
+  setConfig(config);
+  if (revision !== null) setRevision(revision);
+}
 
-  useEffect(() => {
-    const loadConfig = async () => {
+let lastSettingsSave: Promise<void> = Promise.resolve();
+let queuedSettingsPatch: ConfigPatch = {};
+let queuedSettingsSave: Promise<void> | null = null;
+
+/**
+ * Saves settings edited in the settings window: only the given keys are sent,
+ * and only if the config is still at the revision this window last saw. A
+ * rejected save reloads the config, so the window shows what is actually
+ * stored, and rethrows. Saves run one at a time, so a window's own edits never
+ * conflict; edits made while one is in flight (slider drags) go out together.
+ */
+export function saveSettings(patch: ConfigPatch): Promise<void> {
+  queuedSettingsPatch = { ...queuedSettingsPatch, ...patch };
+  if (queuedSettingsSave) return queuedSettingsSave;
+
+  queuedSettingsSave = lastSettingsSave
+    .catch(() => undefined)
+    .then(async () => {
+      const next = queuedSettingsPatch;
+      queuedSettingsPatch = {};
+      queuedSettingsSave = null;
       try {
-        const loaded = await getConfig();
-        setConfig(loaded);
+        const change = await patchConfig(next, useConfigStore.getState().revision);
+        if (change.revision !== null) useConfigStore.getState().setRevision(change.revision);
       } catch (error) {
-        console.error("Failed to load config:", error);
+        await loadConfigIntoStore().catch((reloadError) =>
+          console.error("Failed to reload config:", reloadError)
+        );
+        throw error;
       }
-    };
+    });
+  lastSettingsSave = queuedSettingsSave;
+  return queuedSettingsSave;
+}
+
+export function useConfig() {
+  const { config, setConfig, setRevision, isLoading, setSaveStatus } = useConfigStore();
+  const saveStatus = useSaveStatus();
+  const clearTimeoutRef = useRef<number | null>(null);
 
-    loadConfig();
-  }, [setConfig]);
+  useEffect(() => {
+    loadConfigIntoStore().catch((error) => console.error("Failed to load config:", error));
+  }, []);
 
   useEffect(() => {
     // Keep config in sync across overlay/settings windows
-    const unlistenPromise = listen<AppConfig>(CONFIG_CHANGED_EVENT, (event) => {
-      setConfig(event.payload);
+    const unlistenPromise = listen<ConfigChange>(CONFIG_CHANGED_EVENT, (event) => {
+      const { config, revision } = event.payload;
+      setConfig(config);
+      if (revision !== null) setRevision(revision);
     });
 
     return () => {
@@ -37,7 +82,7 @@ export function useConfig() {
           console.error("Failed to clean up config change listener:", error)
         );
     };
-  }, [setConfig]);
+  }, [setConfig, setRevision]);
 
   // Cleanup timeout on unmount
   useEffect(() => {
@@ -49,7 +94,6 @@ export function useConfig() {
   }, []);
 
   const updateAndSave = async (updates: Partial<AppConfig>) => {
-    const newConfig = { ...config, ...updates };
     useConfigStore.getState().updateConfig(updates);
 
     // Clear any pending status clear
@@ -60,7 +104,7 @@ export function useConfig() {
     setSaveStatus('saving');
 
     try {
-      await saveConfig(newConfig);
+      await saveSettings(updates);
       setSaveStatus('saved');
 
       // Auto-clear saved status after delay
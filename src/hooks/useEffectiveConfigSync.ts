@@ -0,0 +1,28 @@
+import { useEffect } from "react";
+import { useConfigStore, useCurrentBuildOrder } from "@/stores";
+import { getEffectiveConfig } from "@/lib/tauri";
+
+/**
+ * Keeps `effectiveConfig` in the config store resolved for the current build,
+ * so voice, reminders and badges pick up that build's settings overrides.
+ * Re-resolves whenever the config, the build or its overrides change.
+ */
+export function useEffectiveConfigSync() {
+  const config = useConfigStore((state) => state.config);
+  const setEffectiveConfig = useConfigStore((state) => state.setEffectiveConfig);
+  const currentOrder = useCurrentBuildOrder();
+  const buildId = currentOrder?.id ?? null;
+  const overridesKey = JSON.stringify(currentOrder?.userMetadata?.settingsOverrides ?? null);
+
+  useEffect(() => {
+    let cancelled = false;
+    getEffectiveConfig(buildId)
+      .then((effective) => {
+        if (!cancelled) setEffectiveConfig(effective);
+      })
+      .catch((error) => console.error("Failed to resolve effective config:", error));
+    return () => {
+      cancelled = true;
+    };
+  }, [config, buildId, overridesKey, setEffectiveConfig]);
+}
@@ -2,6 +2,9 @@ import { describe, it, expect, vi, beforeEach } from 'vitest';
 import { renderHook, waitFor, act } from '@testing-library/react';
 import { useGlobalHotkeys } from './useGlobalHotkeys';
 import { useBuildOrderStore } from '@/stores/buildOrderStore';
+import { useConfigStore } from '@/stores/configStore';
+import { useTimerStore } from '@/stores/timerStore';
+import { DEFAULT_CONFIG } from '@/types';
 
 const { mockListen } = vi.hoisted(() => ({
   mockListen: vi.fn(() => Promise.resolve(() => { })),
@@ -82,4 +85,48 @@ describe('useGlobalHotkeys', () => {
 
     expect(useBuildOrderStore.getState().activeBranchId).toBe('b1');
   });
+
+  it('should auto-advance from the effective config once the next step is due', async () => {
+    useBuildOrderStore.setState({
+      buildOrders: [
+        {
+          id: 'timed-order',
+          name: 'Timed Order',
+          civilization: 'English',
+          description: 'Desc',
+          difficulty: 'Beginner',
+          enabled: true,
+          steps: [
+            { id: 's1', description: 'Step 1', timing: '0:00' },
+            { id: 's2', description: 'Step 2', timing: '0:30' },
+            { id: 's3', description: 'Step 3', timing: '1:00' },
+          ],
+        },
+      ],
+      currentOrderIndex: 0,
+      currentStepIndex: 1,
+      activeBranchId: null,
+    });
+    // Only the build's override turns auto-advance on
+    useConfigStore.setState({
+      config: DEFAULT_CONFIG,
+      effectiveConfig: { ...DEFAULT_CONFIG, auto_advance: { enabled: true, delay_seconds: 2 } },
+    });
+    useTimerStore.setState({ isRunning: true, elapsedSeconds: 60 });
+    renderHook(() => useGlobalHotkeys());
+
+    act(() => {
+      useTimerStore.setState({ elapsedSeconds: 61 });
+    });
+    expect(useBuildOrderStore.getState().currentStepIndex).toBe(1);
+
+    act(() => {
+      useTimerStore.setState({ elapsedSeconds: 62 });
+    });
+    await waitFor(() => {
+      expect(useBuildOrderStore.getState().currentStepIndex).toBe(2);
+    });
+
+    useTimerStore.setState({ isRunning: false, elapsedSeconds: 0 });
+  });
 });
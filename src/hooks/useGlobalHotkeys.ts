@@ -1,5 +1,5 @@
-import { useEffect, useCallback, useMemo } from "react";
-import { IS_MOCK, emit } from "@/lib/tauri";
+import { useEffect, useCallback, useMemo, useRef } from "react";
+import { IS_MOCK, GAME_PLANS_CHANGED_EVENT, emit, listen } from "@/lib/tauri";
 import { useHotkeyListeners, hotkey, type HotkeyConfig } from "./useHotkeyListener";
 import {
   useBuildOrderStore,
@@ -12,13 +12,31 @@ import {
   resolveActiveSteps,
 } from "@/stores";
 import { parseTimingToSeconds } from "@/stores/timerStore";
-import { toggleClickThrough, toggleCompactMode, speak, setOverlayVisible } from "@/lib/tauri";
+import {
+  toggleClickThrough,
+  toggleCompactMode,
+  speak,
+  setOverlayVisible,
+  getCycleBuildOrderIds,
+  getGamePlans,
+  navigateGamePlan,
+  recordBuildOrderPlayed,
+  cycleProfile,
+  activateProfile,
+} from "@/lib/tauri";
 import { DEFAULT_VOICE_CONFIG } from "@/types";
+import type { GamePlan, GamePlanStore } from "@/types";
 import { logTelemetryEvent } from "@/lib/utils";
 
 export function useGlobalHotkeys() {
-  const { nextStep, previousStep, cycleBuildOrder, resetSteps, setActiveBranch } =
-    useBuildOrderStore();
+  const {
+    nextStep,
+    previousStep,
+    cycleBuildOrder,
+    resetSteps,
+    setActiveBranch,
+    jumpToBuildOrder,
+  } = useBuildOrderStore();
   const { setVisible } = useOverlayStore();
 
   // Apply overlay visibility both to the store (UI state) and the native window
@@ -50,7 +68,7 @@ export function useGlobalHotkeys() {
 
   // Speak step description if voice coaching is enabled
   const speakStep = useCallback(async (description: string) => {
-    const config = useConfigStore.getState().config;
+    const config = useConfigStore.getState().effectiveConfig;
     const voiceConfig = config.voice ?? DEFAULT_VOICE_CONFIG;
 
     if (voiceConfig.enabled && voiceConfig.speakSteps) {
@@ -63,8 +81,71 @@ export function useGlobalHotkeys() {
     }
   }, [convertIconMarkersForTTS]);
 
+  // The active game plan, kept in sync so step presses outside one never
+  // need a round trip to the backend.
+  const activePlanRef = useRef<GamePlan | null>(null);
+  useEffect(() => {
+    const track = (store: GamePlanStore) => {
+      activePlanRef.current =
+        store.plans.find((plan) => plan.id === store.activePlanId) ?? null;
+    };
+    getGamePlans()
+      .then(track)
+      .catch((error) => console.error("Failed to load game plans:", error));
+    const unlisten = listen<GamePlanStore>(GAME_PLANS_CHANGED_EVENT, (event) =>
+      track(event.payload)
+    );
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  // With an active game plan, a step press at a build boundary moves to the
+  // neighbouring build instead. Returns true when it did.
+  const crossGamePlanBoundary = useCallback(async (direction: "next" | "previous") => {
+    const { buildOrders, currentOrderIndex, currentStepIndex, activeBranchId } =
+      useBuildOrderStore.getState();
+    const currentOrder = buildOrders[currentOrderIndex];
+    const plan = activePlanRef.current;
+    if (!currentOrder || !plan?.stages.some((s) => s.buildOrderId === currentOrder.id)) {
+      return false;
+    }
+
+    const position = await navigateGamePlan(
+      currentOrder.id,
+      currentStepIndex,
+      activeBranchId,
+      direction,
+      useTimerStore.getState().elapsedSeconds
+    ).catch((error) => {
+      console.error("Failed to navigate game plan:", error);
+      return null;
+    });
+    if (!position) return false;
+
+    useSessionStore.getState().endSession();
+    jumpToBuildOrder(position.buildOrderId, position.stepIndex);
+    const { buildOrders: orders, currentOrderIndex: index, currentStepIndex: step } =
+      useBuildOrderStore.getState();
+    const landed = orders[index]?.steps?.[step];
+    if (landed?.timing) {
+      recordStepTime(landed.timing);
+    }
+    if (landed?.description) {
+      await speakStep(landed.speech?.trim() || landed.description);
+    }
+    return true;
+  }, [jumpToBuildOrder, recordStepTime, speakStep]);
+
+  const handlePreviousStep = useCallback(async () => {
+    if (await crossGamePlanBoundary("previous")) return;
+    previousStep();
+  }, [crossGamePlanBoundary, previousStep]);
+
   // Handle next step with timer and TTS
   const handleNextStep = useCallback(async () => {
+    if (await crossGamePlanBoundary("next")) return;
+
     const buildOrderStore = useBuildOrderStore.getState();
     const timerStore = useTimerStore.getState();
     const sessionStore = useSessionStore.getState();
@@ -88,6 +169,9 @@ export function useGlobalHotkeys() {
     if (currentStepData) {
       if (currentStepIndex === 0 && !sessionStore.currentSession) {
         sessionStore.startSession(currentOrder.id, currentOrder.name);
+        recordBuildOrderPlayed(currentOrder.id).catch((error) => {
+          console.error("Failed to record build order play:", error);
+        });
       }
 
       const expectedSeconds = parseTimingToSeconds(currentStepData.timing);
@@ -124,11 +208,36 @@ export function useGlobalHotkeys() {
       recordStepTime(nextStepData.timing);
     }
 
-    // Speak the next step description
+    // Speak the next step (author-provided speech text wins over the visual description)
     if (nextStepData?.description) {
-      await speakStep(nextStepData.description);
+      await speakStep(nextStepData.speech?.trim() || nextStepData.description);
     }
-  }, [nextStep, startTimer, recordStepTime, speakStep]);
+  }, [crossGamePlanBoundary, nextStep, startTimer, recordStepTime, speakStep]);
+
+  // Auto-advance: once the running timer passes the next step's timing plus
+  // the configured delay, advance as if next-step were pressed. Reads the
+  // effective config so a build's override applies.
+  const autoAdvancingRef = useRef(false);
+  useEffect(() => {
+    return useTimerStore.subscribe((timer, previous) => {
+      if (!timer.isRunning || timer.elapsedSeconds === previous.elapsedSeconds) return;
+      const { auto_advance } = useConfigStore.getState().effectiveConfig;
+      if (!auto_advance.enabled || autoAdvancingRef.current) return;
+
+      const { buildOrders, currentOrderIndex, currentStepIndex, activeBranchId } =
+        useBuildOrderStore.getState();
+      const order = buildOrders[currentOrderIndex];
+      if (!order) return;
+      const next = resolveActiveSteps(order, activeBranchId)[currentStepIndex + 1];
+      const due = parseTimingToSeconds(next?.timing);
+      if (due === null || timer.elapsedSeconds < due + auto_advance.delay_seconds) return;
+
+      autoAdvancingRef.current = true;
+      handleNextStep().finally(() => {
+        autoAdvancingRef.current = false;
+      });
+    });
+  }, [handleNextStep]);
 
   // Handle reset with timer reset and badge reset (logs its own telemetry)
   const handleReset = useCallback(() => {
@@ -140,8 +249,14 @@ export function useGlobalHotkeys() {
   }, [resetSteps, resetTimer, resetBadges]);
 
   // Handlers that need custom telemetry (with metadata)
-  const handleCycleBuildOrder = useCallback(() => {
-    cycleBuildOrder();
+  const handleCycleBuildOrder = useCallback(async () => {
+    let cycleIds: string[] | undefined;
+    try {
+      cycleIds = await getCycleBuildOrderIds();
+    } catch (error) {
+      console.error("Failed to load cycle build orders:", error);
+    }
+    cycleBuildOrder(cycleIds);
     resetTimer();
     resetBadges();
   }, [cycleBuildOrder, resetTimer, resetBadges]);
@@ -181,7 +296,7 @@ export function useGlobalHotkeys() {
     hotkey("hotkey-toggle-compact", handleToggleCompact, ""), // handles own telemetry
 
     // Step navigation
-    hotkey("hotkey-previous-step", previousStep, "hotkey:step:previous"),
+    hotkey("hotkey-previous-step", handlePreviousStep, "hotkey:step:previous"),
     hotkey("hotkey-next-step", handleNextStep, "hotkey:step:next"),
 
     // Build order controls
@@ -201,6 +316,11 @@ export function useGlobalHotkeys() {
     hotkey("hotkey-activate-branch-3", () => activateBranch(2), "hotkey:branch:3"),
     hotkey("hotkey-activate-branch-4", () => activateBranch(3), "hotkey:branch:4"),
 
+    // Settings profiles
+    hotkey("hotkey-cycle-profile", async () => {
+      await cycleProfile().catch((e) => console.error("Failed to cycle profile:", e));
+    }, "hotkey:profile:cycle"),
+
     // Tray icon events
     hotkey("tray-toggle-overlay", handleToggleOverlay, "tray:overlay:toggle", "tray"),
     hotkey("tray-show-overlay", () => applyVisibility(true), "tray:overlay:show", "tray"),
@@ -209,7 +329,7 @@ export function useGlobalHotkeys() {
     handleToggleOverlay,
     handleToggleClickThrough,
     handleToggleCompact,
-    previousStep,
+    handlePreviousStep,
     handleNextStep,
     handleCycleBuildOrder,
     handleReset,
@@ -223,6 +343,18 @@ export function useGlobalHotkeys() {
   // Set up all hotkey listeners using the factory hook
   useHotkeyListeners(hotkeys);
 
+  // The tray's profile menu sends the chosen id (null = global settings).
+  useEffect(() => {
+    const unlisten = listen<string | null>("tray-activate-profile", (event) => {
+      activateProfile(event.payload ?? null)
+        .then(() => logTelemetryEvent("tray:profile:activate", { source: "tray" }))
+        .catch((e) => console.error("Failed to activate profile:", e));
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
   useEffect(() => {
     if (!IS_MOCK) return;
 
@@ -263,6 +395,7 @@ export function useGlobalHotkeys() {
         [h.activate_branch_3]: "hotkey-activate-branch-3",
         [h.activate_branch_4]: "hotkey-activate-branch-4",
         [h.toggle_counters]: "hotkey-toggle-counters",
+        [h.cycle_profile]: "hotkey-cycle-profile",
       };
 
       const eventName = keyMap[keyString];
@@ -10,7 +10,7 @@ const {
 } = vi.hoisted(() => ({
   mockConfigStore: {
     getState: vi.fn().mockReturnValue({
-      config: {
+      effectiveConfig: {
         reminders: {
           enabled: true,
           villagerQueue: { enabled: true, intervalSeconds: 25 },
@@ -78,7 +78,7 @@ export function useReminders() {
   });
 
   const getReminderConfig = useCallback(() => {
-    const config = useConfigStore.getState().config;
+    const config = useConfigStore.getState().effectiveConfig;
     return config.reminders ?? DEFAULT_REMINDER_CONFIG;
   }, []);
 
@@ -17,7 +17,7 @@ export function useTTS() {
   const { playSound } = useSound();
 
   const getVoiceConfig = useCallback(() => {
-    const config = useConfigStore.getState().config;
+    const config = useConfigStore.getState().effectiveConfig;
     return config.voice ?? DEFAULT_VOICE_CONFIG;
   }, []);
 
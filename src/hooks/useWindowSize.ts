@@ -6,7 +6,7 @@ import {
   setWindowSize,
   getWindowPosition,
   setWindowPosition,
-  saveConfig,
+  patchConfig,
   listen,
   getAvailableMonitors,
   getPrimaryMonitor,
@@ -40,10 +40,8 @@ async function rescueOverlayIfOffScreen() {
   const target = primary ? monitorToBounds(primary) : bounds[0];
   const safe = clampOntoMonitor(rect, target);
   await setWindowPosition(safe.x, safe.y);
-  // Read the latest config from the store (avoids stale-closure deps).
-  const store = useConfigStore.getState();
-  store.updateConfig({ window_position: safe });
-  await saveConfig({ ...store.config, window_position: safe });
+  useConfigStore.getState().updateConfig({ window_position: safe });
+  await patchConfig({ window_position: safe });
 }
 
 export function useWindowSize() {
@@ -131,18 +129,9 @@ export function useWindowSize() {
           const size = await getWindowSize();
           const pos = await getWindowPosition();
 
-          const newConfig = {
-            ...config,
-            window_size: size,
-            window_position: pos
-          };
-
-          updateConfig({
-            window_size: size,
-            window_position: pos
-          });
-
-          await saveConfig(newConfig);
+          const placement = { window_size: size, window_position: pos };
+          updateConfig(placement);
+          await patchConfig(placement);
         } catch (error) {
           console.error("Failed to save window state:", error);
         }
@@ -159,5 +148,5 @@ export function useWindowSize() {
       unlistenResize.then(u => u());
       unlistenMove.then(u => u());
     };
-  }, [config, updateConfig, isLoading]);
+  }, [updateConfig, isLoading]);
 }
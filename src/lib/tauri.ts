@@ -14,7 +14,38 @@ import {
 export type { Monitor };
 import { open as tauriOpen, save as tauriSave } from "@tauri-apps/plugin-dialog";
 import { openPath } from "@tauri-apps/plugin-opener";
-import type { AppConfig, BuildOrder, WindowPosition, WindowSize } from "@/types";
+import type {
+  AppConfig,
+  BuildOrder,
+  BuildOrderStats,
+  BuildRecommendation,
+  BuildSettingsOverrides,
+  BundleImportOptions,
+  BundleImportReport,
+  BundleManifest,
+  BundlePreview,
+  CheckpointIssue,
+  ConfigChange,
+  ConfigPatch,
+  ConfigImportPreview,
+  ConfigLoadReport,
+  ConfigProfile,
+  DataDirInfo,
+  EconomyReport,
+  GamePlan,
+  GamePlanPosition,
+  GamePlanStage,
+  GamePlanStore,
+  GameDataDiff,
+  GameEntityCategory,
+  GameEntityEntry,
+  ProfileStore,
+  RecommendationQuery,
+  StepResourceSuggestion,
+  UserBuildMetadata,
+  WindowPosition,
+  WindowSize,
+} from "@/types";
 import { DEFAULT_CONFIG } from "@/types";
 
 // Type for window with Tauri internals
@@ -65,6 +96,7 @@ export const BUILD_ORDERS_CHANGED_EVENT = "build-orders-changed";
 export const CONFIG_CHANGED_EVENT = "config-changed";
 // Emitted by the Rust foreground watcher when the game gains/loses focus
 export const GAME_FOCUS_CHANGED_EVENT = "game-focus-changed";
+export const GAME_PLANS_CHANGED_EVENT = "game-plans-changed";
 
 export interface GameFocusPayload {
   focused: boolean;
@@ -208,9 +240,110 @@ export async function getConfig(): Promise<AppConfig> {
   return invoke<AppConfig>("get_config");
 }
 
+export async function getConfigDiagnostics(): Promise<ConfigLoadReport> {
+  if (IS_MOCK) return { status: "clean", recoveredFields: [], adjustedFields: [] };
+  return invoke<ConfigLoadReport>("get_config_diagnostics");
+}
+
+/** Config with the active profile and `buildId`'s settings overrides applied. */
+export async function getEffectiveConfig(buildId: string | null): Promise<AppConfig> {
+  if (IS_MOCK) return getConfig();
+  return invoke<AppConfig>("get_effective_config", { buildId });
+}
+
+// Config presets. `sections` are top-level config keys such as "hotkeys",
+// "voice" or "reminders"; omit them for everything. Window placement and the
+// OCR capture region never leave or enter a machine.
+export async function exportConfig(path: string, sections?: string[]): Promise<string[]> {
+  if (IS_MOCK) return [];
+  return invoke<string[]>("export_config", { path, sections: sections ?? null });
+}
+
+export async function previewConfigImport(
+  path: string,
+  sections?: string[]
+): Promise<ConfigImportPreview | null> {
+  if (IS_MOCK) return null;
+  return invoke<ConfigImportPreview>("preview_config_import", { path, sections: sections ?? null });
+}
+
+export async function importConfig(
+  path: string,
+  sections?: string[]
+): Promise<ConfigImportPreview | null> {
+  if (IS_MOCK) return null;
+  return invoke<ConfigImportPreview>("import_config", { path, sections: sections ?? null });
+}
+
+// Data folder
+export async function getDataDir(): Promise<DataDirInfo> {
+  if (IS_MOCK) {
+    return { path: "mock", source: "default", defaultPath: "mock", relocatable: false };
+  }
+  return invoke<DataDirInfo>("get_data_dir");
+}
+
+/**
+ * Copies all data to `target` (an empty or new folder, or the default one)
+ * and switches to it. The old folder is left in place as a backup; moving back
+ * to the default folder renames what was left there aside first.
+ */
+export async function relocateDataDir(target: string): Promise<DataDirInfo> {
+  if (IS_MOCK) throw new Error("Relocating data is not available in mock mode");
+  return invoke<DataDirInfo>("relocate_data_dir", { target });
+}
+
+// Config profiles
+export async function getProfiles(): Promise<ProfileStore> {
+  if (IS_MOCK) return { profiles: [], activeProfileId: null };
+  return invoke<ProfileStore>("get_profiles");
+}
+
+/** Saves the current settings as a profile; pass `fields` for a partial one. */
+export async function createProfile(name: string, fields?: string[]): Promise<ConfigProfile> {
+  if (IS_MOCK) return { id: name.toLowerCase(), name, overlay: {} };
+  return invoke<ConfigProfile>("create_profile", { name, fields: fields ?? null });
+}
+
+export async function duplicateProfile(id: string, name: string): Promise<ConfigProfile> {
+  if (IS_MOCK) return { id: `${id}-2`, name, overlay: {} };
+  return invoke<ConfigProfile>("duplicate_profile", { id, name });
+}
+
+export async function renameProfile(id: string, name: string): Promise<void> {
+  if (IS_MOCK) return;
+  return invoke("rename_profile", { id, name });
+}
+
+export async function deleteProfile(id: string): Promise<void> {
+  if (IS_MOCK) return;
+  return invoke("delete_profile", { id });
+}
+
+/** Layers a profile on the global config; null returns to global settings. */
+export async function activateProfile(id: string | null): Promise<void> {
+  if (IS_MOCK) return;
+  return invoke("activate_profile", { id });
+}
+
+export async function cycleProfile(): Promise<string | null> {
+  if (IS_MOCK) return null;
+  return invoke<string | null>("cycle_profile");
+}
+
+export async function getConfigRevision(): Promise<number | null> {
+  if (IS_MOCK) return null;
+  return invoke<number>("get_config_revision");
+}
+
+/**
+ * Replaces the whole config. Prefer `patchConfig` for edits so settings
+ * changed meanwhile by another window are not overwritten.
+ */
 export async function saveConfig(config: AppConfig): Promise<void> {
   // Always emit immediately so other windows update UI instantly
-  await emit(CONFIG_CHANGED_EVENT, config);
+  const change: ConfigChange = { revision: null, changedPaths: [], config };
+  await emit(CONFIG_CHANGED_EVENT, change);
 
   if (IS_MOCK) {
     localStorage.setItem(MOCK_CONFIG_KEY, JSON.stringify(config));
@@ -236,6 +369,28 @@ export async function saveConfig(config: AppConfig): Promise<void> {
   });
 }
 
+/**
+ * Applies a JSON merge patch to the backend config, leaving settings it does
+ * not mention as they are, even if another window changed them meanwhile.
+ * With `expectedRevision`, fails if the config changed since that revision.
+ */
+export async function patchConfig(
+  patch: ConfigPatch,
+  expectedRevision?: number | null
+): Promise<ConfigChange> {
+  if (IS_MOCK) {
+    const config = { ...(await getConfig()), ...patch } as AppConfig;
+    localStorage.setItem(MOCK_CONFIG_KEY, JSON.stringify(config));
+    const change: ConfigChange = { revision: null, changedPaths: Object.keys(patch), config };
+    await emit(CONFIG_CHANGED_EVENT, change);
+    return change;
+  }
+  return invoke<ConfigChange>("patch_config", {
+    patch,
+    expectedRevision: expectedRevision ?? null,
+  });
+}
+
 export async function reloadHotkeys(): Promise<void> {
   if (IS_MOCK) return Promise.resolve();
   return invoke("reload_hotkeys");
@@ -317,6 +472,221 @@ export async function exportBuildOrder(order: BuildOrder, path: string): Promise
   return invoke("export_build_order", { order, path });
 }
 
+// Bundles: export/import a whole library (builds + collections) as one archive
+export async function exportBundle(
+  path: string,
+  buildIds?: string[],
+  includeCollections = true
+): Promise<BundleManifest | null> {
+  if (IS_MOCK) return null;
+  return invoke<BundleManifest>("export_bundle", { path, buildIds, includeCollections });
+}
+
+export async function previewBundle(path: string): Promise<BundlePreview | null> {
+  if (IS_MOCK) return null;
+  return invoke<BundlePreview>("preview_bundle", { path });
+}
+
+export async function importBundle(path: string, options?: BundleImportOptions): Promise<BundleImportReport | null> {
+  if (IS_MOCK) return null;
+  const report = await invoke<BundleImportReport>("import_bundle", { path, options });
+  await emit(BUILD_ORDERS_CHANGED_EVENT);
+  return report;
+}
+
+// Build ids the cycle hotkey walks: the active collection, or the whole library
+// when no collection is active. An empty list means "use every enabled build".
+export async function getCycleBuildOrderIds(): Promise<string[]> {
+  if (IS_MOCK) return [];
+  return invoke<string[]>("get_cycle_build_order_ids");
+}
+
+// Game plans chain builds; with one active, next/previous step cross between them
+export async function getGamePlans(): Promise<GamePlanStore> {
+  if (IS_MOCK) return { plans: [], activePlanId: null };
+  return invoke<GamePlanStore>("get_game_plans");
+}
+
+export async function createGamePlan(name: string, civilization: string): Promise<GamePlan> {
+  if (IS_MOCK) return { id: "mock-plan", name, civilization, stages: [] };
+  return invoke<GamePlan>("create_game_plan", { name, civilization });
+}
+
+export async function renameGamePlan(id: string, name: string): Promise<void> {
+  if (IS_MOCK) return;
+  return invoke("rename_game_plan", { id, name });
+}
+
+export async function deleteGamePlan(id: string): Promise<void> {
+  if (IS_MOCK) return;
+  return invoke("delete_game_plan", { id });
+}
+
+export async function setGamePlanStages(id: string, stages: GamePlanStage[]): Promise<void> {
+  if (IS_MOCK) return;
+  return invoke("set_game_plan_stages", { id, stages });
+}
+
+export async function validateGamePlan(id: string): Promise<string[]> {
+  if (IS_MOCK) return [];
+  return invoke<string[]>("validate_game_plan", { id });
+}
+
+export async function setActiveGamePlan(id: string | null): Promise<void> {
+  if (IS_MOCK) return;
+  return invoke("set_active_game_plan", { id });
+}
+
+// Where a step press lands when it leaves the current build; null = stay in it
+export async function navigateGamePlan(
+  buildOrderId: string,
+  stepIndex: number,
+  activeBranchId: string | null,
+  direction: "next" | "previous",
+  elapsedSeconds: number
+): Promise<GamePlanPosition | null> {
+  if (IS_MOCK) return null;
+  return invoke<GamePlanPosition | null>("navigate_game_plan", {
+    buildOrderId,
+    stepIndex,
+    activeBranchId,
+    direction,
+    elapsedSeconds: Math.max(0, Math.floor(elapsedSeconds)),
+  });
+}
+
+// Personal build metadata (pin/favorite, notes, play history). Stored apart from
+// build files so it never ends up in shared or exported builds.
+export async function setBuildOrderFlags(
+  id: string,
+  flags: { pinned?: boolean; favorite?: boolean }
+): Promise<UserBuildMetadata | null> {
+  if (IS_MOCK) return null;
+  return invoke<UserBuildMetadata>("set_build_order_flags", { id, ...flags });
+}
+
+export async function setBuildOrderNotes(id: string, notes: string | null): Promise<UserBuildMetadata | null> {
+  if (IS_MOCK) return null;
+  return invoke<UserBuildMetadata>("set_build_order_notes", { id, notes });
+}
+
+export async function setStepAnnotation(
+  id: string,
+  stepId: string,
+  annotation: string | null
+): Promise<UserBuildMetadata | null> {
+  if (IS_MOCK) return null;
+  return invoke<UserBuildMetadata>("set_step_annotation", { id, stepId, annotation });
+}
+
+export async function addStepAttachment(
+  buildOrderId: string,
+  stepId: string,
+  sourcePath: string,
+  caption?: string
+): Promise<BuildOrder | null> {
+  if (IS_MOCK) return null;
+  return invoke<BuildOrder>("add_step_attachment", { buildOrderId, stepId, sourcePath, caption });
+}
+
+export async function removeStepAttachment(
+  buildOrderId: string,
+  stepId: string,
+  fileName: string
+): Promise<BuildOrder | null> {
+  if (IS_MOCK) return null;
+  return invoke<BuildOrder>("remove_step_attachment", { buildOrderId, stepId, fileName });
+}
+
+export async function getBuildAssetsDirPath(buildOrderId: string): Promise<string | null> {
+  if (IS_MOCK) return null;
+  return invoke<string>("get_build_assets_dir_path", { buildOrderId });
+}
+
+export async function recordBuildOrderPlayed(id: string): Promise<void> {
+  if (IS_MOCK) return;
+  await invoke("record_build_order_played", { id });
+}
+
+/** Replaces a build's settings overrides; pass `{}` to follow the config again. */
+export async function setBuildSettingsOverrides(
+  id: string,
+  overrides: BuildSettingsOverrides
+): Promise<UserBuildMetadata | null> {
+  if (IS_MOCK) return null;
+  return invoke<UserBuildMetadata>("set_build_settings_overrides", { id, overrides });
+}
+
+export async function recordBuildOrderResult(
+  id: string,
+  won: boolean,
+  opponentCivilization?: string
+): Promise<void> {
+  if (IS_MOCK) return;
+  await invoke("record_build_order_result", { id, won, opponentCivilization });
+}
+
+// Ranks the player's openers for a matchup (loading-screen pick).
+export async function recommendBuildOrders(query: RecommendationQuery): Promise<BuildRecommendation[]> {
+  if (IS_MOCK) return [];
+  return invoke<BuildRecommendation[]>("recommend_build_orders", { query });
+}
+
+export async function getBuildOrderStats(id: string): Promise<BuildOrderStats | null> {
+  if (IS_MOCK) return null;
+  return invoke<BuildOrderStats>("get_build_order_stats", { id });
+}
+
+// Takes the order itself so unsaved edits can be checked
+export async function simulateBuildOrderEconomy(order: BuildOrder): Promise<EconomyReport | null> {
+  if (IS_MOCK) return null;
+  return invoke<EconomyReport>("simulate_build_order_economy", { order });
+}
+
+// Game data lookups default to the configured asset content version
+export async function getGameDataVersions(): Promise<string[]> {
+  if (IS_MOCK) return [];
+  return invoke<string[]>("get_game_data_versions");
+}
+
+export async function getGameEntity(id: string, contentVersion?: string): Promise<GameEntityEntry | null> {
+  if (IS_MOCK) return null;
+  return invoke<GameEntityEntry>("get_game_entity", { id, contentVersion });
+}
+
+export async function listGameEntities(options?: {
+  category?: GameEntityCategory;
+  civilization?: string;
+  contentVersion?: string;
+}): Promise<GameEntityEntry[]> {
+  if (IS_MOCK) return [];
+  return invoke<GameEntityEntry[]>("list_game_entities", { ...options });
+}
+
+export async function diffGameData(fromVersion: string, toVersion?: string): Promise<GameDataDiff | null> {
+  if (IS_MOCK) return null;
+  return invoke<GameDataDiff>("diff_game_data", { fromVersion, toVersion });
+}
+
+export async function suggestStepResources(
+  order: BuildOrder,
+  includeExisting = false
+): Promise<StepResourceSuggestion[]> {
+  if (IS_MOCK) return [];
+  return invoke<StepResourceSuggestion[]>("suggest_step_resources", { order, includeExisting });
+}
+
+// Team builds: one player's steps as a playable solo build
+export async function getTeamRoleView(id: string, roleId: string): Promise<BuildOrder | null> {
+  if (IS_MOCK) return null;
+  return invoke<BuildOrder>("get_team_role_view", { id, roleId });
+}
+
+export async function checkTeamCheckpoints(order: BuildOrder): Promise<CheckpointIssue[]> {
+  if (IS_MOCK) return [];
+  return invoke<CheckpointIssue[]>("check_team_checkpoints", { order });
+}
+
 // Window commands
 export async function getWindowPosition(): Promise<WindowPosition> {
   if (IS_MOCK) return Promise.resolve({ x: 100, y: 100 });
@@ -356,8 +726,7 @@ export async function showSettings(): Promise<void> {
 // Click-through and compact mode commands
 export async function setClickThrough(enabled: boolean): Promise<void> {
   if (IS_MOCK) {
-    const config = await getConfig();
-    await saveConfig({ ...config, click_through: enabled });
+    await patchConfig({ click_through: enabled });
     return;
   }
   return invoke("set_click_through", { enabled });
@@ -367,7 +736,7 @@ export async function toggleClickThrough(): Promise<boolean> {
   if (IS_MOCK) {
     const config = await getConfig();
     const next = !config.click_through;
-    await saveConfig({ ...config, click_through: next });
+    await patchConfig({ click_through: next });
     return next;
   }
   return invoke<boolean>("toggle_click_through");
@@ -377,7 +746,7 @@ export async function toggleCompactMode(): Promise<boolean> {
   if (IS_MOCK) {
     const config = await getConfig();
     const next = !config.compact_mode;
-    await saveConfig({ ...config, compact_mode: next });
+    await patchConfig({ compact_mode: next });
     return next;
   }
   return invoke<boolean>("toggle_compact_mode");
@@ -387,13 +756,18 @@ export async function toggleCompactMode(): Promise<boolean> {
 // Persists the setting and applies it natively to the overlay window.
 export async function setContentProtection(enabled: boolean): Promise<void> {
   if (IS_MOCK) {
-    const config = await getConfig();
-    await saveConfig({ ...config, content_protection: enabled });
+    await patchConfig({ content_protection: enabled });
     return;
   }
   return invoke("set_content_protection", { enabled });
 }
 
+/** Re-docks the overlay to the configured `overlay_position` corner. */
+export async function applyOverlayPosition(): Promise<void> {
+  if (IS_MOCK) return;
+  return invoke("apply_overlay_position");
+}
+
 // Monitor queries (used to keep the overlay on a visible screen). Mock-safe.
 export async function getAvailableMonitors(): Promise<Monitor[]> {
   if (IS_MOCK) return [];
@@ -14,9 +14,10 @@ interface BuildOrderState {
   setCurrentOrderIndex: (index: number) => void;
   nextStep: () => void;
   previousStep: () => void;
-  cycleBuildOrder: () => void;
+  cycleBuildOrder: (cycleIds?: string[]) => void;
   resetSteps: () => void;
   goToStep: (index: number) => void;
+  jumpToBuildOrder: (id: string, stepIndex: number) => void;
   setActiveBranch: (branchId: string | null) => void;
 }
 
@@ -83,9 +84,15 @@ export const useBuildOrderStore = create<BuildOrderState>((set, get) => ({
     }
   },
 
-  cycleBuildOrder: () => {
+  cycleBuildOrder: (cycleIds) => {
     const { buildOrders, currentOrderIndex } = get();
-    const enabledOrders = buildOrders.filter((o) => o.enabled);
+    // Restrict to the active collection when the backend provides its ids
+    const enabledOrders =
+      cycleIds && cycleIds.length > 0
+        ? cycleIds
+            .map((id) => buildOrders.find((o) => o.id === id && o.enabled))
+            .filter((o): o is BuildOrder => o !== undefined)
+        : buildOrders.filter((o) => o.enabled);
     if (enabledOrders.length === 0) return;
 
     const currentEnabledIndex = enabledOrders.findIndex(
@@ -116,6 +123,19 @@ export const useBuildOrderStore = create<BuildOrderState>((set, get) => ({
     }
   },
 
+  // Game plan hand-over: switch build and land on a step in one update
+  jumpToBuildOrder: (id, stepIndex) => {
+    const { buildOrders } = get();
+    const orderIndex = buildOrders.findIndex((o) => o.id === id);
+    if (orderIndex < 0) return;
+    const steps = buildOrders[orderIndex].steps || [];
+    set({
+      currentOrderIndex: orderIndex,
+      currentStepIndex: Math.min(Math.max(stepIndex, 0), Math.max(steps.length - 1, 0)),
+      activeBranchId: null,
+    });
+  },
+
   setActiveBranch: (branchId) => {
     const { buildOrders, currentOrderIndex, currentStepIndex } = get();
     const currentOrder = buildOrders[currentOrderIndex];
@@ -91,6 +91,24 @@ describe("configStore", () => {
 
       expect(result.current.config.hotkeys.toggle_overlay).toBe("Escape");
     });
+
+    it("seeds the effective config on first load only", () => {
+      const { result } = renderHook(() => useConfigStore());
+      const resolved: AppConfig = { ...DEFAULT_CONFIG, overlay_opacity: 0.3 };
+
+      act(() => {
+        useConfigStore.setState({ isLoading: true });
+        result.current.setConfig(DEFAULT_CONFIG);
+      });
+      expect(result.current.effectiveConfig).toEqual(DEFAULT_CONFIG);
+
+      act(() => {
+        result.current.setEffectiveConfig(resolved);
+        result.current.setConfig({ ...DEFAULT_CONFIG, theme: "light" });
+      });
+      expect(result.current.config.theme).toBe("light");
+      expect(result.current.effectiveConfig).toBe(resolved);
+    });
   });
 
   describe("updateConfig", () => {
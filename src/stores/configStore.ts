@@ -6,12 +6,18 @@ type SaveStatus = 'idle' | 'saving' | 'saved' | 'error';
 
 interface ConfigState {
   config: AppConfig;
+  /** `config` with the current build's settings overrides; what the overlay runs with. */
+  effectiveConfig: AppConfig;
+  /** Last backend config revision seen; null until the first change event. */
+  revision: number | null;
   isLoading: boolean;
   saveStatus: SaveStatus;
   saveError: string | null;
 
   // Actions
   setConfig: (config: AppConfig) => void;
+  setEffectiveConfig: (config: AppConfig) => void;
+  setRevision: (revision: number) => void;
   updateConfig: (updates: Partial<AppConfig>) => void;
   setOpacity: (opacity: number) => void;
   setFontSize: (size: FontSize) => void;
@@ -21,15 +27,25 @@ interface ConfigState {
 
 export const useConfigStore = create<ConfigState>((set) => ({
   config: DEFAULT_CONFIG,
+  effectiveConfig: DEFAULT_CONFIG,
+  revision: null,
   isLoading: true,
   saveStatus: 'idle',
   saveError: null,
 
+  // Until the first resolve arrives the plain config stands in for the
+  // effective one; later changes keep the last resolved config, overrides
+  // included, until useEffectiveConfigSync replaces it.
   setConfig: (config) =>
-    set({
+    set((state) => ({
       config,
+      effectiveConfig: state.isLoading ? config : state.effectiveConfig,
       isLoading: false,
-    }),
+    })),
+
+  setEffectiveConfig: (effectiveConfig) => set({ effectiveConfig }),
+
+  setRevision: (revision) => set({ revision }),
 
   updateConfig: (updates) =>
     set((state) => ({
@@ -72,6 +88,9 @@ export const useTheme = () => useConfigStore((state) => state.config.theme);
 export const useHotkeys = () =>
   useConfigStore(useShallow((state) => state.config.hotkeys));
 
+export const useEffectiveConfig = () =>
+  useConfigStore((state) => state.effectiveConfig);
+
 export const useSaveStatus = () =>
   useConfigStore((state) => state.saveStatus);
 
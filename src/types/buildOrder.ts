@@ -1,3 +1,5 @@
+import type { AutoAdvanceConfig, ReminderConfig, UpgradeBadgesConfig, VoiceConfig } from "./config";
+
 export interface Resources {
   food?: number;
   wood?: number;
@@ -12,6 +14,22 @@ export interface BuildOrderStep {
   description: string;
   timing?: string;
   resources?: Resources;
+  speech?: string; // Spoken instead of description by voice coaching
+  attachments?: StepAttachment[];
+  segments?: DescriptionSegment[]; // Filled in by the backend when serving the library
+  role?: string; // Team builds: TeamRole id that plays this step; unset = everyone
+  checkpoint?: string; // Team builds: coordination point every role reaches together
+}
+
+// Step description split into text and canonical icons ([icon:<id>])
+export type DescriptionSegment =
+  | { type: "text"; text: string }
+  | { type: "icon"; id: string; known: boolean };
+
+// Image stored in the build's assets folder (see getBuildAssetsDirPath)
+export interface StepAttachment {
+  fileName: string;
+  caption?: string | null;
 }
 
 export type BuildOrderSourceType =
@@ -48,10 +66,216 @@ export interface BuildOrder {
   enabled: boolean;
   pinned?: boolean;
   favorite?: boolean;
+  tags?: string[];
+  /** Opponent civilizations this opener targets; omitted means any. */
+  opponentCivilizations?: string[];
+  /** Opponent strategies this opener answers, e.g. "tower rush". */
+  opponentStrategies?: string[];
   branches?: BuildOrderBranch[];
   source?: BuildOrderSourceMetadata;
   contentVersion?: string;
   warnings?: string[];
+  /** Personal data merged in by the backend; never part of shared build files. */
+  userMetadata?: UserBuildMetadata;
+  /** Derived by the backend from the steps; `difficulty` stays the author's label. */
+  difficultyEstimate?: DifficultyEstimate;
+  /** Player roles of a team build; unset for solo builds. */
+  roles?: TeamRole[];
+}
+
+export interface TeamRole {
+  id: string;
+  name: string;
+  description?: string | null;
+}
+
+// Reported by check_team_checkpoints
+export interface CheckpointIssue {
+  checkpoint: string;
+  kind: "missingRole" | "duplicate" | "untimed" | "timingMismatch";
+  roles: string[];
+  message: string;
+}
+
+export interface DifficultyEstimate {
+  level: Difficulty;
+  score: number; // 0 (trivial) to 3 (hardest)
+  reasons: string[];
+  labelLevel?: Difficulty | null;
+  disagreesWithLabel: boolean;
+}
+
+export interface UserBuildMetadata {
+  pinned: boolean;
+  favorite: boolean;
+  notes?: string;
+  /** step id -> personal annotation */
+  stepAnnotations: Record<string, string>;
+  /** Unix epoch milliseconds */
+  lastPlayedAt?: number;
+  playCount: number;
+  record: MatchRecord;
+  /** Results keyed by opponent civilization */
+  matchupRecords: Record<string, MatchRecord>;
+  /** Config sections this build uses instead of the user's own */
+  settingsOverrides?: BuildSettingsOverrides;
+}
+
+/** Whole config sections replaced while this build runs. */
+export interface BuildSettingsOverrides {
+  voice?: VoiceConfig;
+  reminders?: ReminderConfig;
+  autoAdvance?: AutoAdvanceConfig;
+  upgradeBadges?: UpgradeBadgesConfig;
+}
+
+export interface MatchRecord {
+  wins: number;
+  losses: number;
+}
+
+export interface RecommendationQuery {
+  civilization: string;
+  opponentCivilization?: string;
+  opponentStrategy?: string;
+  difficulty?: string;
+  limit?: number;
+}
+
+export interface BuildRecommendation {
+  order: BuildOrder;
+  score: number;
+  reasons: string[];
+}
+
+// Derived by the backend (get_build_order_stats). Resource figures are
+// villager counts, matching step `resources`.
+export interface AgeUp {
+  age: number; // 2 = Feudal, 3 = Castle, 4 = Imperial
+  name: string;
+  stepId: string;
+  stepIndex: number;
+  timingSeconds: number | null;
+  villagers: number | null;
+}
+
+export interface BuildOrderStats {
+  stepCount: number;
+  timedStepCount: number;
+  durationSeconds: number | null;
+  stepsPerMinute: number | null;
+  ageUps: AgeUp[];
+  /** Most villagers on each category at any step (counts, not resources spent). */
+  peakVillagers: { food: number; wood: number; gold: number; stone: number; builders: number };
+  maxVillagers: number | null;
+  branchCount: number;
+  branches: { id: string; name: string; startStepIndex: number; depth: number; ageUps: AgeUp[] }[];
+  longestGap: { fromStepId: string; toStepId: string; seconds: number } | null;
+}
+
+export interface ResourceAmounts {
+  food: number;
+  wood: number;
+  gold: number;
+  stone: number;
+}
+
+// Economy projection from simulate_build_order_economy
+export interface EconomySnapshot {
+  stepId: string;
+  stepIndex: number;
+  timingSeconds: number;
+  villagers: number;
+  incomePerMinute: ResourceAmounts;
+  bank: ResourceAmounts; // Before the step's own spending
+}
+
+export interface FeasibilityIssue {
+  stepId: string;
+  stepIndex: number;
+  kind: "infeasible" | "slack";
+  message: string;
+}
+
+// Bundled unit/building/technology data, per content version
+export type GameEntityCategory = "unit" | "building" | "technology";
+
+export interface GameEntity {
+  id: string;
+  name: string;
+  age: number; // 1 (Dark) to 4 (Imperial)
+  cost: ResourceAmounts;
+  buildSeconds: number;
+  civilizations: string[] | null; // null = every civilization
+  aliases?: string[];
+  civilizationNames?: Record<string, string>;
+}
+
+// Proposed by suggest_step_resources; never applied by the backend
+export interface StepResourceSuggestion {
+  stepId: string;
+  mentions: { id: string; category: GameEntityCategory; name: string; quantity: number; counted: boolean }[];
+  resources: Resources | null; // Proposed step.resources: villagers the text assigns
+  cost: ResourceAmounts; // Spending of the counted mentions; not villager counts for step.resources
+  icons: string[]; // Usable as [icon:<key>]
+}
+
+export interface GameEntityEntry {
+  contentVersion: string;
+  category: GameEntityCategory;
+  entity: GameEntity;
+}
+
+export interface GameDataDiff {
+  fromVersion: string;
+  toVersion: string;
+  added: GameEntity[];
+  removed: GameEntity[];
+  changed: {
+    id: string;
+    category: GameEntityCategory;
+    fields: string[];
+    before: GameEntity;
+    after: GameEntity;
+  }[];
+}
+
+export interface EconomyReport {
+  contentVersion: string;
+  timeline: EconomySnapshot[];
+  issues: FeasibilityIssue[];
+}
+
+// Game plans: builds chained opening -> follow-up -> late game, one civilization
+export type TransitionCondition =
+  | { type: "endOfBuild" }
+  | { type: "atStep"; stepId: string }
+  | { type: "atTime"; seconds: number }; // First next-step press once the match timer reaches it
+
+export interface GamePlanStage {
+  buildOrderId: string;
+  transition: TransitionCondition;
+  note?: string | null;
+}
+
+export interface GamePlan {
+  id: string;
+  name: string;
+  civilization: string;
+  stages: GamePlanStage[];
+}
+
+export interface GamePlanStore {
+  plans: GamePlan[];
+  activePlanId: string | null;
+}
+
+export interface GamePlanPosition {
+  planId: string;
+  stageIndex: number;
+  buildOrderId: string;
+  stepIndex: number;
+  note: string | null;
 }
 
 export type Civilization =
@@ -115,3 +339,44 @@ export const CIVILIZATIONS: Civilization[] = [
 ];
 
 export const DIFFICULTIES: Difficulty[] = ["Beginner", "Intermediate", "Advanced", "Expert"];
+
+// Build order bundles (.aoe4bundle): a shareable archive of builds + collections
+export const BUNDLE_EXTENSION = "aoe4bundle";
+
+export type ConflictResolution = "skip" | "replace" | "keepBoth";
+export type BundleEntryStatus = "new" | "identical" | "conflict" | "invalid";
+
+export interface BundleManifest {
+  formatVersion: number;
+  appVersion: string;
+  contentVersion?: string | null;
+  createdAt: number;
+  builds: { id: string; name: string; path: string; sha256: string }[];
+  collections?: { path: string; sha256: string } | null;
+  assets?: { buildId: string; fileName: string; path: string; sha256: string }[];
+}
+
+export interface BundlePreview {
+  formatVersion: number;
+  appVersion: string;
+  contentVersion?: string | null;
+  contentVersionMismatch: boolean;
+  builds: { id: string; name: string; status: BundleEntryStatus; error?: string | null }[];
+  collections: string[];
+}
+
+export interface BundleImportOptions {
+  defaultResolution?: ConflictResolution;
+  /** Per-build overrides keyed by the id inside the bundle */
+  resolutions?: Record<string, ConflictResolution>;
+  includeCollections?: boolean;
+}
+
+export interface BundleImportReport {
+  imported: string[];
+  replaced: string[];
+  renamed: { from: string; to: string }[];
+  skipped: string[];
+  failed: { id: string; error: string }[];
+  collections: string[];
+}
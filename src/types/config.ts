@@ -13,6 +13,7 @@ export interface HotkeyConfig {
   activate_branch_3: string;
   activate_branch_4: string;
   toggle_counters: string;
+  cycle_profile: string;
 }
 
 export interface WindowPosition {
@@ -36,6 +37,13 @@ export interface VoiceConfig {
   speakSteps: boolean; // Read step descriptions aloud
   speakReminders: boolean; // Speak periodic reminders
   speakDelta: boolean; // Announce when behind pace
+  pronunciations?: PronunciationEntry[]; // Abbreviation expansions applied by the backend TTS
+}
+
+export interface PronunciationEntry {
+  term: string; // As written, e.g. "vils" or "TC"
+  spoken: string; // As spoken, e.g. "villagers"
+  caseSensitive?: boolean;
 }
 
 export interface ReminderItemConfig {
@@ -145,7 +153,92 @@ export interface CoachPackConfig {
 
 export type OverlayPosition = "top-left" | "top-right" | "bottom-left" | "bottom-right" | "custom";
 
+export interface ConfigFieldIssue {
+  /** JSON path of the field, e.g. "voice.rate". */
+  field: string;
+  kind: "clamped" | "rejected";
+  message: string;
+}
+
+/** How config.json was read at startup. */
+export type ConfigLoadStatus = "clean" | "missing" | "recovered" | "reset";
+
+export interface ConfigLoadReport {
+  status: ConfigLoadStatus;
+  /** JSON paths (e.g. "voice.rate") reset to their defaults. */
+  recoveredFields: string[];
+  /** Copy of the damaged original, kept whenever anything was discarded. */
+  backupPath?: string | null;
+  error?: string | null;
+  /** Schema version the file was migrated from, if it was. */
+  migratedFrom?: number | null;
+  /** Values clamped or replaced because they were out of range. */
+  adjustedFields: ConfigFieldIssue[];
+}
+
+/** One setting a config import would change. */
+export interface ConfigValueChange {
+  /** JSON path, e.g. "voice.rate". */
+  path: string;
+  current: unknown;
+  incoming: unknown;
+}
+
+/** What importing a config preset would do (or did). */
+export interface ConfigImportPreview {
+  /** Top-level sections taken from the preset, e.g. "voice", "reminders". */
+  sections: string[];
+  changes: ConfigValueChange[];
+  /** Preset values clamped into range. */
+  adjustedFields: ConfigFieldIssue[];
+}
+
+/** What chose the data folder, by precedence. */
+export type DataDirSource = "environment" | "portable" | "setting" | "default";
+
+/** Folder holding config, profiles and build orders. */
+export interface DataDirInfo {
+  path: string;
+  source: DataDirSource;
+  defaultPath: string;
+  /** False when AOE4_OVERLAY_DATA_DIR or portable mode pins the folder. */
+  relocatable: boolean;
+}
+
+/** Payload of the config-changed event. */
+export interface ConfigChange {
+  /**
+   * Backend revision of `config`; null for the optimistic copy a window
+   * broadcasts before its save reaches the backend.
+   */
+  revision: number | null;
+  /** JSON paths that changed, e.g. "voice.rate". */
+  changedPaths: string[];
+  config: AppConfig;
+}
+
+/** JSON merge patch for patch_config; null clears an optional setting. */
+export type ConfigPatch = { [K in keyof AppConfig]?: AppConfig[K] | null };
+
+/**
+ * Named settings layered on the global config. `overlay` holds top-level
+ * config keys; a partial profile only lists the ones it changes.
+ */
+export interface ConfigProfile {
+  id: string;
+  name: string;
+  overlay: Partial<AppConfig>;
+}
+
+export interface ProfileStore {
+  profiles: ConfigProfile[];
+  /** Active profile, or null when only the global config applies. */
+  activeProfileId?: string | null;
+}
+
 export interface AppConfig {
+  /** Stored file layout version; set by the backend. */
+  schemaVersion?: number;
   overlay_opacity: number;
   ui_scale?: number;
   font_size: FontSize;
@@ -283,6 +376,7 @@ export const DEFAULT_CONFIG: AppConfig = {
     activate_branch_3: "Ctrl+Alt+3",
     activate_branch_4: "Ctrl+Alt+4",
     toggle_counters: "Ctrl+Alt+TAB",
+    cycle_profile: "Ctrl+Alt+F9",
   },
   click_through: false,
   compact_mode: false, // Default to expanded mode (more info visible)
@@ -16,6 +16,25 @@ export const BuildOrderStepSchema = z.object({
   description: z.string(),
   timing: z.string().optional(),
   resources: ResourcesSchema.optional(),
+  speech: z.string().optional(),
+  attachments: z
+    .array(
+      z.object({
+        fileName: z.string(),
+        caption: z.string().nullish(),
+      })
+    )
+    .optional(),
+  segments: z
+    .array(
+      z.discriminatedUnion("type", [
+        z.object({ type: z.literal("text"), text: z.string() }),
+        z.object({ type: z.literal("icon"), id: z.string(), known: z.boolean() }),
+      ])
+    )
+    .optional(),
+  role: z.string().optional(),
+  checkpoint: z.string().optional(),
 });
 
 export const BuildOrderSourceSchema = z.object({
@@ -34,6 +53,31 @@ export const BuildOrderBranchSchema = z.object({
   steps: z.array(BuildOrderStepSchema),
 });
 
+export const MatchRecordSchema = z.object({
+  wins: z.number().int().nonnegative().default(0),
+  losses: z.number().int().nonnegative().default(0),
+});
+
+export const UserBuildMetadataSchema = z.object({
+  pinned: z.boolean().default(false),
+  favorite: z.boolean().default(false),
+  notes: z.string().optional(),
+  stepAnnotations: z.record(z.string(), z.string()).default({}),
+  lastPlayedAt: z.number().optional(),
+  playCount: z.number().int().nonnegative().default(0),
+  record: MatchRecordSchema.default({ wins: 0, losses: 0 }),
+  matchupRecords: z.record(z.string(), MatchRecordSchema).default({}),
+  // Sections are validated by the backend when saved.
+  settingsOverrides: z
+    .object({
+      voice: z.any().optional(),
+      reminders: z.any().optional(),
+      autoAdvance: z.any().optional(),
+      upgradeBadges: z.any().optional(),
+    })
+    .optional(),
+});
+
 export const BuildOrderSchema = z.object({
   id: z.string(),
   name: z.string(),
@@ -44,10 +88,32 @@ export const BuildOrderSchema = z.object({
   enabled: z.boolean(),
   pinned: z.boolean().optional(),
   favorite: z.boolean().optional(),
+  tags: z.array(z.string()).optional(),
+  opponentCivilizations: z.array(z.string()).optional(),
+  opponentStrategies: z.array(z.string()).optional(),
   branches: z.array(BuildOrderBranchSchema).optional(),
   source: BuildOrderSourceSchema.optional(),
   contentVersion: z.string().optional(),
   warnings: z.array(z.string()).optional(),
+  userMetadata: UserBuildMetadataSchema.optional(),
+  difficultyEstimate: z
+    .object({
+      level: z.enum(["Beginner", "Intermediate", "Advanced", "Expert"]),
+      score: z.number(),
+      reasons: z.array(z.string()).default([]),
+      labelLevel: z.enum(["Beginner", "Intermediate", "Advanced", "Expert"]).nullish(),
+      disagreesWithLabel: z.boolean().default(false),
+    })
+    .optional(),
+  roles: z
+    .array(
+      z.object({
+        id: z.string(),
+        name: z.string(),
+        description: z.string().nullish(),
+      })
+    )
+    .optional(),
 });
 
 